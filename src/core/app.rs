@@ -1,19 +1,46 @@
 use crate::core::args::CliArgs;
-use crate::core::config::Config;
+use crate::core::config::{Config, RecordingBackend};
+use crate::core::database::Database;
 use crate::core::error::AppError;
-use crate::metrics::SystemMetrics;
+use crate::core::influx;
+use crate::core::notify::Notifier;
+use crate::core::otlp;
+use crate::core::prometheus::Exporter;
+use crate::core::recorder::{Recorder, SampleStore};
+use crate::core::replay::Replay;
+use crate::core::sink::Sink;
+use crate::core::statsd;
+use crate::metrics::alerts::{Alert, Alerts, State};
+use crate::metrics::source::{FrozenSource, LiveSource, MetricsSource, PlaybackCommand};
 use crate::ui::{Ui, UiMode};
-use crossterm::event::{Event, KeyCode};
+use crate::ui::alerts::Alarm;
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
 use ratatui::Frame;
-use std::time::Instant;
+use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
 
-const DEBOUNCE_DELAY: std::time::Duration = std::time::Duration::from_millis(200);
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(200);
+
+/// Share of the visible span one arrow press moves the cursor; Shift moves it further.
+const CURSOR_STEP: f64 = 0.02;
+const CURSOR_STEP_FAST: f64 = 0.1;
 
 pub struct App {
+    /// Kept so CLI overrides survive a config reload
+    args: CliArgs,
     config: Config,
-    system: SystemMetrics,
+    source: Box<dyn MetricsSource>,
+    /// What the screen shows while paused; `source` keeps collecting
+    frozen: Option<FrozenSource>,
+    recorder: Option<Box<dyn SampleStore>>,
+    exporter: Option<Exporter>,
+    /// InfluxDB, StatsD and OTLP outputs
+    sinks: Vec<Sink>,
+    alerts: Alerts,
+    /// Runs alert actions; `None` without any, and during replay
+    notifier: Option<Notifier>,
     ui: Ui,
-    last_update: Instant,
     should_quit: bool,
     stats_refreshed: bool,
     last_key: Option<KeyCode>,
@@ -24,35 +51,42 @@ impl App {
     /// Create a new application instance
     pub fn new(args: &CliArgs) -> Result<Self, AppError> {
         let config = Config::load().unwrap_or_default();
-        let mut system = SystemMetrics::new();
+        let mut source: Box<dyn MetricsSource> = match &args.replay {
+            Some(path) => Box::new(Replay::open(path)?),
+            None => Box::new(LiveSource::new()),
+        };
         let mut ui = Ui::new();
+        Self::apply_settings(&config, args, &mut ui);
 
-        Self::apply_config_to_ui_inner(&config, &mut ui);
-
-        // Map refresh_rate ms to index in update_interval_presets
-        let idx = ui.update_interval_presets
-            .iter()
-            .position(|d| d.as_millis() as u64 == config.refresh_rate)
-            .unwrap_or(1);
-        ui.selected_update_interval_idx = idx;
-
-        // Apply CLI args after config so --interval can override the preset index.
-        Self::apply_args_to_ui(args, &mut ui);
-
-        // Size history buffers using the final (possibly CLI-overridden) interval.
-        system.resize_history(ui.update_interval_presets[ui.selected_update_interval_idx]);
+        // Size history buffers using the final (possibly CLI-overridden) interval and window.
+        source.resize_history(
+            ui.update_interval_presets[ui.selected_update_interval_idx],
+            ui.history_window_presets[ui.selected_history_window_idx],
+        );
 
         // Find network interface index
         if let Some(ref iface) = config.selected_network_interface {
-            let interfaces = system.network().interface_names();
+            let interfaces = source.metrics().network().interface_names();
             ui.selected_interface = interfaces.iter().position(|n| n == iface).unwrap_or(0);
         }
 
+        let recorder = Self::start_recorder(&config, args, source.as_ref())?;
+        let exporter = Self::start_exporter(&config, args, source.as_ref())?;
+        let sinks = Self::start_sinks(&config, args, source.as_ref())?;
+        let alerts = Alerts::new(&config.alerts).map_err(AppError::Config)?;
+        let notifier = Self::start_notifier(&config, args, source.as_ref())?;
+
         Ok(Self {
+            args: args.clone(),
             config,
-            system,
+            source,
+            frozen: None,
+            recorder,
+            exporter,
+            sinks,
+            alerts,
+            notifier,
             ui,
-            last_update: Instant::now(),
             should_quit: false,
             stats_refreshed: false,
             last_key: None,
@@ -60,8 +94,141 @@ impl App {
         })
     }
 
+    /// Re-read the config file, e.g. on SIGHUP in headless mode. CLI overrides
+    /// still win; outputs restart only if their settings changed. Replacements
+    /// are started before anything is stopped, so on error everything keeps
+    /// running as it was.
+    pub fn reload_config(&mut self) -> Result<(), AppError> {
+        let config = Config::load_strict()?;
+        let (args, source) = (&self.args, self.source.as_ref());
+
+        let recorder = (config.recording != self.config.recording)
+            .then(|| Self::start_recorder(&config, args, source))
+            .transpose()?;
+        let sinks = (config.influx != self.config.influx || config.statsd != self.config.statsd || config.otlp != self.config.otlp)
+            .then(|| Self::start_sinks(&config, args, source))
+            .transpose()?;
+        let rules_changed = config.alerts != self.config.alerts;
+        let alerts = rules_changed
+            .then(|| Alerts::new(&config.alerts).map_err(AppError::Config))
+            .transpose()?;
+        let notifier = (rules_changed || config.notifications != self.config.notifications)
+            .then(|| Self::start_notifier(&config, args, source))
+            .transpose()?;
+        // Last, as it may have to stop the running exporter to take over its address.
+        if config.prometheus != self.config.prometheus {
+            self.exporter = self.restart_exporter(&config)?;
+        }
+
+        // Dropping the old sinks flushes what they still hold, and the old
+        // notifier lets queued actions finish.
+        if let Some(recorder) = recorder {
+            self.recorder = recorder;
+        }
+        if let Some(sinks) = sinks {
+            self.sinks = sinks;
+        }
+        if let Some(alerts) = alerts {
+            self.alerts = alerts;
+        }
+        if let Some(mut notifier) = notifier {
+            // Same rules, so the alerts keep their states: keep what was announced too.
+            if !rules_changed && let (Some(new), Some(old)) = (&mut notifier, &self.notifier) {
+                new.carry_over(old);
+            }
+            self.notifier = notifier;
+        }
+        Self::apply_settings(&config, &self.args, &mut self.ui);
+        self.source.resize_history(
+            self.ui.update_interval_presets[self.ui.selected_update_interval_idx],
+            self.ui.history_window_presets[self.ui.selected_history_window_idx],
+        );
+        self.config = config;
+        Ok(())
+    }
+
+    /// The exporter `config` asks for. If it can't start because the running
+    /// one holds the same port, that one is stopped first, and brought back
+    /// should the new one still fail.
+    fn restart_exporter(&mut self, config: &Config) -> Result<Option<Exporter>, AppError> {
+        let port = config.prometheus.bind.parse::<SocketAddr>().ok().map(|addr| addr.port());
+        match Self::start_exporter(config, &self.args, self.source.as_ref()) {
+            Err(_) if self.exporter.as_ref().is_some_and(|running| port == Some(running.addr().port())) => {
+                self.exporter = None;
+                Self::start_exporter(config, &self.args, self.source.as_ref()).inspect_err(|_| {
+                    self.exporter = Self::start_exporter(&self.config, &self.args, self.source.as_ref()).ok().flatten();
+                })
+            }
+            result => result,
+        }
+    }
+
+    fn start_recorder(config: &Config, args: &CliArgs, source: &dyn MetricsSource) -> Result<Option<Box<dyn SampleStore>>, AppError> {
+        // Never re-record a replay.
+        if args.replay.is_some() || !args.record.unwrap_or(config.recording.enabled) {
+            return Ok(None);
+        }
+        let header = source.metrics().header();
+        Ok(Some(match config.recording.backend {
+            RecordingBackend::Jsonl => Box::new(Recorder::new(&config.recording, header)?),
+            RecordingBackend::Sqlite => Box::new(Database::create(&config.recording, &header)?),
+        }))
+    }
+
+    fn start_exporter(config: &Config, args: &CliArgs, source: &dyn MetricsSource) -> Result<Option<Exporter>, AppError> {
+        // A replay is history, not this host's current state; don't serve it.
+        if args.replay.is_some() || !args.prometheus.unwrap_or(config.prometheus.enabled) {
+            return Ok(None);
+        }
+        let exporter = Exporter::start(&config.prometheus, source.metrics().header())?;
+        exporter.publish(source.metrics().sample());
+        Ok(Some(exporter))
+    }
+
+    fn start_notifier(config: &Config, args: &CliArgs, source: &dyn MetricsSource) -> Result<Option<Notifier>, AppError> {
+        if args.replay.is_some() {
+            return Ok(None);
+        }
+        Notifier::start(&config.alerts, &config.notifications, &source.metrics().header(), args.headless)
+    }
+
+    fn start_sinks(config: &Config, args: &CliArgs, source: &dyn MetricsSource) -> Result<Vec<Sink>, AppError> {
+        let mut sinks = vec![];
+        if args.replay.is_some() {
+            return Ok(sinks);
+        }
+        // Delivery failures can only be reported when no UI is drawing over stderr.
+        let header = source.metrics().header();
+        if config.influx.enabled {
+            if matches!(config.influx.target.as_str(), "stdout" | "-") && !args.headless {
+                return Err(AppError::Config("influx target stdout needs --headless; set target to a file or URL".into()));
+            }
+            sinks.push(influx::start(&config.influx, &header, args.headless)?);
+        }
+        if config.statsd.enabled {
+            sinks.push(statsd::start(&config.statsd, &header, args.headless)?);
+        }
+        if config.otlp.enabled {
+            sinks.push(otlp::start(&config.otlp, &header, args.headless)?);
+        }
+        Ok(sinks)
+    }
+
     /// Handle input events
     pub fn handle_event(&mut self, event: Event) -> Result<(), AppError> {
+        // Clicking or dragging over a chart places the cursor; hovering moves it once shown.
+        if let Event::Mouse(mouse) = event
+            && matches!(self.ui.mode, UiMode::Normal)
+        {
+            match mouse.kind {
+                MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left) => {
+                    self.ui.cursor_at(mouse.column, mouse.row, true);
+                }
+                MouseEventKind::Moved => self.ui.cursor_at(mouse.column, mouse.row, false),
+                _ => {}
+            }
+        }
+
         if let Event::Key(key_event) = event {
             let now = Instant::now();
             let key_code = key_event.code;
@@ -78,6 +245,7 @@ impl App {
 
             match self.ui.mode {
                 UiMode::Normal => match key_code {
+                    KeyCode::Esc if self.ui.cursor.is_some() => self.ui.cursor = None,
                     KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => {
                         self.should_quit = true;
                     }
@@ -88,54 +256,74 @@ impl App {
                         self.ui.compact_view = !self.ui.compact_view;
                         config_changed = true;
                     }
-                    _ => {}
+                    KeyCode::Char('s') | KeyCode::Char('S') => {
+                        self.ui.show_stats = !self.ui.show_stats;
+                        config_changed = true;
+                    }
+                    KeyCode::Char('+') | KeyCode::Char('=') => self.ui.zoom(true),
+                    KeyCode::Char('-') => self.ui.zoom(false),
+                    KeyCode::Char('p') | KeyCode::Char('P') => self.toggle_pause(),
+                    KeyCode::Char('c') | KeyCode::Char('C') => {
+                        let latest = self.shown().metrics().sampled_at();
+                        self.ui.toggle_cursor(latest);
+                    }
+                    KeyCode::Left | KeyCode::Right if self.ui.cursor.is_some() => {
+                        let latest = self.shown().metrics().sampled_at();
+                        let step = if key_event.modifiers.contains(KeyModifiers::SHIFT) { CURSOR_STEP_FAST } else { CURSOR_STEP };
+                        self.ui.move_cursor(latest, if key_code == KeyCode::Left { -step } else { step });
+                    }
+                    KeyCode::Left | KeyCode::Right => {
+                        let latest = self.shown().metrics().sampled_at();
+                        self.ui.pan(latest, key_code == KeyCode::Left);
+                    }
+                    KeyCode::Char('l') | KeyCode::Char('L') => self.ui.snap_to_live(),
+                    KeyCode::Char('a') | KeyCode::Char('A') => self.ui.alarm = None,
+                    code => {
+                        if let Some(command) = playback_command(code) {
+                            self.source.control(command);
+                        }
+                    }
                 },
                 UiMode::OptionsMenu => match key_code {
                     KeyCode::Char('o') | KeyCode::Esc => {
                         self.ui.mode = UiMode::Normal;
                     }
-                    KeyCode::Up => {
-                        if self.ui.selected_option > 0 {
-                            self.ui.selected_option -= 1;
-                        }
+                    KeyCode::Up if self.ui.selected_option > 0 => {
+                        self.ui.selected_option -= 1;
                     }
-                    KeyCode::Down => {
-                        if self.ui.selected_option + 1 < Ui::MENU_OPTION_COUNT {
-                            self.ui.selected_option += 1;
-                        }
+                    KeyCode::Down if self.ui.selected_option + 1 < Ui::MENU_OPTION_COUNT => {
+                        self.ui.selected_option += 1;
                     }
                     KeyCode::Enter | KeyCode::Left | KeyCode::Right => {
-                        if self.ui.selected_option == 0 {
-                            let presets_len = self.ui.update_interval_presets.len();
-                            if key_code == KeyCode::Enter || key_code == KeyCode::Right {
-                                self.ui.selected_update_interval_idx = (self.ui.selected_update_interval_idx + 1) % presets_len;
-                            } else if key_code == KeyCode::Left {
-                                if self.ui.selected_update_interval_idx == 0 {
-                                    self.ui.selected_update_interval_idx = presets_len - 1;
-                                } else {
-                                    self.ui.selected_update_interval_idx -= 1;
-                                }
+                        let forward = key_code != KeyCode::Left;
+                        match self.ui.selected_option {
+                            0 => {
+                                let len = self.ui.update_interval_presets.len();
+                                self.ui.selected_update_interval_idx = cycle(self.ui.selected_update_interval_idx, len, forward);
                             }
-                            config_changed = true;
-                        } else {
-                            match self.ui.selected_option {
-                                1 => self.ui.show_cpu = !self.ui.show_cpu,
-                                2 => self.ui.show_memory = !self.ui.show_memory,
-                                3 => self.ui.show_gpu = !self.ui.show_gpu,
-                                4 => self.ui.show_disk = !self.ui.show_disk,
-                                5 => self.ui.show_network = !self.ui.show_network,
-                                _ => {}
+                            1 => {
+                                let len = self.ui.history_window_presets.len();
+                                self.ui.selected_history_window_idx = cycle(self.ui.selected_history_window_idx, len, forward);
                             }
-                            config_changed = true;
+                            2 => self.ui.show_cpu = !self.ui.show_cpu,
+                            3 => self.ui.show_memory = !self.ui.show_memory,
+                            4 => self.ui.show_gpu = !self.ui.show_gpu,
+                            5 => self.ui.show_disk = !self.ui.show_disk,
+                            6 => self.ui.show_network = !self.ui.show_network,
+                            7 => self.ui.show_kernel = !self.ui.show_kernel,
+                            8 => self.ui.show_limits = !self.ui.show_limits,
+                            9 => self.ui.show_numa = !self.ui.show_numa,
+                            10 => self.ui.show_cache = !self.ui.show_cache,
+                            11 => self.ui.show_raid = !self.ui.show_raid,
+                            _ => {}
                         }
+                        config_changed = true;
                     }
-                    KeyCode::Tab => {
-                        if self.ui.show_network {
-                            let interface_count = self.system.network().interface_names().len();
-                            if interface_count > 0 {
-                                self.ui.selected_interface = (self.ui.selected_interface + 1) % interface_count;
-                                config_changed = true;
-                            }
+                    KeyCode::Tab if self.ui.show_network => {
+                        let interface_count = self.source.metrics().network().interface_names().len();
+                        if interface_count > 0 {
+                            self.ui.selected_interface = (self.ui.selected_interface + 1) % interface_count;
+                            config_changed = true;
                         }
                     }
                     _ => {}
@@ -144,12 +332,17 @@ impl App {
 
             if config_changed {
                 let sample_interval = self.ui.update_interval_presets[self.ui.selected_update_interval_idx];
-                self.system.resize_history(sample_interval);
+                let window = self.ui.history_window_presets[self.ui.selected_history_window_idx];
+                self.source.resize_history(sample_interval, window);
+                if let Some(frozen) = &mut self.frozen {
+                    frozen.resize_history(sample_interval, window);
+                }
 
                 self.config.refresh_rate = sample_interval.as_millis() as u64;
+                self.config.history_window = window.as_secs();
                 self.sync_ui_to_config();
 
-                let interfaces = self.system.network().interface_names();
+                let interfaces = self.source.metrics().network().interface_names();
                 if !interfaces.is_empty() && self.ui.selected_interface < interfaces.len() {
                     self.config.selected_network_interface = Some(interfaces[self.ui.selected_interface].clone());
                 } else {
@@ -165,21 +358,107 @@ impl App {
     /// Update application state
     pub fn update(&mut self) -> Result<(), AppError> {
         let update_interval = self.ui.update_interval_presets[self.ui.selected_update_interval_idx];
-        let now = Instant::now();
-        if now.duration_since(self.last_update) >= update_interval {
-            self.system.update()?;
-            self.last_update = now;
+        if self.source.tick(update_interval)? {
+            for failure in self.source.take_failures() {
+                self.report(failure);
+            }
+            if self.recorder.is_some() || self.exporter.is_some() || !self.sinks.is_empty() || !self.alerts.is_empty() {
+                let sample = self.source.metrics().sample();
+                for i in self.alerts.evaluate(&sample) {
+                    let alert = &self.alerts.all()[i];
+                    if self.args.headless {
+                        let value = alert.value.map_or("-".to_string(), |v| format!("{v:.1}"));
+                        eprintln!(
+                            "sysvitals: alert {} {} ({} = {value}, rule {})",
+                            alert.state.label(),
+                            alert.name,
+                            alert.metric,
+                            alert.condition(),
+                        );
+                    }
+                    if let Some(notifier) = &mut self.notifier {
+                        notifier.alert_changed(i, alert);
+                    }
+                    if alert.state == State::Firing && self.args.replay.is_none() && !self.args.headless {
+                        let bell = self.config.notifications.bell.contains(&alert.severity);
+                        let flash = self.config.notifications.flash.contains(&alert.severity);
+                        match &mut self.ui.alarm {
+                            Some(alarm) => alarm.raise(bell, flash),
+                            None if bell || flash => self.ui.alarm = Some(Alarm::new(bell, flash)),
+                            None => {}
+                        }
+                    }
+                }
+                // The alarm ends by itself once nothing that raised it is firing.
+                let notifications = &self.config.notifications;
+                let wanted = |alert: &&Alert| notifications.bell.contains(&alert.severity) || notifications.flash.contains(&alert.severity);
+                if self.ui.alarm.is_some() && !self.alerts.all().iter().filter(wanted).any(|a| a.state == State::Firing) {
+                    self.ui.alarm = None;
+                }
+                // Recording is a side output: a full disk stops it, not the app.
+                if let Some(recorder) = &mut self.recorder
+                    && let Err(e) = recorder.record(&sample)
+                {
+                    self.recorder = None;
+                    self.report(format!("recording stopped: {e}"));
+                }
+                for sink in &self.sinks {
+                    sink.send(sample.clone());
+                }
+                if let Some(exporter) = &self.exporter {
+                    exporter.publish(sample);
+                }
+            }
             self.stats_refreshed = true;
         }
+        if let Some(alarm) = &mut self.ui.alarm
+            && alarm.ring(Instant::now())
+        {
+            // BEL passes through to the terminal without disturbing the screen.
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+        }
         Ok(())
     }
 
+    /// Tell the user about a problem that doesn't stop the app: on stderr when
+    /// headless, else under the title.
+    fn report(&mut self, message: String) {
+        if self.args.headless {
+            eprintln!("sysvitals: {message}");
+        } else {
+            self.ui.notice = Some((message, Instant::now()));
+        }
+    }
+
     /// Render the UI
     pub fn draw(&mut self, frame: &mut Frame) {
-        self.ui.draw(frame, &self.system, self.stats_refreshed);
+        let shown: &dyn MetricsSource = match &self.frozen {
+            Some(frozen) => frozen,
+            None => self.source.as_ref(),
+        };
+        self.ui.draw(frame, shown, &self.alerts, self.stats_refreshed);
         self.stats_refreshed = false;
     }
 
+    /// The source on screen: the frozen copy while paused, else the live one
+    fn shown(&self) -> &dyn MetricsSource {
+        match &self.frozen {
+            Some(frozen) => frozen,
+            None => self.source.as_ref(),
+        }
+    }
+
+    /// Freeze the display on what it shows now, or resume following live data.
+    fn toggle_pause(&mut self) {
+        if self.frozen.take().is_some() {
+            self.ui.snap_to_live();
+        } else {
+            self.frozen = Some(FrozenSource::new(self.source.as_ref()));
+        }
+        self.ui.paused = self.frozen.is_some();
+    }
+
     /// Check if the application should quit
     pub fn should_quit(&self) -> bool {
         self.should_quit
@@ -187,6 +466,23 @@ impl App {
 
     // ── Config ↔ Ui sync ─────────────────────────────────────────────────────
 
+    /// Push Config, then CLI overrides, into the Ui, including the interval
+    /// and window presets.
+    fn apply_settings(config: &Config, args: &CliArgs, ui: &mut Ui) {
+        Self::apply_config_to_ui_inner(config, ui);
+
+        // Map refresh_rate ms to index in update_interval_presets
+        let idx = ui.update_interval_presets
+            .iter()
+            .position(|d| d.as_millis() as u64 == config.refresh_rate)
+            .unwrap_or(1);
+        ui.selected_update_interval_idx = idx;
+        ui.selected_history_window_idx = nearest_preset(&ui.history_window_presets, Duration::from_secs(config.history_window));
+
+        // Apply CLI args after config so --interval/--window can override the preset index.
+        Self::apply_args_to_ui(args, ui);
+    }
+
     /// Called at startup (before `self` exists) to push Config into a fresh Ui.
     fn apply_config_to_ui_inner(config: &Config, ui: &mut Ui) {
         ui.show_cpu     = config.show_cpu;
@@ -194,7 +490,14 @@ impl App {
         ui.show_gpu     = config.show_gpu;
         ui.show_network = config.show_network;
         ui.show_disk    = config.show_disk;
+        ui.show_kernel  = config.show_kernel;
+        ui.show_limits  = config.show_limits;
+        ui.show_numa    = config.show_numa;
+        ui.show_cache   = config.show_cache;
+        ui.show_raid    = config.show_raid;
         ui.compact_view = config.compact_view;
+        ui.show_stats   = config.show_stats;
+        ui.header_format = config.header_format.clone();
     }
 
     /// Apply CLI overrides to the Ui (session-only; does not mutate Config).
@@ -205,18 +508,16 @@ impl App {
         if let Some(v) = args.show_gpu     { ui.show_gpu      = v; }
         if let Some(v) = args.show_disk    { ui.show_disk     = v; }
         if let Some(v) = args.show_network { ui.show_network  = v; }
+        if let Some(v) = args.show_kernel  { ui.show_kernel   = v; }
+        if let Some(v) = args.show_limits  { ui.show_limits   = v; }
+        if let Some(v) = args.show_numa    { ui.show_numa     = v; }
+        if let Some(v) = args.show_cache   { ui.show_cache    = v; }
+        if let Some(v) = args.show_raid    { ui.show_raid     = v; }
         if let Some(ms) = args.interval_ms {
-            let target = std::time::Duration::from_millis(ms);
-            // Pick the preset whose distance from the requested value is smallest.
-            ui.selected_update_interval_idx = ui.update_interval_presets
-                .iter()
-                .enumerate()
-                .min_by_key(|(_, d)| {
-                    let diff = d.as_millis() as i64 - target.as_millis() as i64;
-                    diff.unsigned_abs()
-                })
-                .map(|(i, _)| i)
-                .unwrap_or(1);
+            ui.selected_update_interval_idx = nearest_preset(&ui.update_interval_presets, Duration::from_millis(ms));
+        }
+        if let Some(secs) = args.window_secs {
+            ui.selected_history_window_idx = nearest_preset(&ui.history_window_presets, Duration::from_secs(secs));
         }
     }
 
@@ -227,6 +528,43 @@ impl App {
         self.config.show_gpu     = self.ui.show_gpu;
         self.config.show_network = self.ui.show_network;
         self.config.show_disk    = self.ui.show_disk;
+        self.config.show_kernel  = self.ui.show_kernel;
+        self.config.show_limits  = self.ui.show_limits;
+        self.config.show_numa    = self.ui.show_numa;
+        self.config.show_cache   = self.ui.show_cache;
+        self.config.show_raid    = self.ui.show_raid;
         self.config.compact_view = self.ui.compact_view;
+        self.config.show_stats   = self.ui.show_stats;
+    }
+}
+
+/// Index of the preset whose distance from `target` is smallest.
+fn nearest_preset(presets: &[Duration], target: Duration) -> usize {
+    presets
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, d)| d.abs_diff(target))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// Step a preset index forward or back, wrapping at either end.
+fn cycle(index: usize, len: usize, forward: bool) -> usize {
+    if forward { (index + 1) % len } else { (index + len - 1) % len }
+}
+
+/// Replay keys; live sources ignore the resulting commands.
+fn playback_command(key: KeyCode) -> Option<PlaybackCommand> {
+    match key {
+        KeyCode::Char(' ') => Some(PlaybackCommand::TogglePause),
+        KeyCode::Char(']') => Some(PlaybackCommand::Faster),
+        KeyCode::Char('[') => Some(PlaybackCommand::Slower),
+        KeyCode::Char('.') => Some(PlaybackCommand::Step(1)),
+        KeyCode::Char(',') => Some(PlaybackCommand::Step(-1)),
+        KeyCode::PageDown => Some(PlaybackCommand::Seek(60)),
+        KeyCode::PageUp => Some(PlaybackCommand::Seek(-60)),
+        KeyCode::Home => Some(PlaybackCommand::Start),
+        KeyCode::End => Some(PlaybackCommand::End),
+        _ => None,
     }
 }
@@ -1,16 +1,103 @@
 //! Command-line argument parsing (no external dependencies).
 
+use crate::metrics::sample::parse_window;
+use std::path::PathBuf;
+
+/// Subcommands that print to stdout instead of running the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// One JSON document, then exit
+    Snapshot,
+    /// One record per sample until interrupted
+    Stream,
+    /// Aggregate a recorded metric from the SQLite database
+    Query,
+}
+
+/// `--format` of the printing subcommands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Jsonl,
+    Csv,
+}
+
+impl Format {
+    fn parse(val: &str) -> Option<Self> {
+        match val {
+            "json" => Some(Self::Json),
+            "jsonl" => Some(Self::Jsonl),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// `--agg` of `sysvitals query`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Min,
+    Max,
+    Avg,
+    Last,
+    Count,
+}
+
+impl Aggregate {
+    fn parse(val: &str) -> Option<Self> {
+        match val {
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "avg" => Some(Self::Avg),
+            "last" => Some(Self::Last),
+            "count" => Some(Self::Count),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Avg => "avg",
+            Self::Last => "last",
+            Self::Count => "count",
+        }
+    }
+}
+
 /// Arguments parsed from the command line.
 /// All fields are `Option` so unspecified flags leave the saved config intact.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct CliArgs {
     pub compact:    Option<bool>,
     pub interval_ms: Option<u64>,
+    pub window_secs: Option<u64>,
+    pub record: Option<bool>,
+    pub prometheus: Option<bool>,
+    /// Run without a terminal; see `core::daemon`
+    pub headless: bool,
+    pub pidfile: Option<PathBuf>,
+    /// Recording to play back instead of collecting live metrics
+    pub replay: Option<PathBuf>,
+    pub command: Option<Command>,
+    pub format: Option<Format>,
+    /// Metric name given to `query`, e.g. `cpu.usage`
+    pub metric: Option<String>,
+    pub since_secs: Option<u64>,
+    pub aggregate: Option<Aggregate>,
+    /// Database for `query`; defaults to the recording directory's
+    pub db: Option<PathBuf>,
     pub show_cpu:   Option<bool>,
     pub show_memory: Option<bool>,
     pub show_gpu:   Option<bool>,
     pub show_disk:  Option<bool>,
     pub show_network: Option<bool>,
+    pub show_kernel: Option<bool>,
+    pub show_limits: Option<bool>,
+    pub show_numa: Option<bool>,
+    pub show_cache: Option<bool>,
+    pub show_raid: Option<bool>,
 }
 
 impl CliArgs {
@@ -25,11 +112,50 @@ impl CliArgs {
                     std::process::exit(0);
                 }
                 "-c" | "--compact"     => out.compact       = Some(true),
+                "-r" | "--record"      => out.record        = Some(true),
+                "--prometheus"         => out.prometheus    = Some(true),
+                "--headless"           => out.headless      = true,
                 "--no-cpu"             => out.show_cpu      = Some(false),
                 "--no-memory"          => out.show_memory   = Some(false),
                 "--no-gpu"             => out.show_gpu      = Some(false),
                 "--no-disk"            => out.show_disk     = Some(false),
                 "--no-network"         => out.show_network  = Some(false),
+                "--no-kernel"          => out.show_kernel   = Some(false),
+                "--no-limits"          => out.show_limits   = Some(false),
+                "--no-numa"            => out.show_numa     = Some(false),
+                "--no-caches"          => out.show_cache    = Some(false),
+                "--no-raid"            => out.show_raid     = Some(false),
+                "replay" => {
+                    let path = args.next().ok_or("replay requires a recording file")?;
+                    out.replay = Some(PathBuf::from(path));
+                }
+                "snapshot" => out.command = Some(Command::Snapshot),
+                "stream" => out.command = Some(Command::Stream),
+                "query" => {
+                    let metric = args.next().ok_or("query requires a metric such as cpu.usage")?;
+                    out.command = Some(Command::Query);
+                    out.metric = Some(metric);
+                }
+                "--since" => {
+                    let val = args.next().ok_or("--since requires a duration such as 1h")?;
+                    out.since_secs = Some(parse_window(&val).ok_or_else(|| format!("invalid duration: {val}"))?);
+                }
+                "--agg" => {
+                    let val = args.next().ok_or("--agg requires min, max, avg, last or count")?;
+                    out.aggregate = Some(Aggregate::parse(&val).ok_or_else(|| format!("unknown aggregate: {val}"))?);
+                }
+                "--db" => {
+                    let path = args.next().ok_or("--db requires a path")?;
+                    out.db = Some(PathBuf::from(path));
+                }
+                "--format" => {
+                    let val = args.next().ok_or("--format requires a value")?;
+                    out.format = Some(Format::parse(&val).ok_or_else(|| format!("unknown format: {val}"))?);
+                }
+                "--pidfile" => {
+                    let path = args.next().ok_or("--pidfile requires a path")?;
+                    out.pidfile = Some(PathBuf::from(path));
+                }
                 "-i" | "--interval" => {
                     let val = args.next().ok_or("--interval requires a value in ms")?;
                     let ms: u64 = val.parse().map_err(|_| format!("invalid interval: {val}"))?;
@@ -38,9 +164,33 @@ impl CliArgs {
                     }
                     out.interval_ms = Some(ms);
                 }
+                "-w" | "--window" => {
+                    let val = args.next().ok_or("--window requires a duration such as 15m")?;
+                    out.window_secs = Some(parse_window(&val).ok_or_else(|| format!("invalid window: {val}"))?);
+                }
                 other => return Err(format!("unknown argument: {other}")),
             }
         }
+        if out.headless && out.replay.is_some() {
+            return Err("--headless cannot be used with replay".into());
+        }
+        if out.command.is_some() && (out.headless || out.replay.is_some()) {
+            return Err("snapshot, stream and query cannot be combined with --headless or replay".into());
+        }
+        if out.command != Some(Command::Query) && (out.since_secs.is_some() || out.aggregate.is_some() || out.db.is_some()) {
+            return Err("--since, --agg and --db only apply to query".into());
+        }
+        match (out.command, out.format) {
+            (None, Some(_)) => return Err("--format only applies to snapshot and stream".into()),
+            (Some(Command::Snapshot), Some(Format::Jsonl | Format::Csv)) => {
+                return Err("snapshot only supports --format json".into());
+            }
+            (Some(Command::Stream), Some(Format::Json)) => {
+                return Err("stream supports --format jsonl or csv".into());
+            }
+            (Some(Command::Query), Some(_)) => return Err("query has no --format".into()),
+            _ => {}
+        }
         Ok(out)
     }
 }
@@ -50,20 +200,73 @@ fn print_help() {
 
 USAGE:
     sysvitals [OPTIONS]
+    sysvitals replay <file> [OPTIONS]
+    sysvitals snapshot [--format json] [--interval <ms>]
+    sysvitals stream [--format jsonl|csv] [--interval <ms>] [--no-<panel>...]
+    sysvitals query <metric> [--since <time>] [--agg min|max|avg|last|count] [--db <path>]
 
 OPTIONS:
     -c, --compact          Start in compact bars view
     -i, --interval <ms>    Refresh interval in milliseconds (default: 1000, min: 100)
+    -w, --window <time>    History shown in charts: 2m, 15m, 1h or 24h (default: 2m)
+    -r, --record           Record every sample to disk for this session (see [recording] in config)
+        --prometheus       Serve /metrics for Prometheus this session (see [prometheus] in config)
+        --headless         Collect, record and export without a terminal (for running as a service)
+        --pidfile <path>   Where --headless writes its process ID
+                           (default: $XDG_RUNTIME_DIR/sysvitals/sysvitals.pid)
+        --format <fmt>     Output format of snapshot: json (default);
+                           of stream: jsonl (default) or csv
+        --since <time>     How far back query looks: 90s, 15m, 1h, 2d (default: 1h)
+        --agg <fn>         What query computes: min, max, avg (default), last or count
+        --db <path>        Database query reads (default: sysvitals.db in the recording dir)
         --no-cpu           Hide CPU panel
         --no-memory        Hide memory panel
         --no-gpu           Hide GPU panel
         --no-disk          Hide disk panel
         --no-network       Hide network panel
+        --no-kernel        Hide kernel activity panel
+        --no-limits        Hide system limits panel
+        --no-numa          Hide NUMA panel (only shown on multi-node machines)
+        --no-caches        Hide ZFS ARC / zram / bcache panel (only shown when detected)
+        --no-raid          Hide software RAID panel (only shown when md arrays exist)
     -h, --help             Print this help message
 
+SNAPSHOT:
+    Takes two samples --interval apart (default: 1000 ms) so rates are valid,
+    prints every metric as one JSON document and exits.
+
+STREAM:
+    Prints one record per --interval (default: 1000 ms) until interrupted.
+    jsonl is the recording format (a header line, then one sample per line)
+    and can be replayed; csv has one column per value of the enabled panels,
+    in a fixed order, after a header row.
+
+QUERY:
+    Reads the SQLite recording (set [recording] backend = \"sqlite\").
+    Metrics are <collector>.<value>, or <collector>.<device>.<value> for
+    network, numa, limits, cache and raid, e.g. cpu.usage,
+    memory.used_percent, network.eth0.rx, limits.<label>.used_percent,
+    cache.zram0.compression_ratio, raid.md0.missing_disks:
+        sysvitals query memory.used --since 1h --agg max
+        sysvitals query raid.md0.missing_disks --since 24h --agg max
+
 KEYS (while running):
     q / Esc    Quit
     o          Open options menu
     v          Toggle compact / chart view
-    Tab        Cycle network interface (options menu)");
+    s          Toggle chart statistics footer
+    + / -      Zoom chart history in / out
+    ← / →      Pan chart history back / forward
+    l          Snap back to live
+    p          Pause / resume the display (sampling continues)
+    c          Show / hide the cursor; ← / → move it, Esc hides it
+    a          Acknowledge an alert: stop the bell and border flash
+    Tab        Cycle network interface (options menu)
+
+REPLAY KEYS:
+    Space              Play / pause
+    [ / ]              Slower / faster (1x-64x)
+    , / .              Step one sample back / forward
+    PgUp / PgDn        Seek 1 minute back / forward
+    Home / End         Jump to start / end");
 }
@@ -1,21 +1,61 @@
 //! Application configuration
 
+use crate::metrics::alerts::{AlertRule, Severity};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{PathBuf};
 use std::fs;
 use directories::ProjectDirs;
 
+/// Missing fields take their defaults, so a hand-written file need only
+/// list what it changes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub refresh_rate: u64,
+    /// How far back charts reach, in seconds
+    #[serde(default = "default_history_window")]
+    pub history_window: u64,
     pub show_cpu: bool,
     pub show_memory: bool,
     pub show_gpu: bool,
     pub show_network: bool,
     pub show_disk: bool,
+    #[serde(default = "default_true")]
+    pub show_kernel: bool,
+    #[serde(default = "default_true")]
+    pub show_limits: bool,
+    #[serde(default = "default_true")]
+    pub show_numa: bool,
+    #[serde(default = "default_true")]
+    pub show_cache: bool,
+    #[serde(default = "default_true")]
+    pub show_raid: bool,
     #[serde(default)]
     pub compact_view: bool,
+    /// Show min/avg/p95/p99/max/stddev under each chart
+    #[serde(default)]
+    pub show_stats: bool,
     pub selected_network_interface: Option<String>,
+    /// Template for the one-line host header; see `ui::header::expand`.
+    /// An empty string falls back to a plain title.
+    #[serde(default = "default_header_format")]
+    pub header_format: String,
+    #[serde(default)]
+    pub recording: RecordingConfig,
+    #[serde(default)]
+    pub prometheus: PrometheusConfig,
+    #[serde(default)]
+    pub influx: InfluxConfig,
+    #[serde(default)]
+    pub statsd: StatsdConfig,
+    #[serde(default)]
+    pub otlp: OtlpConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    /// Threshold rules; see `metrics::alerts`
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
 
     #[serde(skip)]
     config_path: PathBuf,
@@ -27,18 +67,260 @@ impl Default for Config {
 
         Self {
             refresh_rate: 1000,
+            history_window: default_history_window(),
             show_cpu: true,
             show_memory: true,
             show_gpu: true,
             show_network: true,
             show_disk: true,
+            show_kernel: true,
+            show_limits: true,
+            show_numa: true,
+            show_cache: true,
+            show_raid: true,
             compact_view: false,
+            show_stats: false,
             selected_network_interface: None,
+            header_format: default_header_format(),
+            recording: RecordingConfig::default(),
+            prometheus: PrometheusConfig::default(),
+            influx: InfluxConfig::default(),
+            statsd: StatsdConfig::default(),
+            otlp: OtlpConfig::default(),
+            notifications: NotificationsConfig::default(),
+            alerts: vec![],
             config_path,
         }
     }
 }
 
+/// On-disk recording of every sample; see `core::recorder`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingConfig {
+    pub enabled: bool,
+    /// JSON-lines files, or one SQLite database that `sysvitals query` can read
+    pub backend: RecordingBackend,
+    /// Where recordings are written; defaults to `<data dir>/recordings`
+    pub dir: Option<PathBuf>,
+    /// Recordings older than this are deleted
+    pub retention_hours: u64,
+    /// A new file is started once the current one reaches this size (JSON lines only)
+    pub max_file_mb: u64,
+    /// Oldest files are deleted while the total exceeds this size
+    pub max_total_mb: u64,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: RecordingBackend::default(),
+            dir: None,
+            retention_hours: 72,
+            max_file_mb: 32,
+            max_total_mb: 512,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingBackend {
+    #[default]
+    Jsonl,
+    Sqlite,
+}
+
+impl RecordingConfig {
+    pub fn dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(|| {
+            ProjectDirs::from("com", "sysvitals", "sysvitals")
+                .map(|dirs| dirs.data_dir().join("recordings"))
+                .unwrap_or_else(|| PathBuf::from("sysvitals_recordings"))
+        })
+    }
+}
+
+/// How alerts get attention: rate limits and log for alert actions (see
+/// `core::notify`), and which severities ring the bell or flash the TUI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// A rule that fires again within this many seconds of its last
+    /// notification stays quiet
+    pub cooldown_secs: u64,
+    /// Notifications across all rules in any rolling hour
+    pub max_per_hour: usize,
+    /// Also notify when an alert that was announced resolves
+    pub on_resolved: bool,
+    /// Where each notification is logged; defaults to `<data dir>/notifications.log`
+    pub log: Option<PathBuf>,
+    /// Severities whose firing rings the terminal bell
+    pub bell: Vec<Severity>,
+    /// Severities whose firing flashes the outer border red
+    pub flash: Vec<Severity>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            cooldown_secs: 300,
+            max_per_hour: 30,
+            on_resolved: true,
+            log: None,
+            bell: vec![Severity::Critical],
+            flash: vec![Severity::Warning, Severity::Critical],
+        }
+    }
+}
+
+impl NotificationsConfig {
+    pub fn log(&self) -> PathBuf {
+        self.log.clone().unwrap_or_else(|| {
+            ProjectDirs::from("com", "sysvitals", "sysvitals")
+                .map(|dirs| dirs.data_dir().join("notifications.log"))
+                .unwrap_or_else(|| PathBuf::from("sysvitals_notifications.log"))
+        })
+    }
+}
+
+/// Prometheus scrape endpoint; see `core::prometheus`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrometheusConfig {
+    pub enabled: bool,
+    /// Address and port to listen on
+    pub bind: String,
+}
+
+impl Default for PrometheusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:9899".to_string(),
+        }
+    }
+}
+
+/// InfluxDB line protocol output; see `core::influx`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InfluxConfig {
+    pub enabled: bool,
+    /// `stdout` (headless only), a file path, or an `http://` write URL such as
+    /// `http://localhost:8086/api/v2/write?org=home&bucket=sysvitals`
+    pub target: String,
+    /// Sent as `Authorization: Token <token>` to HTTP targets
+    pub token: Option<String>,
+    /// Samples per write
+    pub batch_size: usize,
+    /// Further attempts after a failed write before the batch is dropped
+    pub retries: u32,
+    /// Added to every line; `host` defaults to the host name
+    pub tags: BTreeMap<String, String>,
+}
+
+impl Default for InfluxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: "stdout".to_string(),
+            token: None,
+            batch_size: 10,
+            retries: 3,
+            tags: BTreeMap::new(),
+        }
+    }
+}
+
+/// StatsD gauges over UDP; see `core::statsd`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsdConfig {
+    pub enabled: bool,
+    /// Host and port of the StatsD server
+    pub address: String,
+    /// Leads every metric name
+    pub prefix: String,
+    /// How tags are attached; plain StatsD has no tags
+    pub tag_style: TagStyle,
+    /// Samples per send; lines are packed into as few datagrams as fit
+    pub batch_size: usize,
+    /// Further attempts after a failed send before the batch is dropped
+    pub retries: u32,
+    /// Added to every metric; `host` defaults to the host name
+    pub tags: BTreeMap<String, String>,
+}
+
+impl Default for StatsdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1:8125".to_string(),
+            prefix: "sysvitals".to_string(),
+            tag_style: TagStyle::default(),
+            batch_size: 1,
+            retries: 2,
+            tags: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagStyle {
+    /// `name:1|g|#host:a` (Datadog, and most modern servers)
+    #[default]
+    DogStatsd,
+    /// `name,host=a:1|g` (Telegraf's statsd input)
+    Telegraf,
+    /// No tags
+    None,
+}
+
+/// OpenTelemetry metrics over OTLP/HTTP with JSON encoding; see `core::otlp`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OtlpConfig {
+    pub enabled: bool,
+    /// The collector's metrics URL, `http://` only
+    pub endpoint: String,
+    /// Extra request headers, e.g. an API key
+    pub headers: BTreeMap<String, String>,
+    /// Added to the resource next to `host.name`, `os.type` and `service.name`
+    pub resource_attributes: BTreeMap<String, String>,
+    /// Samples per export request
+    pub batch_size: usize,
+    /// Further attempts after a failed export before the batch is dropped
+    pub retries: u32,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://127.0.0.1:4318/v1/metrics".to_string(),
+            headers: BTreeMap::new(),
+            resource_attributes: BTreeMap::new(),
+            batch_size: 10,
+            retries: 3,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_history_window() -> u64 {
+    120
+}
+
+fn default_header_format() -> String {
+    crate::ui::header::DEFAULT_HEADER_FORMAT.to_string()
+}
+
 impl Config {
     fn default_config_path() -> PathBuf {
         if let Some(proj_dirs) = ProjectDirs::from("com", "sysvitals", "sysvitals") {
@@ -48,7 +330,17 @@ impl Config {
         }
     }
 
+    /// Read the config file, or defaults if there is none or it doesn't parse.
     pub fn load() -> Result<Self, std::io::Error> {
+        match Self::load_strict() {
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => Ok(Self::default()),
+            result => result,
+        }
+    }
+
+    /// Like `load`, but a file that exists and doesn't parse is an error, so
+    /// a reload can keep the running settings rather than reset them.
+    pub fn load_strict() -> Result<Self, std::io::Error> {
         let default = Self::default();
 
         if let Some(parent) = default.config_path.parent() {
@@ -57,7 +349,8 @@ impl Config {
 
         let config = match fs::read_to_string(&default.config_path) {
             Ok(contents) => {
-                let mut loaded: Self = toml::from_str(&contents).unwrap_or(default.clone());
+                let mut loaded: Self = toml::from_str(&contents)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
                 loaded.config_path = default.config_path.clone(); // retain path
                 loaded
             },
@@ -73,7 +366,7 @@ impl Config {
         }
 
         let toml = toml::to_string_pretty(self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            .map_err(std::io::Error::other)?;
         fs::write(&self.config_path, toml)
     }
 }
\ No newline at end of file
@@ -0,0 +1,90 @@
+//! Headless mode: collection, recording and exporting without a terminal,
+//! for running as a service. SIGTERM and SIGINT stop it cleanly, SIGHUP
+//! reloads the config file. Progress goes to stderr for the service log.
+
+use crate::core::app::App;
+use directories::ProjectDirs;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// How often the loop checks for signals and due samples.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub fn run(mut app: App, pidfile: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let signals = Signals::register()?;
+    let _pidfile = PidFile::create(pidfile)?;
+    eprintln!("sysvitals: running headless (pid {}, pidfile {})", process::id(), pidfile.display());
+
+    while !signals.terminate.load(Ordering::Relaxed) {
+        if signals.reload.swap(false, Ordering::Relaxed) {
+            match app.reload_config() {
+                Ok(()) => eprintln!("sysvitals: configuration reloaded"),
+                Err(e) => eprintln!("sysvitals: reload failed, keeping the previous settings: {e}"),
+            }
+        }
+        app.update()?;
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    eprintln!("sysvitals: shutting down");
+    Ok(())
+}
+
+/// `$XDG_RUNTIME_DIR/sysvitals/sysvitals.pid` where there is one, else the data directory.
+pub fn default_pidfile() -> PathBuf {
+    ProjectDirs::from("com", "sysvitals", "sysvitals")
+        .map(|dirs| dirs.runtime_dir().unwrap_or(dirs.data_dir()).join("sysvitals.pid"))
+        .unwrap_or_else(|| PathBuf::from("sysvitals.pid"))
+}
+
+/// Flags raised by signal handlers and polled by the main loop
+struct Signals {
+    terminate: Arc<AtomicBool>,
+    reload: Arc<AtomicBool>,
+}
+
+impl Signals {
+    #[cfg(unix)]
+    fn register() -> Result<Self, std::io::Error> {
+        use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+        use signal_hook::flag;
+
+        let signals = Self { terminate: Arc::default(), reload: Arc::default() };
+        flag::register(SIGTERM, Arc::clone(&signals.terminate))?;
+        flag::register(SIGINT, Arc::clone(&signals.terminate))?;
+        flag::register(SIGHUP, Arc::clone(&signals.reload))?;
+        Ok(signals)
+    }
+
+    /// Without Unix signals the process is simply stopped from outside.
+    #[cfg(not(unix))]
+    fn register() -> Result<Self, std::io::Error> {
+        Ok(Self { terminate: Arc::default(), reload: Arc::default() })
+    }
+}
+
+/// Holds our process ID on disk for as long as it lives
+struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    fn create(path: &Path) -> Result<Self, std::io::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, format!("{}\n", process::id()))?;
+        Ok(Self { path: path.to_path_buf() })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,390 @@
+//! SQLite recording backend and `sysvitals query`.
+//!
+//! With `[recording] backend = "sqlite"`, samples go to `sysvitals.db` in the
+//! recording directory instead of JSON-lines files: one table per collector,
+//! each keyed by `ts` (Unix milliseconds) with an index on it, and a
+//! `device` column where a collector reports several (interfaces, NUMA nodes,
+//! limits, caches, RAID arrays). Retention and the total size cap work as for JSON lines.
+//!
+//! Queries name a value as `<collector>[.<device>].<column>`, e.g.
+//! `cpu.usage`, `memory.used_percent`, `network.eth0.rx` or `raid.md0.missing_disks`.
+
+use crate::core::args::{Aggregate, CliArgs};
+use crate::core::config::{Config, RecordingConfig};
+use crate::core::error::AppError;
+use crate::core::recorder::SampleStore;
+use crate::metrics::sample::{COLLECTORS, Header, Sample, metric_name};
+use crate::ui::header::format_utc_seconds;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const FILE_NAME: &str = "sysvitals.db";
+const MB: u64 = 1024 * 1024;
+/// Pruning runs at most this often.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(600);
+/// Share of the oldest samples dropped per step while over the size cap
+const PRUNE_STEP: f64 = 0.1;
+
+/// A collector's table and the columns it stores after `ts` (and `device`,
+/// for per-device collectors). Names and units are those of `COLLECTORS`.
+struct Table {
+    name: &'static str,
+    columns: &'static [&'static str],
+}
+
+const TABLES: &[Table] = &[
+    Table { name: "cpu", columns: &["usage", "temperature", "load_1m", "load_5m", "load_15m"] },
+    Table { name: "memory", columns: &["used", "total", "swap_used", "swap_total"] },
+    Table { name: "paging", columns: &["swap_in", "swap_out", "faults", "major_faults", "scanned", "stolen", "oom_kills"] },
+    Table { name: "network", columns: &["rx", "tx"] },
+    Table { name: "disk", columns: &["read", "write"] },
+    Table { name: "gpu", columns: &["usage", "memory"] },
+    Table { name: "kernel", columns: &["context_switches", "interrupts", "forks", "procs_running", "procs_blocked"] },
+    Table { name: "host", columns: &["uptime", "processes", "threads"] },
+    Table { name: "numa", columns: &["usage", "mem_total", "mem_free", "hit_rate", "miss_rate"] },
+    Table { name: "limits", columns: &["used", "max"] },
+    // ZFS ARC as device `arc`, plus each zram and bcache device; columns a kind lacks stay NULL
+    Table { name: "cache", columns: &["hit_ratio", "size", "target", "max", "orig", "compr", "mem_used"] },
+    Table { name: "raid", columns: &["active", "expected_disks", "working_disks", "failed_disks", "sync_percent"] },
+];
+
+/// Values worked out from stored columns: table, name, SQL expression
+const DERIVED: &[(&str, &str, &str)] = &[
+    ("memory", "used_percent", "used * 100.0 / NULLIF(total, 0)"),
+    ("memory", "swap_used_percent", "swap_used * 100.0 / NULLIF(swap_total, 0)"),
+    ("numa", "mem_used", "mem_total - mem_free"),
+    ("limits", "used_percent", "used * 100.0 / NULLIF(max, 0)"),
+    ("cache", "compression_ratio", "orig * 1.0 / NULLIF(compr, 0)"),
+    ("raid", "missing_disks", "expected_disks - working_disks"),
+];
+
+pub struct Database {
+    conn: Connection,
+    retention: Duration,
+    max_total_bytes: u64,
+    last_prune: Instant,
+}
+
+impl Database {
+    /// Open (or create) the database in the configured recording directory.
+    pub fn create(config: &RecordingConfig, header: &Header) -> Result<Self, AppError> {
+        let dir = config.dir();
+        fs::create_dir_all(&dir)?;
+        let conn = open(&dir.join(FILE_NAME))?;
+        let header = serde_json::to_string(header).map_err(|e| AppError::System(format!("cannot encode header: {e}")))?;
+        conn.execute("INSERT OR REPLACE INTO meta (key, value) VALUES ('header', ?1)", [header])?;
+
+        let mut database = Self {
+            conn,
+            retention: Duration::from_secs(config.retention_hours * 3600),
+            max_total_bytes: config.max_total_mb.max(1) * MB,
+            last_prune: Instant::now(),
+        };
+        database.prune()?;
+        Ok(database)
+    }
+
+    /// Drop samples past the retention period, then the oldest ones until the
+    /// data fits under the size cap. Freed pages are reused, so the file
+    /// itself stops growing rather than shrinking.
+    fn prune(&mut self) -> Result<(), AppError> {
+        self.last_prune = Instant::now();
+        if !self.retention.is_zero() {
+            let cutoff = now_ms().saturating_sub(self.retention.as_millis() as u64);
+            self.delete_before(cutoff)?;
+        }
+        while self.used_bytes()? > self.max_total_bytes {
+            let (count, first): (u64, Option<u64>) = self.conn.query_row("SELECT COUNT(*), MIN(ts) FROM cpu", [], |r| Ok((r.get(0)?, r.get(1)?)))?;
+            let Some(first) = first else { break };
+            let skip = ((count as f64 * PRUNE_STEP) as u64).max(1);
+            let cutoff: Option<u64> = self.conn
+                .query_row("SELECT ts FROM cpu ORDER BY ts LIMIT 1 OFFSET ?1", [skip], |r| r.get(0))
+                .optional()?;
+            match cutoff {
+                Some(cutoff) if cutoff > first => self.delete_before(cutoff)?,
+                // Down to the last few samples; leave them.
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_before(&self, cutoff: u64) -> Result<(), AppError> {
+        for table in TABLES {
+            self.conn.execute(&format!("DELETE FROM {} WHERE ts < ?1", table.name), [cutoff])?;
+        }
+        Ok(())
+    }
+
+    /// Pages holding data, in bytes
+    fn used_bytes(&self) -> Result<u64, AppError> {
+        let pages: u64 = self.conn.query_row("SELECT (SELECT page_count FROM pragma_page_count()) - (SELECT freelist_count FROM pragma_freelist_count())", [], |r| r.get(0))?;
+        let page_size: u64 = self.conn.query_row("SELECT page_size FROM pragma_page_size()", [], |r| r.get(0))?;
+        Ok(pages * page_size)
+    }
+}
+
+impl SampleStore for Database {
+    fn record(&mut self, sample: &Sample) -> Result<(), AppError> {
+        if self.last_prune.elapsed() >= PRUNE_INTERVAL {
+            self.prune()?;
+        }
+        // One transaction per sample, so a crash never leaves half of one.
+        let tx = self.conn.transaction()?;
+        let ts = sample.ts;
+
+        let cpu = &sample.cpu;
+        tx.execute(
+            "INSERT INTO cpu (ts, usage, temperature, load_1m, load_5m, load_15m) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![ts, cpu.usage, cpu.temperature, cpu.load[0], cpu.load[1], cpu.load[2]],
+        )?;
+        let memory = &sample.memory;
+        tx.execute(
+            "INSERT INTO memory (ts, used, total, swap_used, swap_total) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![ts, memory.used, memory.total, memory.swap_used, memory.swap_total],
+        )?;
+        if let Some(paging) = &memory.paging {
+            tx.execute(
+                "INSERT INTO paging (ts, swap_in, swap_out, faults, major_faults, scanned, stolen, oom_kills) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![ts, paging.swap_in, paging.swap_out, paging.faults, paging.major_faults, paging.scanned, paging.stolen, paging.oom_kills],
+            )?;
+        }
+        for (interface, rates) in &sample.network {
+            tx.execute("INSERT INTO network (ts, device, rx, tx) VALUES (?1, ?2, ?3, ?4)", params![ts, interface, rates.rx, rates.tx])?;
+        }
+        tx.execute("INSERT INTO disk (ts, read, write) VALUES (?1, ?2, ?3)", params![ts, sample.disk.read, sample.disk.write])?;
+        if let Some(gpu) = &sample.gpu {
+            tx.execute("INSERT INTO gpu (ts, usage, memory) VALUES (?1, ?2, ?3)", params![ts, gpu.usage, gpu.memory])?;
+        }
+        if let Some(kernel) = &sample.kernel {
+            tx.execute(
+                "INSERT INTO kernel (ts, context_switches, interrupts, forks, procs_running, procs_blocked) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![ts, kernel.context_switches, kernel.interrupts, kernel.forks, kernel.procs_running, kernel.procs_blocked],
+            )?;
+        }
+        let host = &sample.host;
+        tx.execute("INSERT INTO host (ts, uptime, processes, threads) VALUES (?1, ?2, ?3, ?4)", params![ts, host.uptime, host.processes, host.threads])?;
+        for node in sample.numa.iter().flatten() {
+            let usage = (!node.core_usage.is_empty()).then(|| node.core_usage.iter().sum::<f64>() / node.core_usage.len() as f64);
+            tx.execute(
+                "INSERT INTO numa (ts, device, usage, mem_total, mem_free, hit_rate, miss_rate) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![ts, node.id.to_string(), usage, node.mem_total, node.mem_free, node.hit_rate, node.miss_rate],
+            )?;
+        }
+        for limit in sample.limits.iter().flatten() {
+            tx.execute("INSERT INTO limits (ts, device, used, max) VALUES (?1, ?2, ?3, ?4)", params![ts, limit.label, limit.used, limit.max])?;
+        }
+        if let Some(cache) = &sample.cache {
+            if let Some(arc) = &cache.arc {
+                tx.execute(
+                    "INSERT INTO cache (ts, device, hit_ratio, size, target, max) VALUES (?1, 'arc', ?2, ?3, ?4, ?5)",
+                    params![ts, arc.hit_ratio, arc.size, arc.target, arc.max],
+                )?;
+            }
+            for zram in &cache.zram {
+                tx.execute(
+                    "INSERT INTO cache (ts, device, orig, compr, mem_used) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![ts, zram.name, zram.orig, zram.compr, zram.mem_used],
+                )?;
+            }
+            for bcache in &cache.bcache {
+                tx.execute("INSERT INTO cache (ts, device, hit_ratio) VALUES (?1, ?2, ?3)", params![ts, bcache.name, bcache.hit_ratio])?;
+            }
+        }
+        for array in sample.raid.iter().flatten() {
+            tx.execute(
+                "INSERT INTO raid (ts, device, active, expected_disks, working_disks, failed_disks, sync_percent) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![ts, array.name, array.active, array.expected_disks, array.working_disks, array.failed_disks(), array.sync.as_ref().map(|s| s.percent)],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Open `path`, creating the schema if it's new.
+fn open(path: &Path) -> Result<Connection, AppError> {
+    let conn = Connection::open(path)?;
+    // WAL lets `sysvitals query` read while a recorder is writing.
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.busy_timeout(Duration::from_secs(5))?;
+    let mut schema = String::from("CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);\n");
+    for table in TABLES {
+        let per_device = COLLECTORS.iter().any(|c| c.name == table.name && c.per_device);
+        let device = if per_device { ", device TEXT NOT NULL" } else { "" };
+        let columns: String = table.columns.iter().map(|column| format!(", {column}")).collect();
+        schema.push_str(&format!(
+            "CREATE TABLE IF NOT EXISTS {0} (ts INTEGER NOT NULL{device}{columns});\nCREATE INDEX IF NOT EXISTS {0}_ts ON {0} (ts);\n",
+            table.name,
+        ));
+    }
+    conn.execute_batch(&schema)?;
+    Ok(conn)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+/// A metric name resolved against the schema
+struct Metric<'a> {
+    table: &'static Table,
+    device: Option<&'a str>,
+    /// SQL expression over the table's columns; only ever built from `TABLES` and `DERIVED`
+    expr: &'static str,
+    unit: &'static str,
+}
+
+fn resolve(name: &str) -> Result<Metric<'_>, String> {
+    let named = metric_name(name)?;
+    let (collector, value) = (named.collector.name, named.metric.name);
+    let table = TABLES.iter().find(|t| t.name == collector);
+    let stored = table.and_then(|t| t.columns.iter().find(|c| **c == value).copied());
+    let derived = DERIVED.iter().find(|(t, c, _)| *t == collector && *c == value).map(|(.., expr)| *expr);
+    match (table, stored.or(derived)) {
+        (Some(table), Some(expr)) => Ok(Metric { table, device: named.device, expr, unit: named.metric.unit }),
+        _ => Err(format!("{name} isn't kept in the database")),
+    }
+}
+
+/// Default look-back of `sysvitals query`
+const DEFAULT_SINCE: Duration = Duration::from_secs(3600);
+
+/// `sysvitals query`: aggregate one metric over `--since` and print it.
+pub fn query(args: &CliArgs) -> Result<(), AppError> {
+    let db = args.db.clone().unwrap_or_else(|| Config::load().unwrap_or_default().recording.dir().join(FILE_NAME));
+    let since = args.since_secs.map_or(DEFAULT_SINCE, Duration::from_secs);
+    let aggregate = args.aggregate.unwrap_or(Aggregate::Avg);
+    aggregate_metric(&db, args.metric.as_deref().unwrap_or_default(), since, aggregate)
+}
+
+fn aggregate_metric(db: &Path, name: &str, since: Duration, aggregate: Aggregate) -> Result<(), AppError> {
+    if !db.exists() {
+        return Err(AppError::Config(format!("{}: no database; record with [recording] backend = \"sqlite\"", db.display())));
+    }
+    let metric = resolve(name).map_err(AppError::Config)?;
+    let conn = open(db)?;
+    let from = now_ms().saturating_sub(since.as_millis() as u64);
+
+    let table = metric.table.name;
+    let filter = match metric.device {
+        Some(_) => "ts >= ?1 AND device = ?2",
+        None => "ts >= ?1 AND ?2 IS NULL",
+    };
+    let expr = metric.expr;
+    // For MIN and MAX, SQLite takes the bare `ts` from the row that holds the extreme.
+    let sql = match aggregate {
+        Aggregate::Min => format!("SELECT MIN({expr}), ts, COUNT({expr}) FROM {table} WHERE {filter}"),
+        Aggregate::Max => format!("SELECT MAX({expr}), ts, COUNT({expr}) FROM {table} WHERE {filter}"),
+        Aggregate::Avg => format!("SELECT AVG({expr}), NULL, COUNT({expr}) FROM {table} WHERE {filter}"),
+        Aggregate::Last => format!(
+            "SELECT {expr}, ts, (SELECT COUNT({expr}) FROM {table} WHERE {filter}) FROM {table} WHERE {filter} AND {expr} IS NOT NULL ORDER BY ts DESC LIMIT 1"
+        ),
+        Aggregate::Count => format!("SELECT COUNT({expr}), NULL, COUNT({expr}) FROM {table} WHERE {filter}"),
+    };
+    let row: Option<(Option<f64>, Option<u64>, u64)> = conn
+        .query_row(&sql, params![from, metric.device], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+        .optional()?;
+
+    let span = format_span(since);
+    let (value, at, count) = match row {
+        Some((Some(value), at, count)) if count > 0 => (value, at, count),
+        _ => {
+            if let Some(device) = metric.device {
+                let mut statement = conn.prepare(&format!("SELECT DISTINCT device FROM {table} WHERE ts >= ?1 ORDER BY device"))?;
+                let devices: Vec<String> = statement.query_map([from], |r| r.get(0))?.collect::<Result<_, _>>()?;
+                if !devices.contains(&device.to_string()) && !devices.is_empty() {
+                    return Err(AppError::Config(format!("no {table} device {device} in the last {span}; recorded: {}", devices.join(", "))));
+                }
+            }
+            return Err(AppError::Config(format!("no {name} samples in the last {span}")));
+        }
+    };
+
+    let value = match aggregate {
+        Aggregate::Count => value.to_string(),
+        _ => format_value(value, metric.unit),
+    };
+    let at = at.map(|ts| format!(" at {}", format_utc_seconds(ts / 1000))).unwrap_or_default();
+    println!("{} {name} over the last {span}: {value}{at} ({count} samples)", aggregate.name());
+    Ok(())
+}
+
+fn format_value(value: f64, unit: &str) -> String {
+    const GB: f64 = (1u64 << 30) as f64;
+    match unit {
+        "bytes" if value >= GB => format!("{:.2} GiB ({value:.0} bytes)", value / GB),
+        "bytes" if value >= MB as f64 => format!("{:.1} MiB ({value:.0} bytes)", value / MB as f64),
+        "bytes" => format!("{value:.0} bytes"),
+        "" => format!("{value:.2}"),
+        "%" | "°C" => format!("{value:.1}{unit}"),
+        unit if unit.starts_with('/') => format!("{value:.2}{unit}"),
+        unit => format!("{value:.2} {unit}"),
+    }
+}
+
+/// `3600 s` as `1h`, `90 s` as `90s`
+fn format_span(span: Duration) -> String {
+    let secs = span.as_secs();
+    match secs {
+        s if s % 86_400 == 0 => format!("{}d", s / 86_400),
+        s if s % 3_600 == 0 => format!("{}h", s / 3_600),
+        s if s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::sample::test_support::{header, sample};
+
+    #[test]
+    fn test_resolve_metric_names() {
+        let metric = resolve("network.eth0.rx").unwrap();
+        assert_eq!((metric.table.name, metric.device, metric.expr), ("network", Some("eth0"), "rx"));
+        assert_eq!(resolve("memory.used_percent").unwrap().unit, "%");
+        assert!(resolve("network.rx").is_err());
+        assert!(resolve("cpu.usage; DROP TABLE cpu").is_err());
+        assert!(resolve("bogus.usage").is_err());
+        // Everything an alert can watch can be queried too.
+        for collector in COLLECTORS {
+            for metric in collector.metrics {
+                let device = if collector.per_device { "x." } else { "" };
+                assert!(resolve(&format!("{}.{device}{}", collector.name, metric.name)).is_ok(), "{}.{}", collector.name, metric.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_record_and_prune() {
+        let dir = std::env::temp_dir().join(format!("sysvitals-db-test-{}", std::process::id()));
+        let config = RecordingConfig { dir: Some(dir.clone()), retention_hours: 1, ..Default::default() };
+        let mut database = Database::create(&config, &header()).unwrap();
+
+        let mut sample = sample();
+        let now = now_ms();
+        for ts in [now - 2 * 3_600_000, now - 1000, now] {
+            sample.ts = ts;
+            database.record(&sample).unwrap();
+        }
+        database.prune().unwrap();
+
+        let (count, rx): (u64, f64) = database.conn
+            .query_row("SELECT COUNT(*), MAX(rx) FROM network WHERE device = 'eth0'", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!((count, rx), (2, 8.0));
+        let ratio: f64 = database.conn
+            .query_row(&format!("SELECT {} FROM cache WHERE device = 'zram0'", resolve("cache.zram0.compression_ratio").unwrap().expr), [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(ratio, 4.0);
+        let (working, missing): (u64, u64) = database.conn
+            .query_row("SELECT working_disks, expected_disks - working_disks FROM raid WHERE device = 'md0'", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!((working, missing), (2, 0));
+        drop(database);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -29,4 +29,10 @@ impl From<std::io::Error> for AppError {
     fn from(err: std::io::Error) -> Self {
         Self::Io(err)
     }
-}
\ No newline at end of file
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::System(format!("database error: {err}"))
+    }
+}
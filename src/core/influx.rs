@@ -0,0 +1,106 @@
+//! InfluxDB line protocol output.
+//!
+//! Each sample becomes one line per collector and device, e.g.
+//! `sysvitals_cpu,host=box usage_pct=12.5,load_1m=0.4 1700000000000000000`,
+//! with nanosecond timestamps so no `precision` parameter is needed. Lines go
+//! to stdout, are appended to a file, or are POSTed to a write URL (v1 `/write`
+//! or v2 `/api/v2/write`).
+
+use crate::core::config::InfluxConfig;
+use crate::core::error::AppError;
+use crate::core::sink::{self, Append, Delivery, HttpPost, Point, Sink, Stdout, Transport};
+use crate::metrics::sample::Header;
+use std::path::Path;
+
+pub fn start(config: &InfluxConfig, header: &Header, report_errors: bool) -> Result<Sink, AppError> {
+    let transport: Box<dyn Transport> = match config.target.as_str() {
+        "stdout" | "-" => Box::new(Stdout),
+        url if url.contains("://") => {
+            let headers = config.token.iter().map(|token| ("Authorization".to_string(), format!("Token {token}"))).collect();
+            Box::new(HttpPost::new(url, "text/plain; charset=utf-8", headers).map_err(AppError::Config)?)
+        }
+        path => Box::new(Append::open(Path::new(path))?),
+    };
+    let tags = sink::tags(header, &config.tags)
+        .iter()
+        .map(|(key, value)| format!(",{}={}", escape(key), escape(value)))
+        .collect::<String>();
+    let delivery = Delivery { batch_size: config.batch_size, retries: config.retries, report_errors };
+    Ok(Sink::start("influx", Box::new(move |sample| encode(&sink::points(sample), &tags, sample.ts)), transport, delivery)?)
+}
+
+/// One line per run of points sharing a group and label. `tags` is the
+/// already-escaped `,key=value` list common to every line.
+fn encode(points: &[Point], tags: &str, ts: u64) -> Vec<String> {
+    let ns = ts.saturating_mul(1_000_000);
+    points
+        .chunk_by(|a, b| a.group == b.group && a.label == b.label)
+        .map(|run| {
+            let first = &run[0];
+            let mut line = format!("sysvitals_{}{tags}", first.group);
+            if let Some((key, value)) = &first.label {
+                line.push_str(&format!(",{key}={}", escape(value)));
+            }
+            let fields: Vec<String> = run
+                .iter()
+                .map(|point| match point.integer {
+                    true => format!("{}={}i", point.name, point.value as i64),
+                    false => format!("{}={}", point.name, point.value),
+                })
+                .collect();
+            format!("{line} {} {ns}", fields.join(","))
+        })
+        .collect()
+}
+
+/// Tag keys and values escape commas, equals signs and spaces.
+fn escape(value: &str) -> String {
+    value.replace('\\', r"\\").replace(',', r"\,").replace('=', r"\=").replace(' ', r"\ ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sink::test_support::endpoint;
+    use crate::metrics::sample::test_support::{header, sample};
+
+    fn point(group: &'static str, name: &'static str, label: Option<&str>, value: f64, integer: bool) -> Point {
+        Point { group, name, label: label.map(|l| ("interface", l.to_string())), value, integer }
+    }
+
+    #[test]
+    fn test_encode_groups_lines() {
+        let points = [
+            point("cpu", "usage_pct", None, 12.5, false),
+            point("cpu", "load_1m", None, 0.25, false),
+            point("memory", "used_bytes", None, 1024.0, true),
+            point("net", "rx_mbit_s", Some("eth 0"), 1.0, false),
+            point("net", "rx_mbit_s", Some("wlan0"), 2.0, false),
+        ];
+        let lines = encode(&points, &format!(",host={}", escape("my box")), 1_700_000_000_000);
+        assert_eq!(lines, [
+            r"sysvitals_cpu,host=my\ box usage_pct=12.5,load_1m=0.25 1700000000000000000",
+            r"sysvitals_memory,host=my\ box used_bytes=1024i 1700000000000000000",
+            r"sysvitals_net,host=my\ box,interface=eth\ 0 rx_mbit_s=1 1700000000000000000",
+            r"sysvitals_net,host=my\ box,interface=wlan0 rx_mbit_s=2 1700000000000000000",
+        ]);
+    }
+
+    #[test]
+    fn test_http_batches_and_retries() {
+        // Refuses the first request, accepts the second.
+        let (base, server) = endpoint(&["500 Internal Server Error", "204 No Content"]);
+        let config = InfluxConfig { enabled: true, target: format!("{base}/api/v2/write?bucket=test"), batch_size: 2, retries: 1, ..Default::default() };
+        let sink = start(&config, &header(), false).unwrap();
+        sink.send(sample());
+        sink.send(sample());
+
+        // Retries stop once the sink is dropped, so wait for the second request first.
+        let requests = server.join().unwrap();
+        drop(sink);
+        assert!(requests[1].head.starts_with("POST /api/v2/write?bucket=test "));
+        assert_eq!(requests[0].body, requests[1].body);
+        let cpu_lines = requests[1].body.lines().filter(|l| l.starts_with("sysvitals_cpu,host=box ")).count();
+        assert_eq!(cpu_lines, 2);
+    }
+}
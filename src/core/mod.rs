@@ -1,7 +1,18 @@
 pub mod app;
 pub mod args;
 pub mod runner;
+pub mod daemon;
+pub mod output;
 pub mod config;
+pub mod recorder;
+pub mod database;
+pub mod prometheus;
+pub mod sink;
+pub mod influx;
+pub mod statsd;
+pub mod otlp;
+pub mod notify;
+pub mod replay;
 pub mod error;
 
 pub use app::App;
@@ -0,0 +1,446 @@
+//! Alert actions. When an alert fires or resolves, each of its rule's actions
+//! runs on a background thread: a shell command with the alert in `SYSVITALS_*`
+//! environment variables, a JSON POST to a webhook, and a desktop notification
+//! through `notify-send`. A per-rule cooldown and an hourly cap keep a flapping
+//! rule from flooding anyone, and every outcome is appended to a log.
+
+use crate::core::config::NotificationsConfig;
+use crate::core::error::AppError;
+use crate::core::sink::HttpPost;
+use crate::metrics::alerts::{Alert, AlertRule, Severity, State};
+use crate::metrics::sample::Header;
+use crate::ui::header::format_utc_seconds;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Commands and `notify-send` still running after this are killed.
+const ACTION_TIMEOUT: Duration = Duration::from_secs(10);
+const HOUR_MS: u64 = 3600 * 1000;
+
+/// What an action is told about the alert
+#[derive(Debug, Clone)]
+struct Event {
+    name: String,
+    state: State,
+    severity: Severity,
+    metric: String,
+    value: Option<f64>,
+    threshold: f64,
+    condition: String,
+    /// When the alert entered `state`, milliseconds since the Unix epoch
+    since: u64,
+    host: String,
+}
+
+impl Event {
+    fn value(&self) -> String {
+        self.value.map_or(String::new(), |v| format!("{v:.1}"))
+    }
+
+    fn env(&self) -> [(&'static str, String); 9] {
+        [
+            ("SYSVITALS_ALERT_NAME", self.name.clone()),
+            ("SYSVITALS_ALERT_STATE", self.state.label().to_lowercase()),
+            ("SYSVITALS_ALERT_SEVERITY", self.severity.label().to_string()),
+            ("SYSVITALS_ALERT_METRIC", self.metric.clone()),
+            ("SYSVITALS_ALERT_VALUE", self.value()),
+            ("SYSVITALS_ALERT_THRESHOLD", self.threshold.to_string()),
+            ("SYSVITALS_ALERT_CONDITION", self.condition.clone()),
+            ("SYSVITALS_ALERT_SINCE", (self.since / 1000).to_string()),
+            ("SYSVITALS_HOST", self.host.clone()),
+        ]
+    }
+
+    fn payload(&self) -> String {
+        json!({
+            "alert": self.name,
+            "state": self.state.label().to_lowercase(),
+            "severity": self.severity.label(),
+            "metric": self.metric,
+            "value": self.value,
+            "threshold": self.threshold,
+            "condition": self.condition,
+            "since": self.since,
+            "host": self.host,
+        })
+        .to_string()
+    }
+}
+
+/// One rule's actions
+struct Actions {
+    command: Option<String>,
+    webhook: Option<HttpPost>,
+    desktop: bool,
+}
+
+enum Job {
+    Run(usize, Event),
+    /// Held back by a rate limit; only logged
+    Skip(Event, &'static str),
+}
+
+pub struct Notifier {
+    config: NotificationsConfig,
+    host: String,
+    /// Which rules have any action
+    active: Vec<bool>,
+    /// When each rule last notified of firing
+    last_fired: Vec<Option<u64>>,
+    /// Whether each rule's current firing was notified, so its resolution is too
+    announced: Vec<bool>,
+    /// Notification times within the last hour, oldest first
+    recent: VecDeque<u64>,
+    sender: Option<Sender<Job>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Notifier {
+    /// `None` when no rule has an action.
+    pub fn start(rules: &[AlertRule], config: &NotificationsConfig, header: &Header, report_errors: bool) -> Result<Option<Self>, AppError> {
+        if !rules.iter().any(AlertRule::has_actions) {
+            return Ok(None);
+        }
+        let actions = rules
+            .iter()
+            .map(|rule| {
+                let webhook = match &rule.webhook {
+                    Some(url) => Some(HttpPost::new(url, "application/json", vec![]).map_err(|e| AppError::Config(format!("alert {}: {e}", rule.rule)))?),
+                    None => None,
+                };
+                Ok(Actions { command: rule.command.clone(), webhook, desktop: rule.notify })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        let path = config.log();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let log = OpenOptions::new().create(true).append(true).open(&path)?;
+
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::Builder::new().name("notify".into()).spawn(move || {
+            let mut worker = Worker { actions, log, report_errors };
+            // Ends once the notifier is dropped and the queue is drained.
+            for job in receiver {
+                worker.handle(job);
+            }
+        })?;
+
+        Ok(Some(Self {
+            config: config.clone(),
+            host: header.host_name.clone().unwrap_or_default(),
+            active: rules.iter().map(AlertRule::has_actions).collect(),
+            last_fired: vec![None; rules.len()],
+            announced: vec![false; rules.len()],
+            recent: VecDeque::new(),
+            sender: Some(sender),
+            thread: Some(thread),
+        }))
+    }
+
+    /// Take over what `previous` announced and when, for the same rules under
+    /// new settings, so an alert firing across a reload still notifies when it resolves.
+    pub fn carry_over(&mut self, previous: &Notifier) {
+        self.last_fired.clone_from(&previous.last_fired);
+        self.announced.clone_from(&previous.announced);
+        self.recent.clone_from(&previous.recent);
+    }
+
+    /// React to alert `index` (as numbered in the config) changing state.
+    pub fn alert_changed(&mut self, index: usize, alert: &Alert) {
+        if !self.active.get(index).copied().unwrap_or(false) {
+            return;
+        }
+        let ts = alert.since;
+        let verdict = match alert.state {
+            State::Firing => self.admit_firing(index, ts),
+            State::Resolved if std::mem::take(&mut self.announced[index]) && self.config.on_resolved => self.admit(ts),
+            _ => return,
+        };
+        let event = Event {
+            name: alert.name.clone(),
+            state: alert.state,
+            severity: alert.severity,
+            metric: alert.metric.clone(),
+            value: alert.value,
+            threshold: alert.threshold,
+            condition: alert.condition(),
+            since: ts,
+            host: self.host.clone(),
+        };
+        let job = match verdict {
+            Ok(()) => Job::Run(index, event),
+            Err(reason) => Job::Skip(event, reason),
+        };
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(job);
+        }
+    }
+
+    fn admit_firing(&mut self, index: usize, ts: u64) -> Result<(), &'static str> {
+        if let Some(last) = self.last_fired[index]
+            && ts.saturating_sub(last) < self.config.cooldown_secs * 1000
+        {
+            return Err("cooldown");
+        }
+        self.admit(ts)?;
+        self.last_fired[index] = Some(ts);
+        self.announced[index] = true;
+        Ok(())
+    }
+
+    /// Count a notification against the hourly cap, if there's room.
+    fn admit(&mut self, ts: u64) -> Result<(), &'static str> {
+        while self.recent.front().is_some_and(|&t| ts.saturating_sub(t) >= HOUR_MS) {
+            self.recent.pop_front();
+        }
+        if self.recent.len() >= self.config.max_per_hour {
+            return Err("hourly limit");
+        }
+        self.recent.push_back(ts);
+        Ok(())
+    }
+}
+
+impl Drop for Notifier {
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+struct Worker {
+    actions: Vec<Actions>,
+    log: File,
+    report_errors: bool,
+}
+
+impl Worker {
+    fn handle(&mut self, job: Job) {
+        let (index, event) = match job {
+            Job::Run(index, event) => (index, event),
+            Job::Skip(event, reason) => {
+                self.record(&event, "all", &format!("skipped ({reason})"));
+                return;
+            }
+        };
+        let Some(actions) = self.actions.get(index) else { return };
+        let mut outcomes = vec![];
+        if let Some(command) = &actions.command {
+            outcomes.push(("command", run_command(command, &event)));
+        }
+        if let Some(webhook) = &actions.webhook {
+            outcomes.push(("webhook", webhook.post(&event.payload())));
+        }
+        if actions.desktop {
+            outcomes.push(("notify-send", notify_send(&event)));
+        }
+        for (action, outcome) in outcomes {
+            match outcome {
+                Ok(()) => self.record(&event, action, "delivered"),
+                Err(e) => {
+                    if self.report_errors {
+                        eprintln!("sysvitals: alert {} {action} failed: {e}", event.name);
+                    }
+                    self.record(&event, action, &format!("failed: {e}"));
+                }
+            }
+        }
+    }
+
+    /// One line per action: time, state, alert, action and outcome
+    fn record(&mut self, event: &Event, action: &str, outcome: &str) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let _ = writeln!(
+            self.log,
+            "{}\t{}\t{}\t{action}\t{outcome}",
+            format_utc_seconds(now),
+            event.state.label(),
+            event.name,
+        );
+    }
+}
+
+fn run_command(command: &str, event: &Event) -> io::Result<()> {
+    let mut shell = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    let child = shell
+        .arg(command)
+        .envs(event.env())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    wait(child)
+}
+
+fn notify_send(event: &Event) -> io::Result<()> {
+    let urgency = match event.severity {
+        Severity::Info => "low",
+        Severity::Warning => "normal",
+        Severity::Critical => "critical",
+    };
+    let summary = format!("{} {}", event.state.label(), event.name);
+    let body = format!("{} = {} (rule {}) on {}", event.metric, event.value(), event.condition, event.host);
+    let child = Command::new("notify-send")
+        .args(["--app-name=sysvitals", "--urgency", urgency, &summary, &body])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => io::Error::other("notify-send not found; install libnotify"),
+            _ => e,
+        })?;
+    wait(child)
+}
+
+/// Wait for `child`, killing it after `ACTION_TIMEOUT`.
+fn wait(mut child: Child) -> io::Result<()> {
+    let deadline = Instant::now() + ACTION_TIMEOUT;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return match status.success() {
+                true => Ok(()),
+                false => Err(io::Error::other(status.to_string())),
+            };
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::other(format!("killed after {}s", ACTION_TIMEOUT.as_secs())));
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sink::test_support::endpoint;
+    use crate::metrics::alerts::Alerts;
+    use crate::metrics::sample::test_support::{header, sample};
+    use serde_json::Value;
+
+    /// Feed `usage` at each `(secs, usage)` through the alert and notifier.
+    fn drive(rules: &[AlertRule], config: &NotificationsConfig, steps: &[(u64, f64)]) {
+        let mut sample = sample();
+        let mut alerts = Alerts::new(rules).unwrap();
+        let mut notifier = Notifier::start(rules, config, &header(), false).unwrap().unwrap();
+        for &(secs, usage) in steps {
+            sample.ts = secs * 1000;
+            sample.cpu.usage = usage;
+            for i in alerts.evaluate(&sample) {
+                notifier.alert_changed(i, &alerts.all()[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_command_with_rate_limits() {
+        let dir = std::env::temp_dir().join(format!("sysvitals-notify-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let out = dir.join("out");
+        let rules = [AlertRule {
+            rule: "cpu.usage > 90".into(),
+            command: Some(format!("echo \"$SYSVITALS_ALERT_STATE $SYSVITALS_ALERT_VALUE $SYSVITALS_HOST\" >> {}", out.display())),
+            ..Default::default()
+        }];
+        let config = NotificationsConfig { cooldown_secs: 60, max_per_hour: 3, log: Some(dir.join("log")), ..Default::default() };
+        drive(&rules, &config, &[
+            (0, 95.0),
+            (10, 50.0),
+            // Fires again within the cooldown; its resolution stays quiet too.
+            (20, 95.0),
+            (30, 50.0),
+            (100, 95.0),
+            // Fourth notification this hour
+            (110, 50.0),
+        ]);
+
+        assert_eq!(fs::read_to_string(&out).unwrap(), "firing 95.0 box\nresolved 50.0 box\nfiring 95.0 box\n");
+        let log = fs::read_to_string(dir.join("log")).unwrap();
+        let outcomes: Vec<&str> = log.lines().map(|l| l.rsplit('\t').next().unwrap()).collect();
+        assert_eq!(outcomes, ["delivered", "delivered", "skipped (cooldown)", "delivered", "skipped (hourly limit)"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_carry_over_keeps_resolution() {
+        let out = std::env::temp_dir().join(format!("sysvitals-carry-test-{}", std::process::id()));
+        let _ = fs::remove_file(&out);
+        let rules = [AlertRule {
+            rule: "cpu.usage > 90".into(),
+            command: Some(format!("echo \"$SYSVITALS_ALERT_STATE\" >> {}", out.display())),
+            ..Default::default()
+        }];
+        let log = std::env::temp_dir().join(format!("sysvitals-carry-test-{}.log", std::process::id()));
+        let config = NotificationsConfig { log: Some(log.clone()), ..Default::default() };
+        let mut sample = sample();
+        let mut alerts = Alerts::new(&rules).unwrap();
+        let mut step = |notifier: &mut Notifier, usage| {
+            sample.ts += 1000;
+            sample.cpu.usage = usage;
+            for i in alerts.evaluate(&sample) {
+                notifier.alert_changed(i, &alerts.all()[i]);
+            }
+        };
+
+        let mut old = Notifier::start(&rules, &config, &header(), false).unwrap().unwrap();
+        step(&mut old, 95.0);
+        // Only the notification settings change; the alert keeps firing.
+        let mut new = Notifier::start(&rules, &NotificationsConfig { max_per_hour: 5, ..config }, &header(), false).unwrap().unwrap();
+        new.carry_over(&old);
+        drop(old);
+        step(&mut new, 50.0);
+        drop(new);
+
+        assert_eq!(fs::read_to_string(&out).unwrap(), "firing\nresolved\n");
+        fs::remove_file(&out).unwrap();
+        fs::remove_file(&log).unwrap();
+    }
+
+    #[test]
+    fn test_webhook_payload() {
+        let (base, receiver) = endpoint(&["204 No Content"]);
+        let log = std::env::temp_dir().join(format!("sysvitals-webhook-test-{}.log", std::process::id()));
+        let rules = [AlertRule {
+            name: Some("busy".into()),
+            rule: "cpu.usage > 90".into(),
+            severity: Severity::Critical,
+            webhook: Some(format!("{base}/hook")),
+            ..Default::default()
+        }];
+        let config = NotificationsConfig { log: Some(log.clone()), ..Default::default() };
+        drive(&rules, &config, &[(5, 97.0)]);
+
+        let request = receiver.join().unwrap().remove(0);
+        assert!(request.head.starts_with("POST /hook "));
+        let payload: Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(payload["alert"], "busy");
+        assert_eq!(payload["state"], "firing");
+        assert_eq!(payload["severity"], "critical");
+        assert_eq!(payload["metric"], "cpu.usage");
+        assert_eq!(payload["value"], 97.0);
+        assert_eq!(payload["since"], 5000);
+        assert_eq!(payload["host"], "box");
+        assert!(fs::read_to_string(&log).unwrap().ends_with("webhook\tdelivered\n"));
+        fs::remove_file(&log).unwrap();
+    }
+}
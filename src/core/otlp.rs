@@ -0,0 +1,258 @@
+//! OpenTelemetry metrics export over OTLP/HTTP, JSON encoded.
+//!
+//! Values are mapped onto the OpenTelemetry system semantic conventions
+//! (`system.cpu.utilization`, `system.memory.usage`, `system.network.io`, ...)
+//! and the hardware ones for GPUs, under a resource describing this host.
+//! Each sample becomes one `ResourceMetrics`; a batch of them is POSTed as one
+//! export request.
+
+use crate::core::config::OtlpConfig;
+use crate::core::error::AppError;
+use crate::core::sink::{Delivery, HttpPost, Sink, Transport};
+use crate::metrics::sample::{Header, Sample};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::io;
+
+const MB: f64 = 1024.0 * 1024.0;
+/// `AggregationTemporality.CUMULATIVE`
+const CUMULATIVE: u8 = 2;
+
+pub fn start(config: &OtlpConfig, header: &Header, report_errors: bool) -> Result<Sink, AppError> {
+    let headers = config.headers.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    let post = HttpPost::new(&config.endpoint, "application/json", headers).map_err(AppError::Config)?;
+    let mut encoder = Encoder::new(resource(header, &config.resource_attributes));
+    let delivery = Delivery { batch_size: config.batch_size, retries: config.retries, report_errors };
+    Ok(Sink::start("otlp", Box::new(move |sample| vec![encoder.encode(sample).to_string()]), Box::new(Export(post)), delivery)?)
+}
+
+/// Wraps a batch of `ResourceMetrics` in one export request
+struct Export(HttpPost);
+
+impl Transport for Export {
+    fn send(&mut self, lines: &[String]) -> io::Result<()> {
+        self.0.post(&format!("{{\"resourceMetrics\":[{}]}}", lines.join(",")))
+    }
+}
+
+/// Host identity as OTel resource attributes; configured ones win.
+fn resource(header: &Header, configured: &BTreeMap<String, String>) -> Value {
+    let mut attributes = BTreeMap::from([
+        ("service.name".to_string(), "sysvitals".to_string()),
+        ("service.version".to_string(), env!("CARGO_PKG_VERSION").to_string()),
+        ("os.type".to_string(), match std::env::consts::OS { "macos" => "darwin", os => os }.to_string()),
+        ("host.arch".to_string(), match std::env::consts::ARCH { "x86_64" => "amd64", "aarch64" => "arm64", arch => arch }.to_string()),
+    ]);
+    let identity = [("host.name", &header.host_name), ("os.description", &header.os_version), ("os.version", &header.kernel_version)];
+    for (key, value) in identity {
+        if let Some(value) = value {
+            attributes.insert(key.to_string(), value.clone());
+        }
+    }
+    attributes.extend(configured.clone());
+    let attributes: Vec<(&str, &str)> = attributes.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    json!({ "attributes": key_values(&attributes) })
+}
+
+fn key_values(attributes: &[(&str, &str)]) -> Value {
+    attributes.iter().map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } })).collect()
+}
+
+enum Number {
+    Double(f64),
+    Int(i64),
+}
+
+/// Turns samples into `ResourceMetrics`, keeping running totals for the `io`
+/// counters, which OTel defines as cumulative bytes rather than rates.
+struct Encoder {
+    resource: Value,
+    /// When the totals started, nanoseconds since the Unix epoch
+    start: Option<u64>,
+    /// Previous sample, milliseconds since the Unix epoch
+    last_ts: Option<u64>,
+    /// Bytes per interface and direction
+    network: BTreeMap<(String, &'static str), f64>,
+    /// Bytes read and written
+    disk: [f64; 2],
+}
+
+impl Encoder {
+    fn new(resource: Value) -> Self {
+        Self { resource, start: None, last_ts: None, network: BTreeMap::new(), disk: [0.0; 2] }
+    }
+
+    fn encode(&mut self, sample: &Sample) -> Value {
+        self.accumulate(sample);
+        let now = sample.ts.saturating_mul(1_000_000);
+        let start = *self.start.get_or_insert(now);
+        let point = |value: Number, attributes: &[(&str, &str)]| {
+            let mut point = json!({
+                "startTimeUnixNano": start.to_string(),
+                "timeUnixNano": now.to_string(),
+                "attributes": key_values(attributes),
+            });
+            match value {
+                Number::Double(v) => point["asDouble"] = json!(v),
+                Number::Int(v) => point["asInt"] = json!(v.to_string()),
+            }
+            point
+        };
+        let mut metrics = vec![];
+
+        let cpu = &sample.cpu;
+        metrics.push(gauge("system.cpu.utilization", "1", "CPU time in use, 0 to 1", vec![point(Number::Double(cpu.usage / 100.0), &[])]));
+        for (period, value) in ["1m", "5m", "15m"].iter().zip(cpu.load) {
+            metrics.push(gauge(&format!("system.cpu.load_average.{period}"), "{thread}", "System load average", vec![point(Number::Double(value), &[])]));
+        }
+
+        let memory = &sample.memory;
+        let free = memory.total.saturating_sub(memory.used);
+        metrics.push(sum("system.memory.usage", "By", "Memory in use and free", false, vec![
+            point(Number::Int(memory.used as i64), &[("system.memory.state", "used")]),
+            point(Number::Int(free as i64), &[("system.memory.state", "free")]),
+        ]));
+        metrics.push(sum("system.memory.limit", "By", "Installed memory", false, vec![point(Number::Int(memory.total as i64), &[])]));
+        if memory.total > 0 {
+            metrics.push(gauge("system.memory.utilization", "1", "Share of memory in use and free", vec![
+                point(Number::Double(memory.used as f64 / memory.total as f64), &[("system.memory.state", "used")]),
+                point(Number::Double(free as f64 / memory.total as f64), &[("system.memory.state", "free")]),
+            ]));
+        }
+        if memory.swap_total > 0 {
+            let swap_free = memory.swap_total.saturating_sub(memory.swap_used);
+            metrics.push(sum("system.paging.usage", "By", "Swap in use and free", false, vec![
+                point(Number::Int(memory.swap_used as i64), &[("system.paging.state", "used")]),
+                point(Number::Int(swap_free as i64), &[("system.paging.state", "free")]),
+            ]));
+            metrics.push(gauge("system.paging.utilization", "1", "Share of swap in use and free", vec![
+                point(Number::Double(memory.swap_used as f64 / memory.swap_total as f64), &[("system.paging.state", "used")]),
+                point(Number::Double(swap_free as f64 / memory.swap_total as f64), &[("system.paging.state", "free")]),
+            ]));
+        }
+
+        let network = self.network
+            .iter()
+            .map(|((interface, direction), bytes)| {
+                point(Number::Int(*bytes as i64), &[("network.interface.name", interface), ("network.io.direction", direction)])
+            })
+            .collect();
+        metrics.push(sum("system.network.io", "By", "Bytes sent and received", true, network));
+        metrics.push(sum("system.disk.io", "By", "Bytes read and written, all disks", true, vec![
+            point(Number::Int(self.disk[0] as i64), &[("disk.io.direction", "read")]),
+            point(Number::Int(self.disk[1] as i64), &[("disk.io.direction", "write")]),
+        ]));
+
+        metrics.push(sum("system.process.count", "{process}", "Processes on the host", false, vec![point(Number::Int(sample.host.processes as i64), &[])]));
+        metrics.push(gauge("system.uptime", "s", "Time since boot", vec![point(Number::Int(sample.host.uptime as i64), &[])]));
+
+        if let Some(gpu) = &sample.gpu {
+            let id = [("hw.id", "gpu0"), ("hw.type", "gpu")];
+            metrics.push(gauge("hw.gpu.utilization", "1", "GPU utilisation, 0 to 1", vec![point(Number::Double(gpu.usage / 100.0), &id)]));
+            metrics.push(gauge("hw.gpu.memory.utilization", "1", "Share of GPU memory in use", vec![point(Number::Double(gpu.memory / 100.0), &id)]));
+        }
+
+        json!({
+            "resource": self.resource,
+            "scopeMetrics": [{
+                "scope": { "name": "sysvitals", "version": env!("CARGO_PKG_VERSION") },
+                "metrics": metrics,
+            }],
+        })
+    }
+
+    /// Add the bytes moved since the previous sample, assuming the sampled
+    /// rates held over the whole gap. The first sample only sets the origin.
+    fn accumulate(&mut self, sample: &Sample) {
+        let seconds = self.last_ts.map_or(0.0, |last| sample.ts.saturating_sub(last) as f64 / 1000.0);
+        self.last_ts = Some(sample.ts);
+        // Interfaces are sampled in Mb/s, disks in MB/s.
+        for (interface, rates) in &sample.network {
+            for (direction, rate) in [("receive", rates.rx), ("transmit", rates.tx)] {
+                *self.network.entry((interface.clone(), direction)).or_default() += rate * 1e6 / 8.0 * seconds;
+            }
+        }
+        self.disk[0] += sample.disk.read * MB * seconds;
+        self.disk[1] += sample.disk.write * MB * seconds;
+    }
+}
+
+fn gauge(name: &str, unit: &str, description: &str, points: Vec<Value>) -> Value {
+    json!({ "name": name, "unit": unit, "description": description, "gauge": { "dataPoints": points } })
+}
+
+fn sum(name: &str, unit: &str, description: &str, monotonic: bool, points: Vec<Value>) -> Value {
+    json!({
+        "name": name,
+        "unit": unit,
+        "description": description,
+        "sum": { "dataPoints": points, "aggregationTemporality": CUMULATIVE, "isMonotonic": monotonic },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sink::test_support::endpoint;
+    use crate::metrics::sample::test_support::{header, sample};
+
+    fn metric<'a>(resource_metrics: &'a Value, name: &str) -> &'a Value {
+        resource_metrics["scopeMetrics"][0]["metrics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|m| m["name"] == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_network_io_accumulates_rates() {
+        // eth0 receives at 8 Mb/s
+        let mut sample = sample();
+        let mut encoder = Encoder::new(resource(&header(), &BTreeMap::new()));
+        sample.ts = 1_000;
+        encoder.encode(&sample);
+        sample.ts = 3_000;
+        let encoded = encoder.encode(&sample);
+
+        // 8 Mb/s for two seconds
+        let io = metric(&encoded, "system.network.io");
+        let receive = io["sum"]["dataPoints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["attributes"][1]["value"]["stringValue"] == "receive")
+            .unwrap();
+        assert_eq!(receive["asInt"], "2000000");
+        assert_eq!(receive["startTimeUnixNano"], "1000000000");
+        assert_eq!(io["sum"]["isMonotonic"], true);
+    }
+
+    #[test]
+    fn test_exports_to_collector() {
+        let (base, collector) = endpoint(&["200 OK"]);
+        let config = OtlpConfig {
+            enabled: true,
+            endpoint: format!("{base}/v1/metrics"),
+            headers: BTreeMap::from([("x-api-key".to_string(), "secret".to_string())]),
+            batch_size: 2,
+            ..Default::default()
+        };
+        let sink = start(&config, &header(), false).unwrap();
+        sink.send(sample());
+        sink.send(sample());
+
+        let request = collector.join().unwrap().remove(0);
+        drop(sink);
+        assert!(request.head.starts_with("POST /v1/metrics "));
+        assert!(request.head.contains("Content-Type: application/json"));
+        assert!(request.head.contains("x-api-key: secret"));
+        let request: Value = serde_json::from_str(&request.body).unwrap();
+        let resource_metrics = request["resourceMetrics"].as_array().unwrap();
+        assert_eq!(resource_metrics.len(), 2);
+        let attributes = resource_metrics[0]["resource"]["attributes"].as_array().unwrap();
+        assert!(attributes.iter().any(|a| a["key"] == "host.name" && a["value"]["stringValue"] == "box"));
+        let utilization = metric(&resource_metrics[0], "system.cpu.utilization");
+        assert_eq!(utilization["gauge"]["dataPoints"][0]["asDouble"], 0.125);
+    }
+}
@@ -0,0 +1,292 @@
+//! Machine-readable output for scripts, printed to stdout instead of running
+//! the TUI. The shapes are the recording types in `metrics::sample`, so they
+//! follow the collectors and share their `FORMAT_VERSION`.
+
+use crate::core::args::{CliArgs, Format};
+use crate::core::config::Config;
+use crate::core::error::AppError;
+use crate::metrics::SystemMetrics;
+use crate::metrics::sample::{Sample, Snapshot};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Gap between samples unless `--interval` says otherwise
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `sysvitals snapshot`: sample twice, print the second sample, exit.
+pub fn snapshot(args: &CliArgs) -> Result<(), AppError> {
+    let interval = args.interval_ms.map_or(DEFAULT_INTERVAL, Duration::from_millis);
+
+    // Rates are measured between samples, so the first only sets the baseline.
+    let mut system = SystemMetrics::new();
+    thread::sleep(interval);
+    system.update()?;
+
+    let snapshot = Snapshot { header: system.header(), sample: system.sample() };
+    // JSON is the only format; `CliArgs::parse` rejects the others.
+    let mut out = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut out, &snapshot)
+        .map_err(|e| AppError::System(format!("cannot encode snapshot: {e}")))?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// `sysvitals stream`: print a record per sample until interrupted or the
+/// reader goes away.
+pub fn stream(args: &CliArgs) -> Result<(), AppError> {
+    let interval = args.interval_ms.map_or(DEFAULT_INTERVAL, Duration::from_millis);
+    let panels = Panels::new(&Config::load().unwrap_or_default(), args);
+
+    let format = args.format.unwrap_or(Format::Jsonl);
+
+    let mut system = SystemMetrics::new();
+    let mut out = io::stdout().lock();
+    let mut columns = vec![];
+    let mut first = true;
+    let mut next = Instant::now();
+    loop {
+        // Fixed deadlines, so slow collection doesn't make the stream drift.
+        next += interval;
+        thread::sleep(next.saturating_duration_since(Instant::now()));
+        system.update()?;
+        let sample = system.sample();
+
+        let written = if format == Format::Csv {
+            // Columns are fixed by the first sample; anything that appears
+            // later is left out, anything that goes away is left empty.
+            if first {
+                columns = csv_columns(&sample, &panels);
+                write_csv_header(&mut out, &columns)?;
+            }
+            write_csv_row(&mut out, &columns, &sample)
+        } else {
+            // The header goes first, as in a recording, so the stream can be replayed.
+            if first {
+                write_json_line(&mut out, &system.header())?;
+            }
+            write_json_line(&mut out, &sample)
+        };
+        first = false;
+
+        match written.and_then(|()| out.flush()) {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            result => result?,
+        }
+    }
+}
+
+fn write_json_line<T: serde::Serialize>(out: &mut impl Write, value: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    writeln!(out)
+}
+
+/// Which panels CSV output has columns for: the saved config, then CLI overrides
+struct Panels {
+    cpu: bool,
+    memory: bool,
+    gpu: bool,
+    network: bool,
+    disk: bool,
+    kernel: bool,
+    limits: bool,
+    numa: bool,
+    cache: bool,
+    raid: bool,
+}
+
+impl Panels {
+    fn new(config: &Config, args: &CliArgs) -> Self {
+        Self {
+            cpu:     args.show_cpu.unwrap_or(config.show_cpu),
+            memory:  args.show_memory.unwrap_or(config.show_memory),
+            gpu:     args.show_gpu.unwrap_or(config.show_gpu),
+            network: args.show_network.unwrap_or(config.show_network),
+            disk:    args.show_disk.unwrap_or(config.show_disk),
+            kernel:  args.show_kernel.unwrap_or(config.show_kernel),
+            limits:  args.show_limits.unwrap_or(config.show_limits),
+            numa:    args.show_numa.unwrap_or(config.show_numa),
+            cache:   args.show_cache.unwrap_or(config.show_cache),
+            raid:    args.show_raid.unwrap_or(config.show_raid),
+        }
+    }
+}
+
+/// Reads one CSV value out of a sample; `None` leaves the cell empty.
+type Extract = Box<dyn Fn(&Sample) -> Option<f64>>;
+
+struct Column {
+    name: String,
+    value: Extract,
+}
+
+fn column(name: impl AsRef<str>, value: impl Fn(&Sample) -> Option<f64> + 'static) -> Column {
+    // Names come partly from devices and limits; keep them to `[a-z0-9_]` so
+    // the header row never needs quoting.
+    let name = name.as_ref()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    Column { name, value: Box::new(value) }
+}
+
+/// Columns for every enabled panel present in `first`, in panel order.
+/// Units are in the names: `pct`, `bytes`, `mbit_s` (network), `mb_s` (disk), `per_s`.
+fn csv_columns(first: &Sample, panels: &Panels) -> Vec<Column> {
+    let mut columns = vec![column("ts_ms", |s| Some(s.ts as f64))];
+
+    if panels.cpu {
+        columns.push(column("cpu_usage_pct", |s| Some(s.cpu.usage)));
+        columns.push(column("cpu_temp_c", |s| s.cpu.temperature));
+        for (i, period) in ["1m", "5m", "15m"].into_iter().enumerate() {
+            columns.push(column(format!("load_{period}"), move |s| Some(s.cpu.load[i])));
+        }
+    }
+    if panels.numa && let Some(nodes) = &first.numa {
+        for id in nodes.iter().map(|n| n.id) {
+            let node = move |s: &Sample| s.numa.as_ref()?.iter().find(|n| n.id == id).cloned();
+            columns.push(column(format!("numa{id}_mem_used_bytes"), move |s| {
+                node(s).map(|n| n.mem_total.saturating_sub(n.mem_free) as f64)
+            }));
+            columns.push(column(format!("numa{id}_cpu_pct"), move |s| {
+                node(s).filter(|n| !n.core_usage.is_empty())
+                    .map(|n| n.core_usage.iter().sum::<f64>() / n.core_usage.len() as f64)
+            }));
+        }
+    }
+    if panels.memory {
+        columns.push(column("mem_used_bytes", |s| Some(s.memory.used as f64)));
+        columns.push(column("mem_total_bytes", |s| Some(s.memory.total as f64)));
+        columns.push(column("swap_used_bytes", |s| Some(s.memory.swap_used as f64)));
+        columns.push(column("swap_total_bytes", |s| Some(s.memory.swap_total as f64)));
+        if first.memory.paging.is_some() {
+            columns.push(column("swap_in_pages_per_s", |s| Some(s.memory.paging.as_ref()?.swap_in)));
+            columns.push(column("swap_out_pages_per_s", |s| Some(s.memory.paging.as_ref()?.swap_out)));
+            columns.push(column("major_faults_per_s", |s| Some(s.memory.paging.as_ref()?.major_faults)));
+            columns.push(column("oom_kills", |s| Some(s.memory.paging.as_ref()?.oom_kills as f64)));
+        }
+    }
+    if panels.cache && let Some(cache) = &first.cache {
+        if cache.arc.is_some() {
+            columns.push(column("arc_size_bytes", |s| Some(s.cache.as_ref()?.arc.as_ref()?.size)));
+            columns.push(column("arc_target_bytes", |s| Some(s.cache.as_ref()?.arc.as_ref()?.target)));
+            columns.push(column("arc_hit_pct", |s| Some(s.cache.as_ref()?.arc.as_ref()?.hit_ratio)));
+        }
+        if !cache.zram.is_empty() {
+            columns.push(column("zram_orig_bytes", |s| Some(s.cache.as_ref()?.zram.iter().map(|z| z.orig as f64).sum())));
+            columns.push(column("zram_compr_bytes", |s| Some(s.cache.as_ref()?.zram.iter().map(|z| z.compr as f64).sum())));
+        }
+    }
+    if panels.disk {
+        columns.push(column("disk_read_mb_s", |s| Some(s.disk.read)));
+        columns.push(column("disk_write_mb_s", |s| Some(s.disk.write)));
+    }
+    if panels.raid && let Some(arrays) = &first.raid {
+        for name in arrays.iter().map(|a| a.name.clone()) {
+            columns.push(column(format!("{name}_working_disks"), move |s| {
+                let array = s.raid.as_ref()?.iter().find(|a| a.name == name)?;
+                array.working_disks.map(|n| n as f64)
+            }));
+        }
+    }
+    if panels.network {
+        for iface in first.network.keys() {
+            let (rx, tx) = (iface.clone(), iface.clone());
+            columns.push(column(format!("net_{iface}_rx_mbit_s"), move |s| Some(s.network.get(&rx)?.rx)));
+            columns.push(column(format!("net_{iface}_tx_mbit_s"), move |s| Some(s.network.get(&tx)?.tx)));
+        }
+    }
+    if panels.gpu && first.gpu.is_some() {
+        columns.push(column("gpu_usage_pct", |s| Some(s.gpu.as_ref()?.usage)));
+        columns.push(column("gpu_mem_pct", |s| Some(s.gpu.as_ref()?.memory)));
+    }
+    if panels.kernel && first.kernel.is_some() {
+        columns.push(column("ctxt_per_s", |s| Some(s.kernel.as_ref()?.context_switches)));
+        columns.push(column("intr_per_s", |s| Some(s.kernel.as_ref()?.interrupts)));
+        columns.push(column("forks_per_s", |s| Some(s.kernel.as_ref()?.forks)));
+        columns.push(column("procs_running", |s| Some(s.kernel.as_ref()?.procs_running)));
+        columns.push(column("procs_blocked", |s| Some(s.kernel.as_ref()?.procs_blocked)));
+    }
+    if panels.limits && let Some(limits) = &first.limits {
+        for label in limits.iter().map(|l| l.label.clone()) {
+            columns.push(column(format!("limit_{label}_pct"), move |s| {
+                let limit = s.limits.as_ref()?.iter().find(|l| l.label == label)?;
+                (limit.max > 0).then(|| limit.used as f64 / limit.max as f64 * 100.0)
+            }));
+        }
+    }
+
+    // Sanitizing can make two devices' names collide (`br-1` and `br_1`);
+    // later ones get `_2`, `_3`, ... so every header stays unique.
+    let mut seen = BTreeSet::new();
+    for column in &mut columns {
+        let base = column.name.clone();
+        for n in 2.. {
+            if seen.insert(column.name.clone()) {
+                break;
+            }
+            column.name = format!("{base}_{n}");
+        }
+    }
+    columns
+}
+
+fn write_csv_header(out: &mut impl Write, columns: &[Column]) -> io::Result<()> {
+    let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    writeln!(out, "{}", names.join(","))
+}
+
+fn write_csv_row(out: &mut impl Write, columns: &[Column], sample: &Sample) -> io::Result<()> {
+    let values: Vec<String> = columns
+        .iter()
+        .map(|c| (c.value)(sample).map(|v| v.to_string()).unwrap_or_default())
+        .collect();
+    writeln!(out, "{}", values.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::network::InterfaceSample;
+    use crate::metrics::sample::test_support::sample;
+
+    #[test]
+    fn test_csv_columns() {
+        let panels = Panels {
+            cpu: true,
+            memory: false,
+            gpu: false,
+            network: true,
+            disk: true,
+            kernel: false,
+            limits: true,
+            numa: true,
+            cache: false,
+            raid: true,
+        };
+        let mut first = sample();
+        for name in ["br-1", "br_1"] {
+            first.network.insert(name.into(), InterfaceSample { rx: 1.0, tx: 2.0 });
+        }
+        let columns = csv_columns(&first, &panels);
+
+        let mut out = vec![];
+        write_csv_header(&mut out, &columns).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().trim_end().split(',').collect::<Vec<_>>(), [
+            "ts_ms", "cpu_usage_pct", "cpu_temp_c", "load_1m", "load_5m", "load_15m",
+            "disk_read_mb_s", "disk_write_mb_s",
+            "md0_working_disks",
+            "net_br_1_rx_mbit_s", "net_br_1_tx_mbit_s", "net_br_1_rx_mbit_s_2", "net_br_1_tx_mbit_s_2",
+            "net_eth0_rx_mbit_s", "net_eth0_tx_mbit_s",
+            "limit_files_pct",
+        ]);
+
+        // A device that vanishes leaves its cells empty.
+        let mut later = first.clone();
+        later.network.remove("eth0");
+        let mut out = vec![];
+        write_csv_row(&mut out, &columns, &later).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "1700000000000,12.5,45,0.5,0.25,0.125,2,0.5,2,1,2,1,2,,,1\n");
+    }
+}
@@ -0,0 +1,271 @@
+//! Embedded Prometheus scrape endpoint.
+//!
+//! A background thread serves `GET /metrics` in the text exposition format
+//! (version 0.0.4), rendered from the most recent `Sample` handed to
+//! `Exporter::publish`. Values are gauges in base units: ratios rather than
+//! percentages, bytes rather than MB.
+
+use crate::core::config::PrometheusConfig;
+use crate::core::error::AppError;
+use crate::metrics::sample::{Header, Sample};
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+/// A scraper that stalls mid-request is dropped after this long.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+const MB: f64 = 1024.0 * 1024.0;
+
+pub struct Exporter {
+    latest: Arc<Mutex<Option<Sample>>>,
+    addr: SocketAddr,
+    /// Tells the serving thread to exit and release the port
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Exporter {
+    /// Bind the configured address and start serving. Binding happens here so
+    /// a taken port is reported at startup rather than lost in the thread.
+    pub fn start(config: &PrometheusConfig, header: Header) -> Result<Self, AppError> {
+        let addr: SocketAddr = config.bind
+            .parse()
+            .map_err(|e| AppError::Config(format!("invalid prometheus bind address {}: {e}", config.bind)))?;
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| io::Error::new(e.kind(), format!("cannot listen on {addr}: {e}")))?;
+
+        let addr = listener.local_addr()?;
+
+        let latest = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (shared, stopped) = (Arc::clone(&latest), Arc::clone(&stop));
+        let thread = thread::Builder::new()
+            .name("prometheus".into())
+            .spawn(move || serve(listener, &header, &shared, &stopped))?;
+        Ok(Self { latest, addr, stop, thread: Some(thread) })
+    }
+
+    /// The address actually listened on
+    pub fn addr(&self) -> SocketAddr { self.addr }
+
+    /// Make `sample` what the next scrape sees.
+    pub fn publish(&self, sample: Sample) {
+        *self.latest.lock().unwrap_or_else(PoisonError::into_inner) = Some(sample);
+    }
+}
+
+impl Drop for Exporter {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wake the thread blocked in accept so it sees the flag, then wait for
+        // it to close the listener so the port can be bound again straight away.
+        let mut wake = self.addr;
+        if wake.ip().is_unspecified() {
+            wake.set_ip(match wake.ip() {
+                IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            });
+        }
+        if TcpStream::connect_timeout(&wake, CLIENT_TIMEOUT).is_ok()
+            && let Some(thread) = self.thread.take()
+        {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Answer scrapes one at a time; they are rare and quick to render.
+fn serve(listener: TcpListener, header: &Header, latest: &Mutex<Option<Sample>>, stop: &AtomicBool) {
+    for stream in listener.incoming() {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        // A misbehaving client only costs its own request.
+        let _ = stream.and_then(|stream| respond(stream, header, latest));
+    }
+}
+
+fn respond(mut stream: TcpStream, header: &Header, latest: &Mutex<Option<Sample>>) -> io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers; nothing in them changes the response.
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = path.split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET" | "HEAD", "/metrics") => {
+            let sample = latest.lock().unwrap_or_else(PoisonError::into_inner).clone();
+            let body = sample.map(|s| render(header, &s)).unwrap_or_default();
+            ("200 OK", CONTENT_TYPE, body)
+        }
+        ("GET" | "HEAD", "/") => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            "<html><body><h1>sysvitals</h1><a href=\"/metrics\">Metrics</a></body></html>\n".to_string(),
+        ),
+        ("GET" | "HEAD", _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain", "method not allowed\n".to_string()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len(),
+    )?;
+    if method != "HEAD" {
+        stream.write_all(body.as_bytes())?;
+    }
+    stream.flush()
+}
+
+/// Exposition text for one metric family; every family is a gauge.
+struct Family<'a> {
+    out: &'a mut String,
+    name: &'a str,
+}
+
+impl<'a> Family<'a> {
+    fn new(out: &'a mut String, name: &'a str, help: &str) -> Self {
+        let _ = writeln!(out, "# HELP sysvitals_{name} {help}");
+        let _ = writeln!(out, "# TYPE sysvitals_{name} gauge");
+        Self { out, name }
+    }
+
+    fn value(&mut self, labels: &[(&str, &str)], value: f64) -> &mut Self {
+        let _ = write!(self.out, "sysvitals_{}", self.name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels.iter().map(|(k, v)| format!("{k}=\"{}\"", escape(v))).collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {}", format_value(value));
+        self
+    }
+}
+
+/// Rust prints `inf` and `NaN`; the exposition format wants `+Inf`, `-Inf` and `NaN`.
+fn format_value(value: f64) -> String {
+    match value {
+        f64::INFINITY => "+Inf".to_string(),
+        f64::NEG_INFINITY => "-Inf".to_string(),
+        v if v.is_nan() => "NaN".to_string(),
+        v => v.to_string(),
+    }
+}
+
+/// Label values escape backslash, double quote and newline.
+fn escape(value: &str) -> String {
+    value.replace('\\', r"\\").replace('"', "\\\"").replace('\n', r"\n")
+}
+
+/// Render `sample` in the Prometheus text format.
+fn render(header: &Header, sample: &Sample) -> String {
+    let mut out = String::new();
+    let unknown = String::new();
+
+    Family::new(&mut out, "info", "Host identity; always 1")
+        .value(&[
+            ("hostname", header.host_name.as_ref().unwrap_or(&unknown)),
+            ("kernel", header.kernel_version.as_ref().unwrap_or(&unknown)),
+            ("os", header.os_version.as_ref().unwrap_or(&unknown)),
+            ("cpu", header.cpu_name.as_ref().unwrap_or(&unknown)),
+        ], 1.0);
+    Family::new(&mut out, "boot_time_seconds", "Host boot time, seconds since the Unix epoch")
+        .value(&[], header.boot_time as f64);
+    Family::new(&mut out, "sample_timestamp_seconds", "When the exported values were sampled, seconds since the Unix epoch")
+        .value(&[], sample.ts as f64 / 1000.0);
+
+    Family::new(&mut out, "cpu_usage_ratio", "Overall CPU utilisation, 0 to 1")
+        .value(&[], sample.cpu.usage / 100.0);
+    if let Some(temperature) = sample.cpu.temperature {
+        Family::new(&mut out, "cpu_temperature_celsius", "CPU package temperature")
+            .value(&[], temperature);
+    }
+    let mut load = Family::new(&mut out, "load_average", "System load average");
+    for (period, value) in ["1m", "5m", "15m"].iter().zip(sample.cpu.load) {
+        load.value(&[("period", period)], value);
+    }
+
+    let memory = &sample.memory;
+    Family::new(&mut out, "memory_used_bytes", "Memory in use")
+        .value(&[], memory.used as f64);
+    Family::new(&mut out, "memory_total_bytes", "Installed memory")
+        .value(&[], memory.total as f64);
+    Family::new(&mut out, "swap_used_bytes", "Swap in use")
+        .value(&[], memory.swap_used as f64);
+    Family::new(&mut out, "swap_total_bytes", "Configured swap")
+        .value(&[], memory.swap_total as f64);
+
+    // Interfaces are sampled in Mb/s.
+    let mut rx = Family::new(&mut out, "network_receive_bytes_per_second", "Bytes received per second");
+    for (interface, stats) in &sample.network {
+        rx.value(&[("interface", interface)], stats.rx * 1e6 / 8.0);
+    }
+    let mut tx = Family::new(&mut out, "network_transmit_bytes_per_second", "Bytes sent per second");
+    for (interface, stats) in &sample.network {
+        tx.value(&[("interface", interface)], stats.tx * 1e6 / 8.0);
+    }
+
+    // Disks are sampled in MB/s.
+    Family::new(&mut out, "disk_read_bytes_per_second", "Bytes read per second, all disks")
+        .value(&[], sample.disk.read * MB);
+    Family::new(&mut out, "disk_written_bytes_per_second", "Bytes written per second, all disks")
+        .value(&[], sample.disk.write * MB);
+
+    if let Some(gpu) = &sample.gpu {
+        let name = [("gpu", header.gpu_name.as_ref().unwrap_or(&unknown).as_str())];
+        Family::new(&mut out, "gpu_usage_ratio", "GPU utilisation, 0 to 1")
+            .value(&name, gpu.usage / 100.0);
+        Family::new(&mut out, "gpu_memory_used_ratio", "Share of GPU memory in use, 0 to 1")
+            .value(&name, gpu.memory / 100.0);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::network::InterfaceSample;
+    use crate::metrics::sample::test_support::{header, sample};
+
+    #[test]
+    fn test_render() {
+        let header = Header { kernel_version: Some("6.1 \"lts\"\\\nbuild".into()), ..header() };
+        let mut sample = sample();
+        sample.network.insert("wg\"0".into(), InterfaceSample { rx: 0.0, tx: 0.0 });
+        sample.cpu.load = [f64::INFINITY, f64::NEG_INFINITY, f64::NAN];
+        let text = render(&header, &sample);
+        let lines: Vec<&str> = text.lines().collect();
+
+        // Every family is announced with HELP and TYPE before its values.
+        let at = |line: &str| lines.iter().position(|l| *l == line).unwrap();
+        assert!(at("# HELP sysvitals_cpu_usage_ratio Overall CPU utilisation, 0 to 1") < at("# TYPE sysvitals_cpu_usage_ratio gauge"));
+        assert!(at("# TYPE sysvitals_cpu_usage_ratio gauge") < at("sysvitals_cpu_usage_ratio 0.125"));
+        let families = lines.iter().filter(|l| l.starts_with("# TYPE ")).count();
+        assert_eq!(lines.iter().filter(|l| l.starts_with("# HELP ")).count(), families);
+
+        // 8 Mb/s received, 2 MB/s read
+        assert!(lines.contains(&"sysvitals_network_receive_bytes_per_second{interface=\"eth0\"} 1000000"));
+        assert!(lines.contains(&"sysvitals_disk_read_bytes_per_second 2097152"));
+        assert!(lines.contains(&r#"sysvitals_network_receive_bytes_per_second{interface="wg\"0"} 0"#));
+        // Non-finite values use the exposition format's spelling.
+        assert!(lines.contains(&r#"sysvitals_load_average{period="1m"} +Inf"#));
+        assert!(lines.contains(&r#"sysvitals_load_average{period="5m"} -Inf"#));
+        assert!(lines.contains(&r#"sysvitals_load_average{period="15m"} NaN"#));
+        assert!(lines.contains(&r#"sysvitals_info{hostname="box",kernel="6.1 \"lts\"\\\nbuild",os="",cpu=""} 1"#));
+    }
+}
@@ -0,0 +1,134 @@
+//! Append-only on-disk recording of metric samples.
+//!
+//! Each file is JSON lines: a `Header` first, then one `Sample` per refresh.
+//! Files are named after the millisecond they were started, so sorting by
+//! name sorts by age. A new file is begun at startup and whenever the current
+//! one reaches `max_file_mb`; old files are pruned by age and total size.
+
+use crate::core::config::RecordingConfig;
+use crate::core::error::AppError;
+use crate::metrics::sample::{Header, Sample};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const EXTENSION: &str = "jsonl";
+const MB: u64 = 1024 * 1024;
+/// Age-based pruning runs at most this often between rotations.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Where recorded samples go: JSON-lines files here, or SQLite with
+/// `backend = "sqlite"` (see `core::database`)
+pub trait SampleStore {
+    fn record(&mut self, sample: &Sample) -> Result<(), AppError>;
+}
+
+pub struct Recorder {
+    dir: PathBuf,
+    header: Header,
+    file: File,
+    path: PathBuf,
+    written: u64,
+    max_file_bytes: u64,
+    max_total_bytes: u64,
+    retention: Duration,
+    last_prune: Instant,
+}
+
+impl Recorder {
+    /// Start a new recording file under the configured directory.
+    pub fn new(config: &RecordingConfig, header: Header) -> Result<Self, AppError> {
+        let dir = config.dir();
+        fs::create_dir_all(&dir)?;
+        let (file, path, written) = start_file(&dir, &header)?;
+
+        let mut recorder = Self {
+            dir,
+            header,
+            file,
+            path,
+            written,
+            max_file_bytes: config.max_file_mb.max(1) * MB,
+            max_total_bytes: config.max_total_mb.max(1) * MB,
+            retention: Duration::from_secs(config.retention_hours * 3600),
+            last_prune: Instant::now(),
+        };
+        recorder.prune()?;
+        Ok(recorder)
+    }
+
+    /// Delete recordings past the retention period, then the oldest ones until
+    /// the total fits under the size cap. The file being written is kept.
+    fn prune(&mut self) -> Result<(), AppError> {
+        self.last_prune = Instant::now();
+        let now = SystemTime::now();
+
+        let mut total = 0;
+        let mut kept = vec![];
+        for path in recordings(&self.dir)? {
+            let meta = fs::metadata(&path)?;
+            let age = meta.modified().ok().and_then(|m| now.duration_since(m).ok()).unwrap_or_default();
+            if path != self.path && !self.retention.is_zero() && age > self.retention {
+                fs::remove_file(&path)?;
+            } else {
+                total += meta.len();
+                kept.push((path, meta.len()));
+            }
+        }
+
+        for (path, len) in kept {
+            if total <= self.max_total_bytes || path == self.path {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total -= len;
+        }
+        Ok(())
+    }
+}
+
+impl SampleStore for Recorder {
+    /// Append one sample, rotating and pruning as needed.
+    fn record(&mut self, sample: &Sample) -> Result<(), AppError> {
+        let line = to_line(sample)?;
+
+        if self.written + line.len() as u64 > self.max_file_bytes {
+            (self.file, self.path, self.written) = start_file(&self.dir, &self.header)?;
+            self.prune()?;
+        } else if self.last_prune.elapsed() >= PRUNE_INTERVAL {
+            self.prune()?;
+        }
+
+        // One write per line so a crash can at worst truncate the last sample.
+        self.file.write_all(&line)?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+}
+
+/// Recording files in `dir`, oldest first.
+fn recordings(dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == EXTENSION))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+fn start_file(dir: &Path, header: &Header) -> Result<(File, PathBuf, u64), AppError> {
+    let started = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis());
+    let path = dir.join(format!("sysvitals-{started}.{EXTENSION}"));
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let line = to_line(header)?;
+    file.write_all(&line)?;
+    Ok((file, path, line.len() as u64))
+}
+
+fn to_line<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, AppError> {
+    let mut line = serde_json::to_vec(value).map_err(|e| AppError::System(format!("cannot encode sample: {e}")))?;
+    line.push(b'\n');
+    Ok(line)
+}
@@ -0,0 +1,194 @@
+//! Playback of a recording written by `core::recorder`, as a `MetricsSource`.
+
+use crate::core::error::AppError;
+use crate::metrics::SystemMetrics;
+use crate::metrics::sample::{FORMAT_VERSION, Header, Sample};
+use crate::metrics::source::{MetricsSource, Playback, PlaybackCommand};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const SPEEDS: [u32; 7] = [1, 2, 4, 8, 16, 32, 64];
+
+/// Gaps longer than this (recording stopped, machine asleep) play back as this long.
+const MAX_GAP: Duration = Duration::from_secs(5);
+
+pub struct Replay {
+    header: Header,
+    samples: Vec<Sample>,
+    system: SystemMetrics,
+    /// Where the first sample sits on the monotonic clock; the rest follow
+    /// at their recorded offsets, gaps included
+    origin: Instant,
+    /// Typical spacing between recorded samples
+    interval: Duration,
+    /// History window chosen by the user
+    window: Duration,
+    position: usize,
+    playing: bool,
+    speed_idx: usize,
+    last_tick: Instant,
+    /// Recorded time played since `position` was reached
+    carry: Duration,
+    changed: bool,
+}
+
+impl Replay {
+    /// Load a whole recording into memory and position it at the first sample.
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines();
+
+        let header: Header = lines
+            .next()
+            .and_then(|line| serde_json::from_str(line).ok())
+            .ok_or_else(|| AppError::Config(format!("{} is not a sysvitals recording", path.display())))?;
+        if header.version > FORMAT_VERSION {
+            return Err(AppError::Config(format!(
+                "recording format v{} is newer than this build supports (v{FORMAT_VERSION})",
+                header.version,
+            )));
+        }
+
+        // A crash can leave the last line truncated; skip anything unreadable.
+        let samples: Vec<Sample> = lines.filter_map(|line| serde_json::from_str(line).ok()).collect();
+        let Some(first) = samples.first() else {
+            return Err(AppError::Config(format!("{} has no samples", path.display())));
+        };
+
+        let origin = Instant::now();
+        let system = SystemMetrics::from_sample(&header, first, origin);
+        let interval = typical_interval(&samples);
+
+        Ok(Self {
+            header,
+            samples,
+            system,
+            origin,
+            interval,
+            window: Duration::ZERO,
+            position: 0,
+            playing: true,
+            speed_idx: 0,
+            last_tick: Instant::now(),
+            carry: Duration::ZERO,
+            changed: true,
+        })
+    }
+
+    /// Show the sample at `index`. Moving forward by one is incremental; any
+    /// other jump rebuilds the histories from the window leading up to it.
+    fn show(&mut self, index: usize) {
+        let index = index.min(self.samples.len() - 1);
+        if index == self.position + 1 {
+            self.system.apply_sample(&self.samples[index], self.time_of(index));
+        } else {
+            let start = (index + 1).saturating_sub(SystemMetrics::history_len(self.interval, self.window));
+            let mut system = SystemMetrics::from_sample(&self.header, &self.samples[start], self.time_of(start));
+            system.resize_history(self.interval, self.window);
+            for i in start + 1..=index {
+                system.apply_sample(&self.samples[i], self.time_of(i));
+            }
+            self.system = system;
+        }
+        self.position = index;
+        self.changed = true;
+    }
+
+    fn seek_to(&mut self, index: usize) {
+        self.show(index);
+        self.carry = Duration::ZERO;
+    }
+
+    /// Monotonic time standing in for when the sample at `index` was taken
+    fn time_of(&self, index: usize) -> Instant {
+        self.origin + Duration::from_millis(self.samples[index].ts.saturating_sub(self.samples[0].ts))
+    }
+
+    /// Recorded time between the sample at `index` and the next one
+    fn gap_after(&self, index: usize) -> Duration {
+        let gap = self.samples[index + 1].ts.saturating_sub(self.samples[index].ts);
+        Duration::from_millis(gap).min(MAX_GAP)
+    }
+
+    fn at_end(&self) -> bool {
+        self.position + 1 >= self.samples.len()
+    }
+}
+
+impl MetricsSource for Replay {
+    fn metrics(&self) -> &SystemMetrics { &self.system }
+
+    fn tick(&mut self, _interval: Duration) -> Result<bool, AppError> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_tick);
+        self.last_tick = now;
+
+        if self.playing {
+            self.carry += elapsed * SPEEDS[self.speed_idx];
+            while !self.at_end() && self.carry >= self.gap_after(self.position) {
+                self.carry -= self.gap_after(self.position);
+                self.show(self.position + 1);
+            }
+            if self.at_end() {
+                self.playing = false;
+            }
+        }
+        Ok(std::mem::take(&mut self.changed))
+    }
+
+    /// History length follows the recorded spacing, not the refresh interval.
+    /// The histories are rebuilt so a wider window fills in straight away.
+    fn resize_history(&mut self, _sample_interval: Duration, window: Duration) {
+        if window != self.window {
+            self.window = window;
+            self.seek_to(self.position);
+        }
+    }
+
+    fn playback(&self) -> Option<Playback> {
+        Some(Playback {
+            playing: self.playing,
+            speed: SPEEDS[self.speed_idx],
+            position: self.position,
+            len: self.samples.len(),
+            timestamp: self.samples[self.position].ts,
+        })
+    }
+
+    fn control(&mut self, command: PlaybackCommand) -> bool {
+        let last = self.samples.len() - 1;
+        match command {
+            PlaybackCommand::TogglePause => {
+                if !self.playing && self.at_end() {
+                    self.seek_to(0);
+                }
+                self.playing = !self.playing;
+                self.last_tick = Instant::now();
+            }
+            PlaybackCommand::Faster => self.speed_idx = (self.speed_idx + 1).min(SPEEDS.len() - 1),
+            PlaybackCommand::Slower => self.speed_idx = self.speed_idx.saturating_sub(1),
+            PlaybackCommand::Step(n) => {
+                self.playing = false;
+                self.seek_to((self.position as i64 + n).clamp(0, last as i64) as usize);
+            }
+            PlaybackCommand::Seek(secs) => {
+                let target = self.samples[self.position].ts as i64 + secs * 1000;
+                let index = self.samples.partition_point(|s| (s.ts as i64) < target);
+                self.seek_to(index.min(last));
+            }
+            PlaybackCommand::Start => self.seek_to(0),
+            PlaybackCommand::End => self.seek_to(last),
+        }
+        self.changed = true;
+        true
+    }
+}
+
+/// Median spacing between samples, so one long gap doesn't skew it.
+fn typical_interval(samples: &[Sample]) -> Duration {
+    let mut gaps: Vec<u64> = samples.windows(2).map(|w| w[1].ts.saturating_sub(w[0].ts)).collect();
+    gaps.sort_unstable();
+    let median = gaps.get(gaps.len() / 2).copied().unwrap_or(1000);
+    Duration::from_millis(median.max(100))
+}
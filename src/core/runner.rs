@@ -1,5 +1,5 @@
 use crate::core::app::App;
-use crossterm::event::{self, Event};
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
@@ -11,7 +11,7 @@ pub fn run(mut app: App) -> Result<(), Box<dyn std::error::Error>> {
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
         let _ = disable_raw_mode();
-        let _ = crossterm::execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = crossterm::execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen);
         original_hook(panic_info);
     }));
 
@@ -27,12 +27,17 @@ fn run_loop(
 ) -> Result<(), Box<dyn std::error::Error>> {
     loop {
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                app.handle_event(Event::Key(key))?;
-                if app.should_quit() {
+            // Take everything queued before redrawing, so a burst of mouse
+            // movement doesn't leave the cursor trailing behind the pointer.
+            loop {
+                app.handle_event(event::read()?)?;
+                if app.should_quit() || !event::poll(Duration::ZERO)? {
                     break;
                 }
             }
+            if app.should_quit() {
+                break;
+            }
         }
 
         app.update()?;
@@ -47,13 +52,13 @@ fn run_loop(
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>, io::Error> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    crossterm::execute!(stdout, EnterAlternateScreen)?;
+    crossterm::execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     Terminal::new(backend)
 }
 
 fn cleanup_terminal() -> Result<(), io::Error> {
     disable_raw_mode()?;
-    crossterm::execute!(io::stdout(), LeaveAlternateScreen)?;
+    crossterm::execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen)?;
     Ok(())
 }
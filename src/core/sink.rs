@@ -0,0 +1,505 @@
+//! Push-style outputs. Each sample is encoded into lines and handed to a
+//! background thread that batches writes and retries failed ones, so a slow or
+//! missing endpoint never holds up collection or the UI.
+//!
+//! Formats are in `core::influx`, `core::statsd` and `core::otlp`; the first
+//! two start from the flat list of values `points` makes of a sample.
+
+use crate::metrics::sample::{Header, Sample};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Wait before the first retry; doubled for each one after.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+/// Connect, read and write timeout for HTTP endpoints
+const HTTP_TIMEOUT: Duration = Duration::from_secs(2);
+/// Samples a sink holds while its endpoint is unreachable
+const QUEUE_LENGTH: usize = 600;
+/// Keeps datagrams under a typical Ethernet MTU so they aren't fragmented.
+const MAX_DATAGRAM: usize = 1432;
+
+// ── Values ───────────────────────────────────────────────────────────────────
+
+/// One value of a sample, flattened for line-oriented formats
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    /// Collector, e.g. `cpu` or `net`
+    pub group: &'static str,
+    pub name: &'static str,
+    /// Which device a per-device value belongs to, e.g. `("interface", "eth0")`
+    pub label: Option<(&'static str, String)>,
+    pub value: f64,
+    /// Byte sizes and counts, written as integers where the format has them
+    pub integer: bool,
+}
+
+/// The values a sample carries, grouped by collector and device. Units
+/// follow the names as in CSV output: `pct`, `bytes`, `mbit_s`, `mb_s`, `per_s`.
+pub fn points(sample: &Sample) -> Vec<Point> {
+    let mut points = vec![];
+    let mut add = |group, name, label: Option<(&'static str, &str)>, value: f64, integer| {
+        if value.is_finite() {
+            points.push(Point { group, name, label: label.map(|(k, v)| (k, v.to_string())), value, integer });
+        }
+    };
+
+    let cpu = &sample.cpu;
+    add("cpu", "usage_pct", None, cpu.usage, false);
+    if let Some(temperature) = cpu.temperature {
+        add("cpu", "temperature_c", None, temperature, false);
+    }
+    add("cpu", "load_1m", None, cpu.load[0], false);
+    add("cpu", "load_5m", None, cpu.load[1], false);
+    add("cpu", "load_15m", None, cpu.load[2], false);
+
+    let memory = &sample.memory;
+    add("memory", "used_bytes", None, memory.used as f64, true);
+    add("memory", "total_bytes", None, memory.total as f64, true);
+    add("memory", "swap_used_bytes", None, memory.swap_used as f64, true);
+    add("memory", "swap_total_bytes", None, memory.swap_total as f64, true);
+    if let Some(paging) = &memory.paging {
+        add("paging", "swap_in_per_s", None, paging.swap_in, false);
+        add("paging", "swap_out_per_s", None, paging.swap_out, false);
+        add("paging", "faults_per_s", None, paging.faults, false);
+        add("paging", "major_faults_per_s", None, paging.major_faults, false);
+        add("paging", "oom_kills", None, paging.oom_kills as f64, true);
+    }
+
+    for (interface, rates) in &sample.network {
+        add("net", "rx_mbit_s", Some(("interface", interface)), rates.rx, false);
+        add("net", "tx_mbit_s", Some(("interface", interface)), rates.tx, false);
+    }
+    add("disk", "read_mb_s", None, sample.disk.read, false);
+    add("disk", "write_mb_s", None, sample.disk.write, false);
+
+    if let Some(gpu) = &sample.gpu {
+        add("gpu", "usage_pct", None, gpu.usage, false);
+        add("gpu", "memory_pct", None, gpu.memory, false);
+    }
+    if let Some(kernel) = &sample.kernel {
+        add("kernel", "context_switches_per_s", None, kernel.context_switches, false);
+        add("kernel", "interrupts_per_s", None, kernel.interrupts, false);
+        add("kernel", "forks_per_s", None, kernel.forks, false);
+        add("kernel", "procs_running", None, kernel.procs_running, false);
+        add("kernel", "procs_blocked", None, kernel.procs_blocked, false);
+    }
+
+    let host = &sample.host;
+    add("host", "uptime_s", None, host.uptime as f64, true);
+    add("host", "processes", None, host.processes as f64, true);
+    add("host", "threads", None, host.threads as f64, true);
+    points
+}
+
+/// Tags for every value: `host` from the header unless configured, then the rest.
+pub fn tags(header: &Header, configured: &BTreeMap<String, String>) -> Vec<(String, String)> {
+    let mut tags = configured.clone();
+    if let Some(host) = &header.host_name {
+        tags.entry("host".to_string()).or_insert_with(|| host.clone());
+    }
+    tags.into_iter().collect()
+}
+
+// ── Delivery ─────────────────────────────────────────────────────────────────
+
+/// Turns a sample into the lines a sink writes
+pub type Encode = Box<dyn FnMut(&Sample) -> Vec<String> + Send>;
+
+/// Where a sink's lines end up
+pub trait Transport: Send {
+    /// Deliver `lines` in one go; on error none of them count as delivered.
+    fn send(&mut self, lines: &[String]) -> io::Result<()>;
+}
+
+/// How a sink groups and retries writes
+#[derive(Debug, Clone, Copy)]
+pub struct Delivery {
+    /// Samples per write
+    pub batch_size: usize,
+    /// Further attempts after a failed write before the batch is dropped
+    pub retries: u32,
+    /// Log dropped batches to stderr; off while the TUI owns the terminal
+    pub report_errors: bool,
+}
+
+/// A running output. Dropping it flushes what's queued, trying each batch
+/// once and giving up on the rest at the first failure.
+pub struct Sink {
+    sender: Option<SyncSender<Sample>>,
+    /// Shared with the worker so a full queue can make room by dropping its oldest sample
+    receiver: Arc<Mutex<Receiver<Sample>>>,
+    stopping: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Sink {
+    pub fn start(name: &str, mut encode: Encode, transport: Box<dyn Transport>, delivery: Delivery) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_LENGTH);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let stopping = Arc::new(AtomicBool::new(false));
+        let mut worker = Worker { transport, delivery, label: name.to_string(), stopping: stopping.clone() };
+        let queue = receiver.clone();
+        let thread = thread::Builder::new()
+            .name(name.into())
+            .spawn(move || {
+                let mut lines = vec![];
+                let mut batched = 0;
+                loop {
+                    // Released before delivering, so `send` can make room meanwhile.
+                    let next = queue.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                    // Ends once the sink is dropped and the queue is drained.
+                    let Ok(sample) = next else { break };
+                    lines.extend(encode(&sample));
+                    batched += 1;
+                    if batched >= delivery.batch_size.max(1) {
+                        if !worker.deliver(&lines) {
+                            return;
+                        }
+                        lines.clear();
+                        batched = 0;
+                    }
+                }
+                if !lines.is_empty() {
+                    worker.deliver(&lines);
+                }
+            })?;
+        Ok(Self { sender: Some(sender), receiver, stopping, thread: Some(thread) })
+    }
+
+    /// Queue `sample` for the next batch. While an endpoint is down the
+    /// queue fills up; then the oldest sample makes way for the new one.
+    pub fn send(&self, sample: Sample) {
+        let Some(sender) = &self.sender else { return };
+        if let Err(TrySendError::Full(sample)) = sender.try_send(sample) {
+            // The worker only holds the lock while receiving, which frees a slot anyway.
+            if let Ok(receiver) = self.receiver.try_lock() {
+                let _ = receiver.try_recv();
+            }
+            let _ = sender.try_send(sample);
+        }
+    }
+}
+
+impl Drop for Sink {
+    fn drop(&mut self) {
+        // Shutting down: no retries, and nothing more once a write fails, so
+        // quitting or reloading waits on at most one unreachable endpoint.
+        self.stopping.store(true, Ordering::Relaxed);
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+struct Worker {
+    transport: Box<dyn Transport>,
+    delivery: Delivery,
+    label: String,
+    stopping: Arc<AtomicBool>,
+}
+
+impl Worker {
+    /// Send `lines`, backing off between attempts; a batch that still fails
+    /// is dropped. Once the sink is shutting down there are no more retries,
+    /// and `false` says to drop the rest of the queue as well.
+    fn deliver(&mut self, lines: &[String]) -> bool {
+        let mut delay = RETRY_DELAY;
+        let mut attempt = 0;
+        loop {
+            match self.transport.send(lines) {
+                Ok(()) => return true,
+                Err(e) if self.stopping.load(Ordering::Relaxed) => {
+                    if self.delivery.report_errors {
+                        eprintln!("sysvitals: {} dropped {} lines and the rest of its queue on exit: {e}", self.label, lines.len());
+                    }
+                    return false;
+                }
+                Err(e) if attempt >= self.delivery.retries => {
+                    if self.delivery.report_errors {
+                        eprintln!("sysvitals: {} dropped {} lines after {} attempts: {e}", self.label, lines.len(), attempt + 1);
+                    }
+                    return true;
+                }
+                Err(_) => {
+                    // Parked rather than slept, so dropping the sink cuts the wait short.
+                    let until = Instant::now() + delay;
+                    while !self.stopping.load(Ordering::Relaxed) && let Some(left) = until.checked_duration_since(Instant::now()) {
+                        thread::park_timeout(left);
+                    }
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+// ── Transports ───────────────────────────────────────────────────────────────
+
+pub struct Stdout;
+
+impl Transport for Stdout {
+    fn send(&mut self, lines: &[String]) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+        for line in lines {
+            writeln!(out, "{line}")?;
+        }
+        out.flush()
+    }
+}
+
+/// Appends to a file, creating it if needed
+pub struct Append {
+    file: File,
+}
+
+impl Append {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Ok(Self { file: OpenOptions::new().create(true).append(true).open(path)? })
+    }
+}
+
+impl Transport for Append {
+    fn send(&mut self, lines: &[String]) -> io::Result<()> {
+        // One write per batch, so a crash can't interleave half a batch.
+        let mut batch = lines.join("\n");
+        batch.push('\n');
+        self.file.write_all(batch.as_bytes())
+    }
+}
+
+/// POSTs to a plain `http://` URL; as a transport, each batch goes newline separated
+pub struct HttpPost {
+    /// `host:port` for the connection and the Host header
+    authority: String,
+    /// Path and query
+    path: String,
+    content_type: &'static str,
+    /// Extra request headers such as Authorization
+    headers: Vec<(String, String)>,
+}
+
+impl HttpPost {
+    /// `url` must be `http://host[:port][/path]`; TLS is left to a local relay.
+    pub fn new(url: &str, content_type: &'static str, headers: Vec<(String, String)>) -> Result<Self, String> {
+        let rest = url.strip_prefix("http://").ok_or_else(|| {
+            format!("{url}: only http:// URLs are supported; use a local relay for https")
+        })?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, "/"),
+        };
+        if authority.is_empty() {
+            return Err(format!("{url}: missing host"));
+        }
+        let authority = if authority.contains(':') { authority.to_string() } else { format!("{authority}:80") };
+        Ok(Self { authority, path: path.to_string(), content_type, headers })
+    }
+
+    /// Send one request; anything but a 2xx answer is an error.
+    pub fn post(&self, body: &str) -> io::Result<()> {
+        let addr = self.authority
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::other(format!("cannot resolve {}", self.authority)))?;
+        let mut stream = TcpStream::connect_timeout(&addr, HTTP_TIMEOUT)?;
+        stream.set_read_timeout(Some(HTTP_TIMEOUT))?;
+        stream.set_write_timeout(Some(HTTP_TIMEOUT))?;
+
+        let headers: String = self.headers.iter().map(|(name, value)| format!("{name}: {value}\r\n")).collect();
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{headers}Connection: close\r\n\r\n",
+            self.path,
+            self.authority,
+            self.content_type,
+            body.len(),
+        )?;
+        stream.write_all(body.as_bytes())?;
+
+        let mut status = String::new();
+        BufReader::new(&stream).read_line(&mut status)?;
+        match status.split_whitespace().nth(1).and_then(|code| code.parse::<u16>().ok()) {
+            Some(200..=299) => Ok(()),
+            _ => Err(io::Error::other(format!("{} answered {:?}", self.authority, status.trim()))),
+        }
+    }
+}
+
+impl Transport for HttpPost {
+    fn send(&mut self, lines: &[String]) -> io::Result<()> {
+        let mut body = lines.join("\n");
+        body.push('\n');
+        self.post(&body)
+    }
+}
+
+/// Sends lines over UDP, as many per datagram as fit
+pub struct Datagrams {
+    socket: UdpSocket,
+    target: SocketAddr,
+}
+
+impl Datagrams {
+    pub fn new(address: &str) -> io::Result<Self> {
+        let target = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::other(format!("cannot resolve {address}")))?;
+        let local: SocketAddr = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }.parse().expect("valid literal");
+        Ok(Self { socket: UdpSocket::bind(local)?, target })
+    }
+}
+
+impl Transport for Datagrams {
+    fn send(&mut self, lines: &[String]) -> io::Result<()> {
+        let mut datagram = String::new();
+        for line in lines {
+            if !datagram.is_empty() && datagram.len() + 1 + line.len() > MAX_DATAGRAM {
+                self.socket.send_to(datagram.as_bytes(), self.target)?;
+                datagram.clear();
+            }
+            if !datagram.is_empty() {
+                datagram.push('\n');
+            }
+            datagram.push_str(line);
+        }
+        if !datagram.is_empty() {
+            self.socket.send_to(datagram.as_bytes(), self.target)?;
+        }
+        Ok(())
+    }
+}
+
+/// A stand-in HTTP endpoint for tests of the outputs that POST
+#[cfg(test)]
+pub mod test_support {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// What the endpoint received in one request
+    pub struct Request {
+        /// Request line and headers
+        pub head: String,
+        pub body: String,
+    }
+
+    /// Listens on a free local port and answers one request per entry of
+    /// `statuses`, in order, then hands back the requests. Returns the
+    /// endpoint's `http://host:port` base.
+    pub fn endpoint(statuses: &'static [&'static str]) -> (String, JoinHandle<Vec<Request>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            statuses
+                .iter()
+                .map(|status| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(&stream);
+                    let (mut head, mut length) = (String::new(), 0);
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap() <= 2 {
+                            break;
+                        }
+                        if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                            length = value.trim().parse().unwrap();
+                        }
+                        head.push_str(&line);
+                    }
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+                    write!(stream, "HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n").unwrap();
+                    Request { head, body: String::from_utf8(body).unwrap() }
+                })
+                .collect()
+        });
+        (base, server)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::sample::test_support::sample;
+    use std::sync::mpsc::Sender;
+
+    /// Fails the first write, after holding it until `release` fires
+    struct Stalled {
+        entered: Sender<()>,
+        release: Receiver<()>,
+        attempts: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Transport for Stalled {
+        fn send(&mut self, lines: &[String]) -> io::Result<()> {
+            let first = self.attempts.lock().unwrap().is_empty();
+            if first {
+                self.entered.send(()).unwrap();
+                self.release.recv().unwrap();
+            }
+            self.attempts.lock().unwrap().extend_from_slice(lines);
+            if first { Err(io::Error::other("unreachable")) } else { Ok(()) }
+        }
+    }
+
+    #[test]
+    fn test_full_queue_drops_oldest() {
+        let (entered, entered_rx) = mpsc::channel();
+        let (release, release_rx) = mpsc::channel();
+        let attempts = Arc::new(Mutex::new(vec![]));
+        let transport = Stalled { entered, release: release_rx, attempts: attempts.clone() };
+        let delivery = Delivery { batch_size: 1, retries: 5, report_errors: false };
+        let sink = Sink::start("test", Box::new(|sample| vec![sample.ts.to_string()]), Box::new(transport), delivery).unwrap();
+
+        let at = |ts| Sample { ts, ..sample() };
+        sink.send(at(0));
+        entered_rx.recv().unwrap();
+        // The worker is stuck on the first batch; overfill the queue behind it.
+        for ts in 1..=QUEUE_LENGTH as u64 + 5 {
+            sink.send(at(ts));
+        }
+        release.send(()).unwrap();
+        // The first batch is retried, then the queue drains from the oldest sample still held.
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while attempts.lock().unwrap().len() < QUEUE_LENGTH + 2 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        drop(sink);
+
+        let attempts = attempts.lock().unwrap();
+        assert_eq!(attempts.len(), QUEUE_LENGTH + 2);
+        assert_eq!(attempts[..3], ["0", "0", "6"]);
+        assert_eq!(attempts.last().unwrap(), &(QUEUE_LENGTH + 5).to_string());
+    }
+
+    #[test]
+    fn test_shutdown_gives_up_on_silent_endpoint() {
+        // Connections complete in the backlog, but no request is ever answered.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let transport = HttpPost::new(&url, "text/plain", vec![]).unwrap();
+        let delivery = Delivery { batch_size: 1, retries: 5, report_errors: false };
+        let sink = Sink::start("test", Box::new(|sample| vec![sample.ts.to_string()]), Box::new(transport), delivery).unwrap();
+        for ts in 0..20 {
+            sink.send(Sample { ts, ..sample() });
+        }
+
+        // Trying each of the 20 batches once would take 20 read timeouts.
+        let start = Instant::now();
+        drop(sink);
+        assert!(start.elapsed() < HTTP_TIMEOUT * 2);
+        drop(listener);
+    }
+}
@@ -0,0 +1,88 @@
+//! StatsD output: every value as a gauge, sent over UDP.
+//!
+//! Names are `<prefix>.<group>[.<device>].<name>`, e.g.
+//! `sysvitals.net.eth0.rx_mbit_s`, so per-device values stay distinct even
+//! when the server ignores tags. Tags follow `StatsdConfig::tag_style`.
+
+use crate::core::config::{StatsdConfig, TagStyle};
+use crate::core::error::AppError;
+use crate::core::sink::{self, Datagrams, Delivery, Point, Sink};
+use crate::metrics::sample::Header;
+
+pub fn start(config: &StatsdConfig, header: &Header, report_errors: bool) -> Result<Sink, AppError> {
+    let transport = Datagrams::new(&config.address)
+        .map_err(|e| AppError::Config(format!("invalid statsd address {}: {e}", config.address)))?;
+    let tags = sink::tags(header, &config.tags)
+        .into_iter()
+        .map(|(key, value)| (sanitize(&key), sanitize(&value)))
+        .collect::<Vec<_>>();
+    let (prefix, style) = (config.prefix.clone(), config.tag_style);
+    let delivery = Delivery { batch_size: config.batch_size, retries: config.retries, report_errors };
+    Ok(Sink::start("statsd", Box::new(move |sample| encode(&sink::points(sample), &prefix, &tags, style)), Box::new(transport), delivery)?)
+}
+
+fn encode(points: &[Point], prefix: &str, tags: &[(String, String)], style: TagStyle) -> Vec<String> {
+    let (name_tags, suffix_tags) = match style {
+        TagStyle::DogStatsd if !tags.is_empty() => {
+            let tags: Vec<String> = tags.iter().map(|(k, v)| format!("{k}:{v}")).collect();
+            (String::new(), format!("|#{}", tags.join(",")))
+        }
+        TagStyle::Telegraf => (tags.iter().map(|(k, v)| format!(",{k}={v}")).collect(), String::new()),
+        _ => (String::new(), String::new()),
+    };
+    points
+        .iter()
+        .map(|point| {
+            let mut name = prefix.to_string();
+            for part in [Some(point.group), point.label.as_ref().map(|(_, device)| device.as_str()), Some(point.name)].into_iter().flatten() {
+                if !name.is_empty() {
+                    name.push('.');
+                }
+                name.push_str(&sanitize(part));
+            }
+            // A leading sign would make the gauge a delta; values are never negative.
+            format!("{name}{name_tags}:{}|g{suffix_tags}", point.value)
+        })
+        .collect()
+}
+
+/// Keep names and tags clear of the protocol's separators.
+fn sanitize(value: &str) -> String {
+    value.chars().map(|c| if matches!(c, ':' | '|' | '@' | '#' | ',' | '=' | ' ' | '\n') { '_' } else { c }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::sample::test_support::{header, sample};
+    use std::net::UdpSocket;
+    use std::time::Duration;
+
+    #[test]
+    fn test_encode_tag_styles() {
+        let points = [
+            Point { group: "cpu", name: "usage_pct", label: None, value: 12.5, integer: false },
+            Point { group: "net", name: "rx_mbit_s", label: Some(("interface", "eth:0".into())), value: 3.0, integer: false },
+        ];
+        let tags = [("host".to_string(), "box".to_string())];
+        assert_eq!(encode(&points, "sv", &tags, TagStyle::DogStatsd), ["sv.cpu.usage_pct:12.5|g|#host:box", "sv.net.eth_0.rx_mbit_s:3|g|#host:box"]);
+        assert_eq!(encode(&points, "sv", &tags, TagStyle::Telegraf), ["sv.cpu.usage_pct,host=box:12.5|g", "sv.net.eth_0.rx_mbit_s,host=box:3|g"]);
+        assert_eq!(encode(&points, "", &tags, TagStyle::None), ["cpu.usage_pct:12.5|g", "net.eth_0.rx_mbit_s:3|g"]);
+    }
+
+    #[test]
+    fn test_sends_gauges_over_udp() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let config = StatsdConfig { enabled: true, address: server.local_addr().unwrap().to_string(), ..Default::default() };
+        let sink = start(&config, &header(), false).unwrap();
+        sink.send(sample());
+
+        let mut buf = [0; 2048];
+        let len = server.recv(&mut buf).unwrap();
+        assert!(len <= 1432);
+        let datagram = std::str::from_utf8(&buf[..len]).unwrap();
+        assert_eq!(datagram.lines().next(), Some("sysvitals.cpu.usage_pct:12.5|g|#host:box"));
+        assert!(datagram.lines().all(|line| line.contains("|g|#host:box")));
+    }
+}
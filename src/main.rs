@@ -2,14 +2,28 @@ mod core;
 mod metrics;
 mod ui;
 
-use core::{App, run};
-use core::args::CliArgs;
+use core::{App, daemon, database, output, run};
+use core::args::{CliArgs, Command};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = CliArgs::parse().unwrap_or_else(|e| {
         eprintln!("error: {e}\nRun with --help for usage.");
         std::process::exit(1);
     });
+    if let Some(command) = args.command {
+        match command {
+            Command::Snapshot => output::snapshot(&args)?,
+            Command::Stream => output::stream(&args)?,
+            Command::Query => database::query(&args)?,
+        }
+        return Ok(());
+    }
+
     let app = App::new(&args)?;
-    run(app)
+    if args.headless {
+        let pidfile = args.pidfile.clone().unwrap_or_else(daemon::default_pidfile);
+        daemon::run(app, &pidfile)
+    } else {
+        run(app)
+    }
 }
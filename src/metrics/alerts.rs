@@ -0,0 +1,345 @@
+//! Threshold alerts over sampled values.
+//!
+//! A rule reads `<metric> <op> <threshold> [for <duration>]`, e.g.
+//! `cpu.usage > 90 for 30s`, with metrics named as in `sysvitals query`.
+//! Each rule moves through pending (breached, waiting out `for`), firing and
+//! resolved (shown for a while after clearing). `hysteresis` makes a firing
+//! alert clear only once the value is that far back past the threshold, so a
+//! value hovering at the limit doesn't flap.
+//!
+//! Rules are evaluated against each `Sample` and timed by sample timestamps,
+//! so a replay shows when rules would have fired.
+
+use crate::metrics::sample::{Metric, Sample, metric_name, parse_window};
+use serde::{Deserialize, Serialize};
+
+/// How long a resolved alert stays listed, in milliseconds.
+const RESOLVED_LINGER_MS: u64 = 5 * 60 * 1000;
+
+/// One `[[alerts]]` entry in the config file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    /// Shown in the alert list; defaults to the rule itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// `<metric> <op> <threshold> [for <duration>]`
+    pub rule: String,
+    #[serde(default)]
+    pub severity: Severity,
+    /// How far back past the threshold a firing alert's value must go to resolve
+    #[serde(default)]
+    pub hysteresis: f64,
+    /// Shell command run when the alert fires or resolves; see `core::notify`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// `http://` URL the alert is POSTed to as JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+    /// Show a desktop notification with `notify-send`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub notify: bool,
+}
+
+impl AlertRule {
+    pub fn has_actions(&self) -> bool {
+        self.command.is_some() || self.webhook.is_some() || self.notify
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    #[default]
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// Not breached
+    Inactive,
+    /// Breached, but not yet for the rule's `for` duration
+    Pending,
+    Firing,
+    /// Cleared recently
+    Resolved,
+}
+
+impl State {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Inactive => "OK",
+            Self::Pending => "PENDING",
+            Self::Firing => "FIRING",
+            Self::Resolved => "RESOLVED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+}
+
+impl Op {
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            ">" => Some(Self::Above),
+            ">=" => Some(Self::AtLeast),
+            "<" => Some(Self::Below),
+            "<=" => Some(Self::AtMost),
+            _ => None,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Above => ">",
+            Self::AtLeast => ">=",
+            Self::Below => "<",
+            Self::AtMost => "<=",
+        }
+    }
+
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Above => value > threshold,
+            Self::AtLeast => value >= threshold,
+            Self::Below => value < threshold,
+            Self::AtMost => value <= threshold,
+        }
+    }
+
+    /// `threshold` moved `by` towards the clear side
+    fn relax(self, threshold: f64, by: f64) -> f64 {
+        match self {
+            Self::Above | Self::AtLeast => threshold - by,
+            Self::Below | Self::AtMost => threshold + by,
+        }
+    }
+}
+
+/// A parsed rule and where it stands
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub name: String,
+    pub severity: Severity,
+    /// Collector the metric belongs to, e.g. `network`
+    pub collector: String,
+    pub metric: String,
+    /// How to read `metric`, and for which device if it has one
+    source: &'static Metric,
+    device: String,
+    op: Op,
+    pub threshold: f64,
+    hysteresis: f64,
+    /// Breach must last this long before firing, in milliseconds
+    hold_ms: u64,
+    pub state: State,
+    /// Latest value seen
+    pub value: Option<f64>,
+    /// When `state` was entered, milliseconds since the Unix epoch
+    pub since: u64,
+}
+
+impl Alert {
+    fn parse(rule: &AlertRule) -> Result<Self, String> {
+        let text = rule.rule.trim();
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let (metric, op, threshold, hold) = match tokens[..] {
+            [metric, op, threshold] => (metric, op, threshold, None),
+            [metric, op, threshold, "for", hold] => (metric, op, threshold, Some(hold)),
+            _ => return Err(format!("{text}: expected \"<metric> <op> <threshold> [for <duration>]\"")),
+        };
+        let op = Op::parse(op).ok_or_else(|| format!("{text}: unknown comparison {op}; use >, >=, < or <="))?;
+        let threshold: f64 = threshold.parse().map_err(|_| format!("{text}: invalid threshold {threshold}"))?;
+        let hold_ms = match hold {
+            Some(hold) => parse_window(hold).ok_or_else(|| format!("{text}: invalid duration {hold}"))? * 1000,
+            None => 0,
+        };
+        let name = metric_name(metric).map_err(|e| format!("{text}: {e}"))?;
+
+        Ok(Self {
+            name: rule.name.clone().unwrap_or_else(|| text.to_string()),
+            severity: rule.severity,
+            collector: name.collector.name.to_string(),
+            metric: metric.to_string(),
+            source: name.metric,
+            device: name.device.unwrap_or_default().to_string(),
+            op,
+            threshold,
+            hysteresis: rule.hysteresis.abs(),
+            hold_ms,
+            state: State::Inactive,
+            value: None,
+            since: 0,
+        })
+    }
+
+    /// The condition, e.g. `> 90`
+    pub fn condition(&self) -> String {
+        format!("{} {}", self.op.symbol(), self.threshold)
+    }
+
+    /// Advance on a new value; returns whether the state changed.
+    fn step(&mut self, value: Option<f64>, ts: u64) -> bool {
+        self.value = value;
+        // A missing value (device gone, collector failing) breaches nothing, so
+        // a firing alert resolves rather than ringing on for a device that left.
+        let holds = |threshold| value.is_some_and(|v| self.op.holds(v, threshold));
+        let breached = holds(self.threshold);
+        let next = match self.state {
+            State::Inactive | State::Resolved if breached => match self.hold_ms {
+                0 => State::Firing,
+                _ => State::Pending,
+            },
+            State::Resolved if ts.saturating_sub(self.since) >= RESOLVED_LINGER_MS => State::Inactive,
+            State::Pending if !breached => State::Inactive,
+            State::Pending if ts.saturating_sub(self.since) >= self.hold_ms => State::Firing,
+            State::Firing if !holds(self.op.relax(self.threshold, self.hysteresis)) => State::Resolved,
+            state => state,
+        };
+        if next == self.state {
+            return false;
+        }
+        self.state = next;
+        self.since = ts;
+        true
+    }
+}
+
+/// The configured rules, evaluated together
+#[derive(Debug, Clone, Default)]
+pub struct Alerts {
+    alerts: Vec<Alert>,
+}
+
+impl Alerts {
+    pub fn new(rules: &[AlertRule]) -> Result<Self, String> {
+        let alerts = rules.iter().map(Alert::parse).collect::<Result<_, _>>()?;
+        Ok(Self { alerts })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alerts.is_empty()
+    }
+
+    pub fn all(&self) -> &[Alert] {
+        &self.alerts
+    }
+
+    /// Alerts worth showing: everything not inactive, most severe first.
+    pub fn visible(&self) -> Vec<&Alert> {
+        let mut visible: Vec<&Alert> = self.alerts.iter().filter(|a| a.state != State::Inactive).collect();
+        visible.sort_by_key(|a| (a.state != State::Firing, a.state != State::Pending, std::cmp::Reverse(a.severity)));
+        visible
+    }
+
+    /// Highest severity firing on any of `collectors`
+    pub fn firing(&self, collectors: &[&str]) -> Option<Severity> {
+        self.alerts
+            .iter()
+            .filter(|a| a.state == State::Firing && collectors.contains(&a.collector.as_str()))
+            .map(|a| a.severity)
+            .max()
+    }
+
+    /// Evaluate every rule against `sample`; returns the indices of alerts
+    /// whose state changed.
+    pub fn evaluate(&mut self, sample: &Sample) -> Vec<usize> {
+        let mut changed = vec![];
+        for (i, alert) in self.alerts.iter_mut().enumerate() {
+            let value = (alert.source.read)(sample, &alert.device);
+            if alert.step(value, sample.ts) {
+                changed.push(i);
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::sample::test_support::sample;
+
+    fn rule(text: &str, hysteresis: f64) -> AlertRule {
+        AlertRule { rule: text.into(), severity: Severity::Critical, hysteresis, ..Default::default() }
+    }
+
+    #[test]
+    fn test_rule_parsing() {
+        assert!(Alerts::new(&[rule("cpu.usage > 90 for 30s", 0.0)]).is_ok());
+        assert!(Alerts::new(&[rule("network.eth0.rx >= 800", 0.0)]).is_ok());
+        assert!(Alerts::new(&[rule("memory.used_percent > 95", 0.0)]).is_ok());
+        assert!(Alerts::new(&[rule("network.rx > 800", 0.0)]).is_err());
+        assert!(Alerts::new(&[rule("cpu.usage = 90", 0.0)]).is_err());
+        assert!(Alerts::new(&[rule("cpu.usage > hot", 0.0)]).is_err());
+        assert!(Alerts::new(&[rule("cpu.usage > 90 for ever", 0.0)]).is_err());
+        assert!(Alerts::new(&[rule("cpu.speed > 90", 0.0)]).is_err());
+
+        // Values come from the shared metric registry, caches and RAID included.
+        let mut alerts = Alerts::new(&[rule("cache.zram0.compression_ratio >= 4", 0.0), rule("raid.md0.missing_disks > 0", 0.0)]).unwrap();
+        alerts.evaluate(&sample());
+        let states: Vec<(State, Option<f64>)> = alerts.all().iter().map(|a| (a.state, a.value)).collect();
+        assert_eq!(states, [(State::Firing, Some(4.0)), (State::Inactive, Some(0.0))]);
+    }
+
+    #[test]
+    fn test_pending_firing_resolved() {
+        let mut sample = sample();
+        let mut alerts = Alerts::new(&[rule("cpu.usage > 90 for 30s", 5.0)]).unwrap();
+        let mut at = |alerts: &mut Alerts, secs: u64, usage: f64| {
+            sample.ts = secs * 1000;
+            sample.cpu.usage = usage;
+            alerts.evaluate(&sample);
+            alerts.all()[0].state
+        };
+
+        assert_eq!(at(&mut alerts, 0, 50.0), State::Inactive);
+        assert_eq!(at(&mut alerts, 10, 95.0), State::Pending);
+        // A dip before `for` elapses starts over.
+        assert_eq!(at(&mut alerts, 20, 80.0), State::Inactive);
+        assert_eq!(at(&mut alerts, 30, 95.0), State::Pending);
+        assert_eq!(at(&mut alerts, 60, 95.0), State::Firing);
+        assert_eq!(alerts.firing(&["cpu"]), Some(Severity::Critical));
+        // Within the hysteresis band it keeps firing.
+        assert_eq!(at(&mut alerts, 70, 88.0), State::Firing);
+        assert_eq!(at(&mut alerts, 80, 84.0), State::Resolved);
+        assert_eq!(at(&mut alerts, 80 + 301, 50.0), State::Inactive);
+        assert!(alerts.visible().is_empty());
+    }
+
+    #[test]
+    fn test_missing_device_resolves() {
+        let mut sample = sample();
+        let mut alerts = Alerts::new(&[rule("raid.md0.missing_disks > 0", 0.0)]).unwrap();
+        sample.raid.as_mut().unwrap()[0].working_disks = Some(1);
+        alerts.evaluate(&sample);
+        assert_eq!(alerts.all()[0].state, State::Firing);
+
+        // The array disappears: the alert resolves, then expires like any other.
+        sample.raid = None;
+        sample.ts += 1000;
+        alerts.evaluate(&sample);
+        assert_eq!((alerts.all()[0].state, alerts.all()[0].value), (State::Resolved, None));
+        assert_eq!(alerts.firing(&["raid"]), None);
+        sample.ts += RESOLVED_LINGER_MS;
+        alerts.evaluate(&sample);
+        assert_eq!(alerts.all()[0].state, State::Inactive);
+    }
+}
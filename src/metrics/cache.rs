@@ -0,0 +1,449 @@
+//! Memory and storage cache metrics (Linux only): ZFS ARC from
+//! `/proc/spl/kstat/zfs/arcstats`, zram from `/sys/block/zram*/mm_stat` and
+//! bcache hit rates from `/sys/block/bcache*/bcache`. Each source is optional.
+
+use serde::{Deserialize, Serialize};
+use crate::core::error::AppError;
+use crate::metrics::historical_metric::HistoricalMetric;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+const ARCSTATS: &str = "/proc/spl/kstat/zfs/arcstats";
+const SYS_BLOCK: &str = "/sys/block";
+
+/// Recorded ZFS ARC state, in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArcSample {
+    pub size: f64,
+    pub target: f64,
+    pub max: u64,
+    pub hit_ratio: f64,
+}
+
+/// Recorded zram device usage, in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZramSample {
+    pub name: String,
+    pub orig: u64,
+    pub compr: u64,
+    pub mem_used: u64,
+}
+
+/// Recorded bcache device state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BcacheSample {
+    pub name: String,
+    pub hit_ratio: f64,
+    pub dirty: String,
+}
+
+/// Recorded state of every cache present
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSample {
+    pub arc: Option<ArcSample>,
+    pub zram: Vec<ZramSample>,
+    pub bcache: Vec<BcacheSample>,
+}
+
+/// ZFS Adaptive Replacement Cache
+#[derive(Clone)]
+pub struct ArcMetrics {
+    size: HistoricalMetric<f64>,
+    target: HistoricalMetric<f64>,
+    pub max_bytes: u64,
+    hit_ratio: HistoricalMetric<f64>,
+    last_hits: u64,
+    last_misses: u64,
+}
+
+impl ArcMetrics {
+    fn new() -> Option<Self> {
+        let stats = parse_arcstats(&fs::read_to_string(ARCSTATS).ok()?);
+        Some(Self {
+            size: HistoricalMetric::new(stats.size as f64),
+            target: HistoricalMetric::new(stats.target as f64),
+            max_bytes: stats.max,
+            hit_ratio: HistoricalMetric::new(hit_ratio(stats.hits, stats.misses)),
+            last_hits: stats.hits,
+            last_misses: stats.misses,
+        })
+    }
+
+    fn from_sample(sample: &ArcSample, at: Instant) -> Self {
+        Self {
+            size: HistoricalMetric::new_at(sample.size, at),
+            target: HistoricalMetric::new_at(sample.target, at),
+            max_bytes: sample.max,
+            hit_ratio: HistoricalMetric::new_at(sample.hit_ratio, at),
+            last_hits: 0,
+            last_misses: 0,
+        }
+    }
+
+    fn apply_sample(&mut self, sample: &ArcSample, at: Instant) {
+        self.size.update_at(sample.size, at);
+        self.target.update_at(sample.target, at);
+        self.max_bytes = sample.max;
+        self.hit_ratio.update_at(sample.hit_ratio, at);
+    }
+
+    fn update(&mut self) -> Result<(), AppError> {
+        let stats = parse_arcstats(&fs::read_to_string(ARCSTATS)?);
+        self.size.update(stats.size as f64);
+        self.target.update(stats.target as f64);
+        self.max_bytes = stats.max;
+        self.hit_ratio.update(hit_ratio(
+            stats.hits.saturating_sub(self.last_hits),
+            stats.misses.saturating_sub(self.last_misses),
+        ));
+        (self.last_hits, self.last_misses) = (stats.hits, stats.misses);
+        Ok(())
+    }
+
+    /// Current ARC size in bytes
+    pub fn size(&self) -> f64 { *self.size.current() }
+    /// Size the ARC is currently steering towards (`c`), in bytes
+    pub fn target(&self) -> f64 { *self.target.current() }
+    /// Hit ratio (%) over the last interval
+    pub fn hit_ratio(&self) -> f64 { *self.hit_ratio.current() }
+
+    pub fn size_history(&self) -> &HistoricalMetric<f64> { &self.size }
+    pub fn target_history(&self) -> &HistoricalMetric<f64> { &self.target }
+
+    fn resize_history(&mut self, len: usize) {
+        self.size.resize(len);
+        self.target.resize(len);
+        self.hit_ratio.resize(len);
+    }
+}
+
+/// One zram device
+#[derive(Clone)]
+pub struct ZramDevice {
+    pub name: String,
+    path: PathBuf,
+    /// Uncompressed bytes stored
+    pub orig_bytes: u64,
+    /// Compressed bytes stored
+    pub compr_bytes: u64,
+    /// Total RAM consumed, including allocator overhead
+    pub mem_used_bytes: u64,
+}
+
+impl ZramDevice {
+    fn new(path: PathBuf) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_string();
+        let (orig_bytes, compr_bytes, mem_used_bytes) =
+            parse_mm_stat(&fs::read_to_string(path.join("mm_stat")).ok()?)?;
+        Some(Self { name, path, orig_bytes, compr_bytes, mem_used_bytes })
+    }
+
+    fn from_sample(sample: &ZramSample) -> Self {
+        Self {
+            name: sample.name.clone(),
+            path: PathBuf::new(),
+            orig_bytes: sample.orig,
+            compr_bytes: sample.compr,
+            mem_used_bytes: sample.mem_used,
+        }
+    }
+
+    fn update(&mut self) -> Result<(), AppError> {
+        let contents = fs::read_to_string(self.path.join("mm_stat"))?;
+        if let Some((orig, compr, used)) = parse_mm_stat(&contents) {
+            (self.orig_bytes, self.compr_bytes, self.mem_used_bytes) = (orig, compr, used);
+        }
+        Ok(())
+    }
+
+    /// Compression ratio (original / compressed); 0 when empty
+    pub fn ratio(&self) -> f64 {
+        if self.compr_bytes == 0 { 0.0 } else { self.orig_bytes as f64 / self.compr_bytes as f64 }
+    }
+}
+
+/// One bcache backing device
+#[derive(Clone)]
+pub struct BcacheDevice {
+    pub name: String,
+    path: PathBuf,
+    /// Hit ratio (%) over the last interval
+    pub hit_ratio: f64,
+    /// Dirty data awaiting writeback, as reported by the kernel (e.g. `1.2M`)
+    pub dirty: String,
+    last_hits: u64,
+    last_misses: u64,
+}
+
+impl BcacheDevice {
+    fn new(path: PathBuf) -> Option<Self> {
+        let name = path.parent()?.file_name()?.to_str()?.to_string();
+        let (last_hits, last_misses) = read_bcache_counters(&path).ok()?;
+        Some(Self {
+            name,
+            dirty: read_trimmed(&path.join("dirty_data")).unwrap_or_default(),
+            path,
+            hit_ratio: hit_ratio(last_hits, last_misses),
+            last_hits,
+            last_misses,
+        })
+    }
+
+    fn from_sample(sample: &BcacheSample) -> Self {
+        Self {
+            name: sample.name.clone(),
+            path: PathBuf::new(),
+            hit_ratio: sample.hit_ratio,
+            dirty: sample.dirty.clone(),
+            last_hits: 0,
+            last_misses: 0,
+        }
+    }
+
+    fn update(&mut self) -> Result<(), AppError> {
+        let (hits, misses) = read_bcache_counters(&self.path)?;
+        self.hit_ratio = hit_ratio(hits.saturating_sub(self.last_hits), misses.saturating_sub(self.last_misses));
+        (self.last_hits, self.last_misses) = (hits, misses);
+        self.dirty = read_trimmed(&self.path.join("dirty_data")).unwrap_or_default();
+        Ok(())
+    }
+}
+
+/// Cache metrics
+#[derive(Clone)]
+pub struct CacheMetrics {
+    arc: Option<ArcMetrics>,
+    zram: Vec<ZramDevice>,
+    zram_orig: HistoricalMetric<f64>,
+    zram_compr: HistoricalMetric<f64>,
+    bcache: Vec<BcacheDevice>,
+}
+
+impl CacheMetrics {
+    /// Create a new cache metrics collector. Fails when none of ZFS, zram or
+    /// bcache is present.
+    pub fn new() -> Result<Self, AppError> {
+        let arc = ArcMetrics::new();
+        let zram: Vec<ZramDevice> = block_devices("zram").into_iter().filter_map(ZramDevice::new).collect();
+        let bcache: Vec<BcacheDevice> = block_devices("bcache")
+            .into_iter()
+            .filter_map(|dev| BcacheDevice::new(dev.join("bcache")))
+            .collect();
+
+        if arc.is_none() && zram.is_empty() && bcache.is_empty() {
+            return Err(AppError::System("no ZFS ARC, zram or bcache found".into()));
+        }
+
+        let (orig, compr) = zram_totals(&zram);
+        Ok(Self {
+            arc,
+            zram,
+            zram_orig: HistoricalMetric::new(orig),
+            zram_compr: HistoricalMetric::new(compr),
+            bcache,
+        })
+    }
+
+    /// Update cache metrics. zram and bcache devices that have gone away
+    /// (reset, detached) are dropped; an unreadable ARC keeps its last values
+    /// and its error is returned once the devices are updated.
+    pub fn update(&mut self) -> Result<(), AppError> {
+        let arc = match &mut self.arc {
+            Some(arc) => arc.update(),
+            None => Ok(()),
+        };
+        self.zram.retain_mut(|dev| dev.update().is_ok());
+        let (orig, compr) = zram_totals(&self.zram);
+        self.zram_orig.update(orig);
+        self.zram_compr.update(compr);
+        self.bcache.retain_mut(|dev| dev.update().is_ok());
+        arc
+    }
+
+    /// Rebuild cache state from a recorded sample
+    pub fn from_sample(sample: &CacheSample, at: Instant) -> Self {
+        let zram: Vec<ZramDevice> = sample.zram.iter().map(ZramDevice::from_sample).collect();
+        let (orig, compr) = zram_totals(&zram);
+        Self {
+            arc: sample.arc.as_ref().map(|s| ArcMetrics::from_sample(s, at)),
+            zram,
+            zram_orig: HistoricalMetric::new_at(orig, at),
+            zram_compr: HistoricalMetric::new_at(compr, at),
+            bcache: sample.bcache.iter().map(BcacheDevice::from_sample).collect(),
+        }
+    }
+
+    pub fn apply_sample(&mut self, sample: &CacheSample, at: Instant) {
+        match (&mut self.arc, &sample.arc) {
+            (Some(arc), Some(s)) => arc.apply_sample(s, at),
+            (None, Some(s)) => self.arc = Some(ArcMetrics::from_sample(s, at)),
+            _ => {}
+        }
+        self.zram = sample.zram.iter().map(ZramDevice::from_sample).collect();
+        let (orig, compr) = zram_totals(&self.zram);
+        self.zram_orig.update_at(orig, at);
+        self.zram_compr.update_at(compr, at);
+        self.bcache = sample.bcache.iter().map(BcacheDevice::from_sample).collect();
+    }
+
+    pub fn arc(&self) -> Option<&ArcMetrics> { self.arc.as_ref() }
+    pub fn zram(&self) -> &[ZramDevice] { &self.zram }
+    pub fn bcache(&self) -> &[BcacheDevice] { &self.bcache }
+
+    /// Uncompressed bytes stored across all zram devices
+    pub fn zram_orig_history(&self) -> &HistoricalMetric<f64> { &self.zram_orig }
+    /// Compressed bytes stored across all zram devices
+    pub fn zram_compr_history(&self) -> &HistoricalMetric<f64> { &self.zram_compr }
+
+    pub fn sample(&self) -> CacheSample {
+        CacheSample {
+            arc: self.arc.as_ref().map(|arc| ArcSample {
+                size: arc.size(),
+                target: arc.target(),
+                max: arc.max_bytes,
+                hit_ratio: arc.hit_ratio(),
+            }),
+            zram: self.zram
+                .iter()
+                .map(|d| ZramSample {
+                    name: d.name.clone(),
+                    orig: d.orig_bytes,
+                    compr: d.compr_bytes,
+                    mem_used: d.mem_used_bytes,
+                })
+                .collect(),
+            bcache: self.bcache
+                .iter()
+                .map(|d| BcacheSample { name: d.name.clone(), hit_ratio: d.hit_ratio, dirty: d.dirty.clone() })
+                .collect(),
+        }
+    }
+
+    pub fn resize_history(&mut self, len: usize) {
+        if let Some(arc) = &mut self.arc {
+            arc.resize_history(len);
+        }
+        self.zram_orig.resize(len);
+        self.zram_compr.resize(len);
+    }
+}
+
+fn hit_ratio(hits: u64, misses: u64) -> f64 {
+    let total = hits + misses;
+    if total == 0 { 0.0 } else { (hits as f64 / total as f64) * 100.0 }
+}
+
+fn zram_totals(devices: &[ZramDevice]) -> (f64, f64) {
+    devices.iter().fold((0.0, 0.0), |(orig, compr), d| {
+        (orig + d.orig_bytes as f64, compr + d.compr_bytes as f64)
+    })
+}
+
+/// `/sys/block` entries whose name starts with `prefix`, sorted by name.
+fn block_devices(prefix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(SYS_BLOCK) else { return vec![] };
+    let mut devices: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().starts_with(prefix))
+        .map(|e| e.path())
+        .collect();
+    devices.sort();
+    devices
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_bcache_counters(dev: &Path) -> Result<(u64, u64), AppError> {
+    let stats = dev.join("stats_total");
+    let read = |file: &str| -> Result<u64, AppError> {
+        let contents = fs::read_to_string(stats.join(file))?;
+        contents.trim().parse().map_err(|_| AppError::System(format!("invalid bcache {file}")))
+    };
+    Ok((read("cache_hits")?, read("cache_misses")?))
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ArcStats {
+    size: u64,
+    target: u64,
+    max: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// `arcstats` is a kstat table: two header lines, then `name type data` rows.
+fn parse_arcstats(contents: &str) -> ArcStats {
+    let mut stats = ArcStats::default();
+    for line in contents.lines().skip(2) {
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(_), Some(data)) = (fields.next(), fields.next(), fields.next()) else { continue };
+        let Ok(value) = data.parse() else { continue };
+        match name {
+            "size" => stats.size = value,
+            "c" => stats.target = value,
+            "c_max" => stats.max = value,
+            "hits" => stats.hits = value,
+            "misses" => stats.misses = value,
+            _ => {}
+        }
+    }
+    stats
+}
+
+/// `mm_stat` starts with `orig_data_size compr_data_size mem_used_total`.
+fn parse_mm_stat(contents: &str) -> Option<(u64, u64, u64)> {
+    let mut fields = contents.split_whitespace().map(|v| v.parse::<u64>());
+    Some((fields.next()?.ok()?, fields.next()?.ok()?, fields.next()?.ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cache_stats() {
+        let arcstats = "13 1 0x01 123 33456 1234567890 9876543210\n\
+            name                            type data\n\
+            hits                            4    9000\n\
+            misses                          4    1000\n\
+            c                               4    8589934592\n\
+            c_max                           4    17179869184\n\
+            size                            4    6442450944\n";
+        assert_eq!(parse_arcstats(arcstats), ArcStats {
+            size: 6442450944,
+            target: 8589934592,
+            max: 17179869184,
+            hits: 9000,
+            misses: 1000,
+        });
+        assert_eq!(hit_ratio(9000, 1000), 90.0);
+        assert_eq!(hit_ratio(0, 0), 0.0);
+
+        assert_eq!(
+            parse_mm_stat("  3145728   1048576   1200000        0  1200000        0        0        0\n"),
+            Some((3145728, 1048576, 1200000)),
+        );
+        assert_eq!(parse_mm_stat(""), None);
+    }
+
+    #[test]
+    fn test_cache_metrics() {
+        if let Ok(mut cache) = CacheMetrics::new() {
+            assert!(cache.update().is_ok());
+            assert!(!cache.zram_orig_history().history().is_empty());
+        }
+
+        // Devices whose sysfs nodes are gone drop out instead of failing the update.
+        let sample = CacheSample {
+            arc: None,
+            zram: vec![ZramSample { name: "zram0".into(), orig: 4096, compr: 1024, mem_used: 2048 }],
+            bcache: vec![BcacheSample { name: "bcache0".into(), hit_ratio: 90.0, dirty: "0".into() }],
+        };
+        let mut gone = CacheMetrics::from_sample(&sample, Instant::now());
+        assert!(gone.update().is_ok());
+        assert!(gone.zram().is_empty() && gone.bcache().is_empty());
+    }
+}
@@ -1,9 +1,10 @@
 //! CPU metrics collection
 
-use sysinfo::{Components, System};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Components, LoadAvg, System};
 use crate::core::error::AppError;
 use crate::metrics::historical_metric::HistoricalMetric;
-use std::collections::VecDeque;
+use std::time::Instant;
 
 /// CPU metrics
 pub struct CpuMetrics {
@@ -11,6 +12,16 @@ pub struct CpuMetrics {
     usage_percent: HistoricalMetric<f64>,
     components: Components,
     temperature: Option<f64>,
+    load: LoadAvg,
+}
+
+/// Recorded CPU state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuSample {
+    pub usage: f64,
+    pub temperature: Option<f64>,
+    /// 1, 5 and 15-minute load averages
+    pub load: [f64; 3],
 }
 
 impl CpuMetrics {
@@ -24,6 +35,29 @@ impl CpuMetrics {
             usage_percent: HistoricalMetric::new(initial_usage),
             components,
             temperature,
+            load: System::load_average(),
+        }
+    }
+
+    /// Rebuild CPU state from a recorded sample, without touching the hardware
+    pub fn from_sample(name: Option<String>, sample: &CpuSample, at: Instant) -> Self {
+        Self {
+            name,
+            usage_percent: HistoricalMetric::new_at(sample.usage, at),
+            components: Components::new(),
+            temperature: sample.temperature,
+            load: load_avg(sample.load),
+        }
+    }
+
+    /// Copy of the current state for display; temperatures are not re-read
+    pub fn snapshot(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            usage_percent: self.usage_percent.clone(),
+            components: Components::new(),
+            temperature: self.temperature,
+            load: self.load.clone(),
         }
     }
 
@@ -33,6 +67,7 @@ impl CpuMetrics {
         self.usage_percent.update(system.global_cpu_usage() as f64);
         self.components.refresh(false);
         self.temperature = find_cpu_temp(&self.components);
+        self.load = System::load_average();
         Ok(())
     }
 
@@ -40,16 +75,37 @@ impl CpuMetrics {
     pub fn usage_percent(&self) -> f64 { *self.usage_percent.current() }
 
     /// Historical CPU usage (%)
-    pub fn usage_history(&self) -> &VecDeque<f64> { self.usage_percent.history() }
+    pub fn usage_history(&self) -> &HistoricalMetric<f64> { &self.usage_percent }
 
     /// Current CPU temperature in °C, if available
     pub fn temperature(&self) -> Option<f64> { self.temperature }
 
+    /// 1/5/15-minute load averages as of the last update
+    pub fn load_average(&self) -> &LoadAvg { &self.load }
+
+    pub fn sample(&self) -> CpuSample {
+        CpuSample {
+            usage: self.usage_percent(),
+            temperature: self.temperature,
+            load: [self.load.one, self.load.five, self.load.fifteen],
+        }
+    }
+
+    pub fn apply_sample(&mut self, sample: &CpuSample, at: Instant) {
+        self.usage_percent.update_at(sample.usage, at);
+        self.temperature = sample.temperature;
+        self.load = load_avg(sample.load);
+    }
+
     pub fn resize_history(&mut self, len: usize) {
         self.usage_percent.resize(len);
     }
 }
 
+fn load_avg([one, five, fifteen]: [f64; 3]) -> LoadAvg {
+    LoadAvg { one, five, fifteen }
+}
+
 /// Return the best-guess CPU temperature from the component list.
 /// Tries common label patterns in priority order.
 fn find_cpu_temp(components: &Components) -> Option<f64> {
@@ -72,6 +128,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::manual_range_contains)]
     fn test_cpu_metrics() {
         let mut system = System::new_all();
         system.refresh_cpu_all();
@@ -79,6 +136,6 @@ mod tests {
         assert!(cpu.update(&mut system).is_ok());
         let usage = cpu.usage_percent();
         assert!(usage >= 0.0 && usage <= 100.0);
-        assert!(!cpu.usage_history().is_empty());
+        assert!(!cpu.usage_history().history().is_empty());
     }
 }
@@ -1,13 +1,20 @@
 //! Disk I/O metrics collection
 
+use serde::{Deserialize, Serialize};
 use sysinfo::{DiskRefreshKind, Disks};
 use crate::core::error::AppError;
 use crate::metrics::historical_metric::HistoricalMetric;
-use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
 const IO_REFRESH: fn() -> DiskRefreshKind = || DiskRefreshKind::nothing().with_io_usage();
 
+/// Recorded disk throughput, in MB/s
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskSample {
+    pub read: f64,
+    pub write: f64,
+}
+
 /// Aggregated disk I/O metrics (read/write rates across all disks)
 pub struct DiskMetrics {
     disks: Disks,
@@ -26,6 +33,26 @@ impl DiskMetrics {
         }
     }
 
+    /// Rebuild throughput state from a recorded sample
+    pub fn from_sample(sample: &DiskSample, at: Instant) -> Self {
+        Self {
+            disks: Disks::new(),
+            read_rate: HistoricalMetric::new_at(sample.read, at),
+            write_rate: HistoricalMetric::new_at(sample.write, at),
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Copy of the current rates for display, without the disk list
+    pub fn snapshot(&self) -> Self {
+        Self {
+            disks: Disks::new(),
+            read_rate: self.read_rate.clone(),
+            write_rate: self.write_rate.clone(),
+            last_update: self.last_update,
+        }
+    }
+
     pub fn update(&mut self) -> Result<(), AppError> {
         self.disks.refresh_specifics(false, IO_REFRESH());
 
@@ -50,8 +77,17 @@ impl DiskMetrics {
 
     pub fn read_rate(&self) -> f64 { *self.read_rate.current() }
     pub fn write_rate(&self) -> f64 { *self.write_rate.current() }
-    pub fn read_history(&self) -> &VecDeque<f64> { self.read_rate.history() }
-    pub fn write_history(&self) -> &VecDeque<f64> { self.write_rate.history() }
+    pub fn read_history(&self) -> &HistoricalMetric<f64> { &self.read_rate }
+    pub fn write_history(&self) -> &HistoricalMetric<f64> { &self.write_rate }
+
+    pub fn sample(&self) -> DiskSample {
+        DiskSample { read: self.read_rate(), write: self.write_rate() }
+    }
+
+    pub fn apply_sample(&mut self, sample: &DiskSample, at: Instant) {
+        self.read_rate.update_at(sample.read, at);
+        self.write_rate.update_at(sample.write, at);
+    }
 
     pub fn resize_history(&mut self, len: usize) {
         self.read_rate.resize(len);
@@ -70,7 +106,7 @@ mod tests {
         assert!(disk.update().is_ok());
         assert!(disk.read_rate() >= 0.0);
         assert!(disk.write_rate() >= 0.0);
-        assert!(!disk.read_history().is_empty());
-        assert!(!disk.write_history().is_empty());
+        assert!(!disk.read_history().history().is_empty());
+        assert!(!disk.write_history().history().is_empty());
     }
 }
@@ -1,16 +1,25 @@
 //! GPU metrics collection
 
 use nvml_wrapper::Nvml;
+use serde::{Deserialize, Serialize};
 use crate::core::error::AppError;
 use crate::metrics::historical_metric::HistoricalMetric;
-use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Recorded GPU utilisation and VRAM usage (%)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuSample {
+    pub usage: f64,
+    pub memory: f64,
+}
 
 /// GPU metrics
 pub struct GpuMetrics {
     usage_percent: HistoricalMetric<f64>,
     memory_percent: HistoricalMetric<f64>,
     pub name: Option<String>,
-    nvml: Nvml,
+    /// `None` when rebuilt from a recording
+    nvml: Option<Nvml>,
 }
 
 impl GpuMetrics {
@@ -24,13 +33,34 @@ impl GpuMetrics {
             usage_percent: HistoricalMetric::new(0.0),
             memory_percent: HistoricalMetric::new(0.0),
             name: Some(name),
-            nvml,
+            nvml: Some(nvml),
         })
     }
 
+    /// Rebuild GPU state from a recorded sample, without NVML
+    pub fn from_sample(name: Option<String>, sample: &GpuSample, at: Instant) -> Self {
+        Self {
+            usage_percent: HistoricalMetric::new_at(sample.usage, at),
+            memory_percent: HistoricalMetric::new_at(sample.memory, at),
+            name,
+            nvml: None,
+        }
+    }
+
+    /// Copy of the current state for display, without NVML
+    pub fn snapshot(&self) -> Self {
+        Self {
+            usage_percent: self.usage_percent.clone(),
+            memory_percent: self.memory_percent.clone(),
+            name: self.name.clone(),
+            nvml: None,
+        }
+    }
+
     /// Update GPU metrics
     pub fn update(&mut self) -> Result<(), AppError> {
-        let device = self.nvml.device_by_index(0).map_err(|e| AppError::System(e.to_string()))?;
+        let Some(nvml) = &self.nvml else { return Ok(()) };
+        let device = nvml.device_by_index(0).map_err(|e| AppError::System(e.to_string()))?;
 
         let usage = device
             .utilization_rates()
@@ -60,13 +90,22 @@ impl GpuMetrics {
     }
 
     /// History of GPU usage (%)
-    pub fn usage_history(&self) -> &VecDeque<f64> {
-        self.usage_percent.history()
+    pub fn usage_history(&self) -> &HistoricalMetric<f64> {
+        &self.usage_percent
     }
 
     /// History of GPU memory usage (%)
-    pub fn memory_history(&self) -> &VecDeque<f64> {
-        self.memory_percent.history()
+    pub fn memory_history(&self) -> &HistoricalMetric<f64> {
+        &self.memory_percent
+    }
+
+    pub fn sample(&self) -> GpuSample {
+        GpuSample { usage: self.usage_percent(), memory: self.memory_percent() }
+    }
+
+    pub fn apply_sample(&mut self, sample: &GpuSample, at: Instant) {
+        self.usage_percent.update_at(sample.usage, at);
+        self.memory_percent.update_at(sample.memory, at);
     }
 
     pub fn resize_history(&mut self, len: usize) {
@@ -81,6 +120,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::manual_range_contains)]
     fn test_gpu_metrics() {
         if let Ok(mut gpu) = GpuMetrics::new() {
             assert!(gpu.update().is_ok());
@@ -90,8 +130,8 @@ mod tests {
 
             assert!(usage >= 0.0 && usage <= 100.0);
             assert!(mem >= 0.0 && mem <= 100.0);
-            assert!(!gpu.usage_history().is_empty());
-            assert!(!gpu.memory_history().is_empty());
+            assert!(!gpu.usage_history().history().is_empty());
+            assert!(!gpu.memory_history().history().is_empty());
         }
     }
 }
\ No newline at end of file
@@ -1,49 +1,387 @@
+//! A metric's current value plus its history over the configured window.
+//!
+//! History is kept in tiers, round-robin-database style: tier 0 holds raw
+//! samples, and each further tier folds `TIER_FACTOR` times as many samples
+//! into one min/avg/max bucket. Every tier holds at most `TIER_POINTS`
+//! buckets, so a 24-hour window costs no more memory than a few minutes of
+//! raw samples, while the per-bucket maximum keeps short spikes visible.
+//!
+//! Every bucket carries the time of its newest sample, so charts can place
+//! points on a real time axis and show gaps where sampling stopped.
+
 use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
 
 const DEFAULT_HISTORY_LENGTH: usize = 100;
 
+/// Most buckets any one tier keeps
+const TIER_POINTS: usize = 512;
+/// How many buckets of one tier make up a bucket of the next
+const TIER_FACTOR: usize = 4;
+
+/// The time to stamp a new sample with. `Instant` stands still while the
+/// machine is suspended, which would hide the gap, so on Linux this follows
+/// the boot-time clock (which counts suspend but, unlike the wall clock, is
+/// never stepped) from a fixed `Instant` origin. Wall time only labels
+/// samples when they are written out.
+pub fn sample_time() -> Instant {
+    static ORIGIN: OnceLock<(Instant, Option<Duration>)> = OnceLock::new();
+    static LAST: Mutex<Option<Instant>> = Mutex::new(None);
+    let (origin, boot) = *ORIGIN.get_or_init(|| (Instant::now(), boot_time()));
+    let now = match (boot, boot_time()) {
+        (Some(start), Some(now)) => origin + now.saturating_sub(start),
+        _ => Instant::now(),
+    };
+    // Keep stamps in order should the two clocks ever disagree.
+    let mut last = LAST.lock().unwrap_or_else(PoisonError::into_inner);
+    let now = now.max(last.unwrap_or(origin));
+    *last = Some(now);
+    now
+}
+
+/// Time since boot, including suspend, from `/proc/uptime` (CLOCK_BOOTTIME)
+fn boot_time() -> Option<Duration> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    parse_uptime(&std::fs::read_to_string("/proc/uptime").ok()?)
+}
+
+fn parse_uptime(contents: &str) -> Option<Duration> {
+    let secs: f64 = contents.split_whitespace().next()?.parse().ok()?;
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+/// Values that can be averaged into downsampled buckets
+pub trait Sampled: Copy + PartialOrd {
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Sampled for f64 {
+    fn to_f64(self) -> f64 { self }
+    fn from_f64(value: f64) -> Self { value }
+}
+
+impl Sampled for u64 {
+    fn to_f64(self) -> f64 { self as f64 }
+    fn from_f64(value: f64) -> Self { value.round() as u64 }
+}
+
+/// Summary of a metric over its history window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub stddev: f64,
+}
+
+/// One resolution of a metric's history as charts see it, oldest bucket first
+pub struct Buckets<'a, T> {
+    /// Average per bucket
+    pub values: &'a VecDeque<T>,
+    /// Per-bucket minimum and maximum, or `None` when each bucket is a
+    /// single sample and they would equal `values`
+    pub range: Option<(&'a VecDeque<T>, &'a VecDeque<T>)>,
+    /// When the newest sample in each bucket was taken
+    pub times: &'a VecDeque<Instant>,
+}
+
+/// One resolution of a metric's history. The newest bucket is updated in
+/// place until it has absorbed `step` samples, so the coarse tiers stay live.
+#[derive(Debug, Clone)]
+struct Tier<T> {
+    /// Raw samples per bucket
+    step: usize,
+    capacity: usize,
+    min: VecDeque<T>,
+    avg: VecDeque<T>,
+    max: VecDeque<T>,
+    /// When the newest sample in each bucket was taken
+    time: VecDeque<Instant>,
+    /// Raw samples in the newest bucket so far, and their sum
+    filled: usize,
+    sum: f64,
+}
+
+impl<T: Sampled> Tier<T> {
+    fn new(step: usize, capacity: usize) -> Self {
+        Self {
+            step,
+            capacity,
+            min: VecDeque::with_capacity(capacity),
+            avg: VecDeque::with_capacity(capacity),
+            max: VecDeque::with_capacity(capacity),
+            time: VecDeque::with_capacity(capacity),
+            filled: 0,
+            sum: 0.0,
+        }
+    }
+
+    /// A coarser tier seeded from the buckets of a finer one
+    fn downsampled(finer: &Tier<T>, step: usize, capacity: usize) -> Self {
+        let mut tier = Self::new(step, capacity);
+        for i in 0..finer.avg.len() {
+            tier.merge(finer.min[i], finer.avg[i], finer.max[i], finer.step, finer.time[i]);
+        }
+        tier
+    }
+
+    /// Fold `weight` raw samples summarised as `min`/`avg`/`max`, the newest
+    /// taken at `time`, into the newest bucket.
+    fn merge(&mut self, min: T, avg: T, max: T, weight: usize, time: Instant) {
+        if self.filled == 0 {
+            if self.avg.len() == self.capacity {
+                self.pop_oldest();
+            }
+            self.min.push_back(min);
+            self.avg.push_back(avg);
+            self.max.push_back(max);
+            self.time.push_back(time);
+            self.sum = 0.0;
+        } else {
+            let last = self.avg.len() - 1;
+            if min < self.min[last] {
+                self.min[last] = min;
+            }
+            if max > self.max[last] {
+                self.max[last] = max;
+            }
+        }
+
+        if let Some(last) = self.time.back_mut() {
+            *last = time;
+        }
+        self.sum += avg.to_f64() * weight as f64;
+        self.filled += weight;
+        if let Some(last) = self.avg.back_mut() {
+            *last = T::from_f64(self.sum / self.filled as f64);
+        }
+        if self.filled >= self.step {
+            self.filled = 0;
+        }
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.avg.len() > capacity {
+            self.pop_oldest();
+        }
+    }
+
+    fn pop_oldest(&mut self) {
+        self.min.pop_front();
+        self.avg.pop_front();
+        self.max.pop_front();
+        self.time.pop_front();
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HistoricalMetric<T> {
     current: T,
-    history: VecDeque<T>,
-    max_len: usize,
+    /// Finest first; the last tier always spans the whole window.
+    tiers: Vec<Tier<T>>,
 }
 
-impl<T: Clone> HistoricalMetric<T> {
+impl<T: Sampled> HistoricalMetric<T> {
     pub fn new(initial: T) -> Self {
-        Self::with_capacity(initial, DEFAULT_HISTORY_LENGTH)
+        Self::new_at(initial, sample_time())
     }
 
-    pub fn with_capacity(initial: T, max_len: usize) -> Self {
-        let mut history = VecDeque::with_capacity(max_len);
-        history.push_back(initial.clone());
-        Self {
-            current: initial,
-            history,
-            max_len,
-        }
+    /// Start from a sample taken at `time` rather than now, e.g. when replaying
+    pub fn new_at(initial: T, time: Instant) -> Self {
+        let mut metric = Self { current: initial, tiers: vec![Tier::new(1, DEFAULT_HISTORY_LENGTH)] };
+        metric.update_at(initial, time);
+        metric
     }
 
     pub fn update(&mut self, value: T) {
-        self.current = value.clone();
-        if self.history.len() == self.max_len {
-            self.history.pop_front();
+        self.update_at(value, sample_time());
+    }
+
+    /// Record a sample taken at `time`, which must not precede earlier ones
+    pub fn update_at(&mut self, value: T, time: Instant) {
+        self.current = value;
+        for tier in &mut self.tiers {
+            tier.merge(value, value, value, 1, time);
         }
-        self.history.push_back(value);
     }
 
     pub fn current(&self) -> &T {
         &self.current
     }
 
+    /// Average value per bucket across the whole window, oldest first.
+    /// Buckets are single samples until the window outgrows `TIER_POINTS`.
     pub fn history(&self) -> &VecDeque<T> {
-        &self.history
+        &self.window_tier().avg
     }
 
-    pub fn resize(&mut self, new_max_len: usize) {
-        self.max_len = new_max_len;
-        while self.history.len() > new_max_len {
-            self.history.pop_front();
+    /// The finest tier that reaches back to `since`, or that holds all
+    /// that is retained, so zoomed-in charts keep full detail. `None`
+    /// asks for the whole window.
+    pub fn buckets(&self, since: Option<Instant>) -> Buckets<'_, T> {
+        let coarsest = self.window_tier();
+        let oldest = coarsest.time.front();
+        let reaches = |front: &Instant| since.is_some_and(|s| *front <= s) || oldest.is_some_and(|o| front <= o);
+        let tier = self.tiers
+            .iter()
+            .find(|tier| tier.time.front().is_some_and(reaches))
+            .unwrap_or(coarsest);
+        Buckets {
+            values: &tier.avg,
+            range: (tier.step > 1).then_some((&tier.min, &tier.max)),
+            times: &tier.time,
+        }
+    }
+
+    /// Value of the bucket `time` falls in, from the finest tier that still
+    /// reaches back that far. `None` outside the retained history.
+    pub fn value_at(&self, time: Instant) -> Option<T> {
+        let Buckets { values, times, .. } = self.buckets(Some(time));
+        if times.front().is_none_or(|first| *first > time) {
+            return None;
+        }
+        values.get(times.partition_point(|t| *t < time)).copied()
+    }
+
+    /// Highest value over the whole window, from the bucket maxima
+    pub fn peak(&self) -> f64 {
+        self.window_tier().max.iter().map(|v| v.to_f64()).reduce(f64::max).unwrap_or(0.0)
+    }
+
+    /// Statistics over the whole window. Min and max are exact, taken from
+    /// the bucket extremes; mean, spread and percentiles are over the bucket
+    /// averages, so the cost is bounded by `TIER_POINTS` however long the window.
+    pub fn stats(&self) -> Stats {
+        let tier = self.window_tier();
+        let mut values: Vec<f64> = tier.avg.iter().map(|v| v.to_f64()).collect();
+        values.sort_unstable_by(f64::total_cmp);
+
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+        // Nearest-rank percentile
+        let percentile = |p: f64| values[((p * count).ceil() as usize).clamp(1, values.len()) - 1];
+        let extreme = |side: &VecDeque<T>, pick: fn(f64, f64) -> f64| {
+            side.iter().map(|v| v.to_f64()).reduce(pick).unwrap_or(mean)
+        };
+
+        Stats {
+            min: extreme(&tier.min, f64::min),
+            max: extreme(&tier.max, f64::max),
+            mean,
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            stddev: variance.sqrt(),
+        }
+    }
+
+    /// Keep enough history to cover `window` samples, adding or dropping
+    /// coarser tiers as needed. New tiers are seeded from the coarsest
+    /// existing one, so widening the window keeps what was already seen.
+    pub fn resize(&mut self, window: usize) {
+        let window = window.max(1);
+        let mut step = 1;
+        let mut index = 0;
+        loop {
+            let buckets = window.div_ceil(step);
+            let capacity = buckets.min(TIER_POINTS);
+            match self.tiers.get_mut(index) {
+                Some(tier) => tier.set_capacity(capacity),
+                None => {
+                    let tier = Tier::downsampled(&self.tiers[index - 1], step, capacity);
+                    self.tiers.push(tier);
+                }
+            }
+            if buckets <= TIER_POINTS {
+                break;
+            }
+            step *= TIER_FACTOR;
+            index += 1;
+        }
+        self.tiers.truncate(index + 1);
+    }
+
+    fn window_tier(&self) -> &Tier<T> {
+        self.tiers.last().expect("at least one tier")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downsampled_window_keeps_spikes() {
+        let window = TIER_POINTS * TIER_FACTOR;
+        let start = Instant::now();
+        let at = |i: usize| start + Duration::from_secs(i as u64);
+        let mut metric = HistoricalMetric::new_at(0.0, start);
+        metric.resize(window);
+        for i in 1..window * 2 {
+            metric.update_at(if i % 100 == 0 { 100.0 } else { 1.0 }, at(i));
+        }
+
+        let history = metric.history();
+        assert_eq!(history.len(), TIER_POINTS);
+        // Each bucket is stamped with its newest sample.
+        let buckets = metric.buckets(None);
+        assert_eq!(buckets.times.back(), Some(&at(window * 2 - 1)));
+        assert_eq!(buckets.times[TIER_POINTS - 2], at(window * 2 - 1 - TIER_FACTOR));
+        let (min, max) = buckets.range.unwrap();
+        assert_eq!(max.iter().cloned().fold(0.0, f64::max), 100.0);
+        assert_eq!(min.iter().cloned().fold(f64::MAX, f64::min), 1.0);
+        assert!(history.iter().all(|v| *v < 100.0));
+
+        // Recent history is still available sample by sample.
+        let recent = metric.buckets(Some(at(window * 2 - 100)));
+        assert!(recent.range.is_none());
+        assert_eq!(recent.values.len(), TIER_POINTS);
+        assert_eq!(metric.value_at(at(4000)), Some(100.0));
+        assert!(metric.value_at(at(3000)).is_some_and(|v| v > 1.0 && v < 100.0));
+        assert_eq!(metric.value_at(at(window * 2)), None);
+        assert_eq!(metric.value_at(at(1000)), None);
+
+        // Narrowing the window drops the coarse tier and shows raw samples again.
+        metric.resize(60);
+        assert_eq!(metric.history().len(), 60);
+        let buckets = metric.buckets(None);
+        assert_eq!(buckets.times.front(), Some(&at(window * 2 - 60)));
+        assert!(buckets.range.is_none());
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut metric = HistoricalMetric::new(1.0);
+        for value in 2..=100 {
+            metric.update(value as f64);
+        }
+
+        let stats = metric.stats();
+        assert_eq!((stats.min, stats.max), (1.0, 100.0));
+        assert_eq!(stats.mean, 50.5);
+        assert_eq!((stats.p95, stats.p99), (95.0, 99.0));
+        assert!((stats.stddev - 28.866).abs() < 0.001);
+
+        // A spike folded into a downsampled bucket still sets the maximum.
+        metric.resize(TIER_POINTS * TIER_FACTOR);
+        for i in 0..TIER_POINTS * TIER_FACTOR {
+            metric.update(if i == 1000 { 500.0 } else { 10.0 });
         }
+        let stats = metric.stats();
+        assert_eq!(stats.max, 500.0);
+        assert_eq!(metric.peak(), stats.max);
+        assert!(stats.p99 < 500.0);
+        // Live samples are stamped in order, even though they follow the boot-time clock.
+        let times = metric.buckets(None).times;
+        assert!(times.iter().zip(times.iter().skip(1)).all(|(a, b)| a <= b));
+        assert_eq!(parse_uptime("12345.67 48000.10\n"), Some(Duration::from_millis(12_345_670)));
+        assert_eq!(parse_uptime(""), None);
     }
 }
@@ -0,0 +1,182 @@
+//! Host identity and process-table metrics
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use crate::core::error::AppError;
+use crate::metrics::sample::Header;
+
+/// Host overview: identity (static) plus uptime, process and session counts (live)
+#[derive(Clone)]
+pub struct HostMetrics {
+    pub host_name: Option<String>,
+    pub kernel_version: Option<String>,
+    pub os_version: Option<String>,
+    /// Boot time in seconds since the Unix epoch
+    pub boot_time: u64,
+    uptime: u64,
+    process_count: usize,
+    thread_count: usize,
+    user_count: Option<usize>,
+    core_count: usize,
+}
+
+/// Recorded live host counters; identity fields go in the recording header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostSample {
+    pub uptime: u64,
+    pub processes: usize,
+    pub threads: usize,
+    pub users: Option<usize>,
+    pub cores: usize,
+}
+
+impl HostMetrics {
+    /// Create a new host metrics collector
+    pub fn new(system: &System) -> Self {
+        let mut host = Self {
+            host_name: System::host_name(),
+            kernel_version: System::kernel_version(),
+            os_version: System::long_os_version(),
+            boot_time: System::boot_time(),
+            uptime: System::uptime(),
+            process_count: 0,
+            thread_count: 0,
+            user_count: logged_in_users(),
+            core_count: system.cpus().len(),
+        };
+        host.count_processes(system);
+        host
+    }
+
+    /// Rebuild host state from a recording header and sample
+    pub fn from_sample(header: &Header, sample: &HostSample) -> Self {
+        let mut host = Self {
+            host_name: header.host_name.clone(),
+            kernel_version: header.kernel_version.clone(),
+            os_version: header.os_version.clone(),
+            boot_time: header.boot_time,
+            uptime: 0,
+            process_count: 0,
+            thread_count: 0,
+            user_count: None,
+            core_count: 0,
+        };
+        host.apply_sample(sample);
+        host
+    }
+
+    /// Update host metrics
+    pub fn update(&mut self, system: &mut System) -> Result<(), AppError> {
+        // Only the task lists are needed; skip cpu/memory/exe per process.
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_tasks(),
+        );
+        self.count_processes(system);
+        self.uptime = System::uptime();
+        self.user_count = logged_in_users();
+        self.core_count = system.cpus().len();
+        Ok(())
+    }
+
+    /// Seconds since boot
+    pub fn uptime(&self) -> u64 { self.uptime }
+
+    /// Number of processes (threads excluded)
+    pub fn process_count(&self) -> usize { self.process_count }
+
+    /// Number of threads across all processes, main threads included
+    pub fn thread_count(&self) -> usize { self.thread_count }
+
+    /// Number of logged-in sessions, if the platform exposes them
+    pub fn user_count(&self) -> Option<usize> { self.user_count }
+
+    /// Number of logical CPU cores
+    pub fn core_count(&self) -> usize { self.core_count }
+
+    pub fn sample(&self) -> HostSample {
+        HostSample {
+            uptime: self.uptime,
+            processes: self.process_count,
+            threads: self.thread_count,
+            users: self.user_count,
+            cores: self.core_count,
+        }
+    }
+
+    pub fn apply_sample(&mut self, sample: &HostSample) {
+        self.uptime = sample.uptime;
+        self.process_count = sample.processes;
+        self.thread_count = sample.threads;
+        self.user_count = sample.users;
+        self.core_count = sample.cores;
+    }
+
+    fn count_processes(&mut self, system: &System) {
+        // On Linux, threads are listed alongside processes; count them through
+        // their parent's task set instead so each one is seen exactly once.
+        let (procs, threads) = system
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind().is_none())
+            .fold((0, 0), |(procs, threads), p| {
+                (procs + 1, threads + 1 + p.tasks().map_or(0, |t| t.len()))
+            });
+        self.process_count = procs;
+        self.thread_count = threads;
+    }
+}
+
+/// Count user sessions the same way `uptime` does: `USER_PROCESS` records in utmp.
+/// The record layout below is glibc's on 64-bit Linux; other targets report no count.
+#[cfg(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64"))]
+fn logged_in_users() -> Option<usize> {
+    std::fs::read("/var/run/utmp").ok().map(|bytes| count_utmp_sessions(&bytes))
+}
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64")))]
+fn logged_in_users() -> Option<usize> {
+    None
+}
+
+/// Size of a glibc `struct utmp` record on 64-bit Linux.
+#[cfg(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64"))]
+const UTMP_RECORD_LEN: usize = 384;
+
+/// `ut_type` value for a normal login session.
+#[cfg(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64"))]
+const USER_PROCESS: i16 = 7;
+
+#[cfg(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64"))]
+fn count_utmp_sessions(bytes: &[u8]) -> usize {
+    bytes
+        .chunks_exact(UTMP_RECORD_LEN)
+        .filter(|rec| i16::from_ne_bytes([rec[0], rec[1]]) == USER_PROCESS)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_metrics() {
+        let mut system = System::new_all();
+        let mut host = HostMetrics::new(&system);
+        assert!(host.update(&mut system).is_ok());
+        assert!(host.process_count() > 0);
+        assert!(host.thread_count() >= host.process_count());
+        assert!(host.core_count() > 0);
+    }
+
+    #[cfg(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64"))]
+    #[test]
+    fn test_count_utmp_sessions() {
+        let mut bytes = vec![0u8; UTMP_RECORD_LEN * 3];
+        bytes[0..2].copy_from_slice(&USER_PROCESS.to_ne_bytes());
+        bytes[UTMP_RECORD_LEN..UTMP_RECORD_LEN + 2].copy_from_slice(&2i16.to_ne_bytes()); // BOOT_TIME
+        bytes[UTMP_RECORD_LEN * 2..UTMP_RECORD_LEN * 2 + 2].copy_from_slice(&USER_PROCESS.to_ne_bytes());
+        assert_eq!(count_utmp_sessions(&bytes), 2);
+    }
+}
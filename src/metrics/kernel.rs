@@ -0,0 +1,317 @@
+//! Kernel activity metrics (Linux only): scheduler and interrupt counters from
+//! `/proc/stat` and per-IRQ, per-CPU rates from `/proc/interrupts`.
+
+use serde::{Deserialize, Serialize};
+use crate::core::error::AppError;
+use crate::metrics::historical_metric::HistoricalMetric;
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Cumulative counters read from `/proc/stat`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct StatCounters {
+    context_switches: u64,
+    interrupts: u64,
+    forks: u64,
+    procs_running: u64,
+    procs_blocked: u64,
+}
+
+/// One line of `/proc/interrupts`
+#[derive(Debug, Clone, PartialEq)]
+struct IrqLine {
+    irq: String,
+    device: String,
+    per_cpu: Vec<u64>,
+}
+
+/// Interrupt rate for a single IRQ source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IrqRate {
+    pub irq: String,
+    pub device: String,
+    /// Interrupts per second summed across CPUs
+    pub total: f64,
+    /// Interrupts per second, indexed by CPU number
+    pub per_cpu: Vec<f64>,
+}
+
+impl IrqRate {
+    /// CPU number receiving most of this IRQ's interrupts, with its rate
+    pub fn busiest_cpu(&self) -> Option<(usize, f64)> {
+        self.per_cpu
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Only the busiest non-idle IRQ sources are kept in samples; the rest is noise.
+const SAMPLED_IRQS: usize = 8;
+
+/// Recorded kernel activity rates (per second)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KernelSample {
+    pub context_switches: f64,
+    pub interrupts: f64,
+    pub forks: f64,
+    pub procs_running: f64,
+    pub procs_blocked: f64,
+    pub irqs: Vec<IrqRate>,
+}
+
+/// Kernel activity metrics
+#[derive(Clone)]
+pub struct KernelMetrics {
+    context_switches: HistoricalMetric<f64>,
+    interrupts: HistoricalMetric<f64>,
+    forks: HistoricalMetric<f64>,
+    procs_running: HistoricalMetric<f64>,
+    procs_blocked: HistoricalMetric<f64>,
+    irq_rates: Vec<IrqRate>,
+    last_stat: StatCounters,
+    last_irqs: HashMap<String, Vec<u64>>,
+    last_update: Instant,
+}
+
+impl KernelMetrics {
+    /// Create a new kernel metrics collector. Fails where `/proc/stat` is unavailable.
+    pub fn new() -> Result<Self, AppError> {
+        let last_stat = read_stat()?;
+        let last_irqs = read_interrupts()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|line| (line.irq, line.per_cpu))
+            .collect();
+
+        Ok(Self {
+            context_switches: HistoricalMetric::new(0.0),
+            interrupts: HistoricalMetric::new(0.0),
+            forks: HistoricalMetric::new(0.0),
+            procs_running: HistoricalMetric::new(last_stat.procs_running as f64),
+            procs_blocked: HistoricalMetric::new(last_stat.procs_blocked as f64),
+            irq_rates: vec![],
+            last_stat,
+            last_irqs,
+            last_update: Instant::now(),
+        })
+    }
+
+    /// Rebuild kernel activity state from a recorded sample
+    pub fn from_sample(sample: &KernelSample, at: Instant) -> Self {
+        Self {
+            context_switches: HistoricalMetric::new_at(sample.context_switches, at),
+            interrupts: HistoricalMetric::new_at(sample.interrupts, at),
+            forks: HistoricalMetric::new_at(sample.forks, at),
+            procs_running: HistoricalMetric::new_at(sample.procs_running, at),
+            procs_blocked: HistoricalMetric::new_at(sample.procs_blocked, at),
+            irq_rates: sample.irqs.clone(),
+            last_stat: StatCounters::default(),
+            last_irqs: HashMap::new(),
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Update kernel metrics. If `/proc/stat` can't be read, the last values
+    /// stay and the next rates span the gap.
+    pub fn update(&mut self) -> Result<(), AppError> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update);
+
+        if elapsed < Duration::from_millis(100) {
+            return Ok(());
+        }
+
+        let elapsed_secs = elapsed.as_secs_f64();
+        let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / elapsed_secs;
+
+        let stat = read_stat()?;
+        self.context_switches.update(rate(stat.context_switches, self.last_stat.context_switches));
+        self.interrupts.update(rate(stat.interrupts, self.last_stat.interrupts));
+        self.forks.update(rate(stat.forks, self.last_stat.forks));
+        self.procs_running.update(stat.procs_running as f64);
+        self.procs_blocked.update(stat.procs_blocked as f64);
+        self.last_stat = stat;
+
+        // /proc/interrupts may be unreadable in some containers; keep the
+        // scheduler counters going regardless.
+        if let Ok(lines) = read_interrupts() {
+            let mut irq_rates: Vec<IrqRate> = lines
+                .iter()
+                .filter_map(|line| {
+                    let before = self.last_irqs.get(&line.irq)?;
+                    let per_cpu: Vec<f64> = line.per_cpu
+                        .iter()
+                        .zip(before.iter().chain(std::iter::repeat(&0)))
+                        .map(|(&now, &before)| rate(now, before))
+                        .collect();
+                    Some(IrqRate {
+                        irq: line.irq.clone(),
+                        device: line.device.clone(),
+                        total: per_cpu.iter().sum(),
+                        per_cpu,
+                    })
+                })
+                .collect();
+            irq_rates.sort_by(|a, b| b.total.total_cmp(&a.total));
+            self.irq_rates = irq_rates;
+            self.last_irqs = lines.into_iter().map(|line| (line.irq, line.per_cpu)).collect();
+        }
+
+        self.last_update = now;
+        Ok(())
+    }
+
+    pub fn context_switch_rate(&self) -> f64 { *self.context_switches.current() }
+    pub fn interrupt_rate(&self) -> f64 { *self.interrupts.current() }
+    pub fn fork_rate(&self) -> f64 { *self.forks.current() }
+    pub fn procs_running(&self) -> f64 { *self.procs_running.current() }
+    pub fn procs_blocked(&self) -> f64 { *self.procs_blocked.current() }
+
+    pub fn context_switch_history(&self) -> &HistoricalMetric<f64> { &self.context_switches }
+    pub fn interrupt_history(&self) -> &HistoricalMetric<f64> { &self.interrupts }
+
+    /// The `n` busiest IRQ sources over the last interval, highest rate first
+    pub fn top_irqs(&self, n: usize) -> &[IrqRate] {
+        &self.irq_rates[..n.min(self.irq_rates.len())]
+    }
+
+    pub fn sample(&self) -> KernelSample {
+        KernelSample {
+            context_switches: self.context_switch_rate(),
+            interrupts: self.interrupt_rate(),
+            forks: self.fork_rate(),
+            procs_running: self.procs_running(),
+            procs_blocked: self.procs_blocked(),
+            irqs: self.top_irqs(SAMPLED_IRQS).iter().filter(|irq| irq.total > 0.0).cloned().collect(),
+        }
+    }
+
+    pub fn apply_sample(&mut self, sample: &KernelSample, at: Instant) {
+        self.context_switches.update_at(sample.context_switches, at);
+        self.interrupts.update_at(sample.interrupts, at);
+        self.forks.update_at(sample.forks, at);
+        self.procs_running.update_at(sample.procs_running, at);
+        self.procs_blocked.update_at(sample.procs_blocked, at);
+        self.irq_rates = sample.irqs.clone();
+    }
+
+    pub fn resize_history(&mut self, len: usize) {
+        self.context_switches.resize(len);
+        self.interrupts.resize(len);
+        self.forks.resize(len);
+        self.procs_running.resize(len);
+        self.procs_blocked.resize(len);
+    }
+}
+
+fn read_stat() -> Result<StatCounters, AppError> {
+    let contents = fs::read_to_string("/proc/stat")?;
+    Ok(parse_stat(&contents))
+}
+
+fn parse_stat(contents: &str) -> StatCounters {
+    let mut counters = StatCounters::default();
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        let key = fields.next().unwrap_or_default();
+        // `intr` is followed by per-IRQ counts; only the leading total is used.
+        let value = fields.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+        match key {
+            "ctxt" => counters.context_switches = value,
+            "intr" => counters.interrupts = value,
+            "processes" => counters.forks = value,
+            "procs_running" => counters.procs_running = value,
+            "procs_blocked" => counters.procs_blocked = value,
+            _ => {}
+        }
+    }
+    counters
+}
+
+fn read_interrupts() -> Result<Vec<IrqLine>, AppError> {
+    let contents = fs::read_to_string("/proc/interrupts")?;
+    Ok(parse_interrupts(&contents))
+}
+
+fn parse_interrupts(contents: &str) -> Vec<IrqLine> {
+    let mut lines = contents.lines();
+    let cpu_count = lines.next().map_or(0, |header| header.split_whitespace().count());
+
+    lines
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let irq = fields.next()?.strip_suffix(':')?.to_string();
+            let rest: Vec<&str> = fields.collect();
+            let per_cpu: Vec<u64> = rest
+                .iter()
+                .take(cpu_count)
+                .map_while(|v| v.parse().ok())
+                .collect();
+            let description = &rest[per_cpu.len()..];
+
+            // Numbered IRQs read "<chip> <hwirq> <trigger> <devices>"; keep just
+            // the devices. Named ones (LOC, NMI, ...) carry a plain description.
+            let is_numbered = irq.bytes().all(|b| b.is_ascii_digit());
+            let device = match description.iter().position(|token| is_trigger(token)) {
+                Some(i) if is_numbered => description[i + 1..].join(" "),
+                _ => description.join(" "),
+            };
+
+            Some(IrqLine { irq, device, per_cpu })
+        })
+        .collect()
+}
+
+/// The trigger closing a numbered IRQ's chip prefix: `2-edge` or `9-fasteoi`
+/// on x86, a separate `Level` or `Edge` on ARM GIC.
+fn is_trigger(token: &str) -> bool {
+    let token = token.to_ascii_lowercase();
+    ["edge", "level", "fasteoi"]
+        .iter()
+        .any(|trigger| token == *trigger || token.strip_suffix(trigger).is_some_and(|rest| rest.ends_with('-')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_files() {
+        let stat = "cpu  1 2 3 4\nintr 5000 10 20 0\nctxt 123456\nbtime 1700000000\nprocesses 789\nprocs_running 3\nprocs_blocked 1\n";
+        assert_eq!(parse_stat(stat), StatCounters {
+            context_switches: 123456,
+            interrupts: 5000,
+            forks: 789,
+            procs_running: 3,
+            procs_blocked: 1,
+        });
+
+        let interrupts = "           CPU0       CPU1\n  \
+            0:         40          0   IO-APIC   2-edge      timer\n \
+            127:       1200        300   IR-PCI-MSI 327680-edge      xhci_hcd\n  \
+             11:     123456     654321     GICv3  27 Level     arch_timer\n  \
+             45:         10          0   ITS-MSI 524288 Edge      nvme0q0\n\
+            NMI:          5          6   Non-maskable interrupts\n\
+            ERR:          0\n";
+        let lines = parse_interrupts(interrupts);
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[1], IrqLine { irq: "127".into(), device: "xhci_hcd".into(), per_cpu: vec![1200, 300] });
+        assert_eq!(lines[2], IrqLine { irq: "11".into(), device: "arch_timer".into(), per_cpu: vec![123456, 654321] });
+        assert_eq!(lines[3].device, "nvme0q0");
+        assert_eq!(lines[4].device, "Non-maskable interrupts");
+        assert_eq!(lines[5].per_cpu, vec![0]);
+    }
+
+    #[test]
+    fn test_kernel_metrics() {
+        if let Ok(mut kernel) = KernelMetrics::new() {
+            std::thread::sleep(Duration::from_millis(150));
+            assert!(kernel.update().is_ok());
+            assert!(kernel.context_switch_rate() >= 0.0);
+            assert!(!kernel.context_switch_history().history().is_empty());
+        }
+    }
+}
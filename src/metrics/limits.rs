@@ -0,0 +1,229 @@
+//! Kernel resource limits (Linux only): file handles, inode and dentry caches,
+//! pids, conntrack entries and entropy, each read as a used/max pair.
+
+use serde::{Deserialize, Serialize};
+use crate::core::error::AppError;
+use crate::metrics::historical_metric::HistoricalMetric;
+use std::collections::VecDeque;
+use std::fs;
+use std::time::Instant;
+
+/// How a limit's fill level should be interpreted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitKind {
+    /// Running out is bad: high usage is the warning sign
+    Capacity,
+    /// The kernel grows and shrinks this cache itself; usage is informational
+    Cache,
+    /// The value is a reserve: low fill is the warning sign
+    Reserve,
+}
+
+/// One used/max resource pair with its fill-percentage history
+#[derive(Clone)]
+pub struct Limit {
+    pub label: &'static str,
+    pub kind: LimitKind,
+    pub used: u64,
+    pub max: u64,
+    percent: HistoricalMetric<f64>,
+    read: fn() -> Option<(u64, u64)>,
+}
+
+impl Limit {
+    fn new(&(label, kind, read): &Source) -> Option<Self> {
+        let (used, max) = read()?;
+        Some(Self { label, kind, used, max, percent: HistoricalMetric::new(fill_percent(used, max)), read })
+    }
+
+    /// Rebuild a limit from a recorded sample; `None` for unknown labels
+    fn from_sample(sample: &LimitSample, at: Instant) -> Option<Self> {
+        let &(label, kind, read) = SOURCES.iter().find(|(label, ..)| *label == sample.label)?;
+        let percent = HistoricalMetric::new_at(fill_percent(sample.used, sample.max), at);
+        Some(Self { label, kind, used: sample.used, max: sample.max, percent, read })
+    }
+
+    fn update(&mut self) {
+        // Keep the last reading if the file vanishes (e.g. conntrack module unloaded).
+        if let Some((used, max)) = (self.read)() {
+            self.used = used;
+            self.max = max;
+        }
+        self.percent.update(fill_percent(self.used, self.max));
+    }
+
+    /// Current fill (%)
+    pub fn percent(&self) -> f64 { *self.percent.current() }
+
+    /// Historical fill (%)
+    pub fn history(&self) -> &VecDeque<f64> { self.percent.history() }
+
+    /// Highest fill over the history window (%)
+    pub fn peak(&self) -> f64 { self.percent.peak() }
+}
+
+/// Recorded used/max pair for one limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitSample {
+    pub label: String,
+    pub used: u64,
+    pub max: u64,
+}
+
+fn fill_percent(used: u64, max: u64) -> f64 {
+    if max == 0 { 0.0 } else { (used as f64 / max as f64) * 100.0 }
+}
+
+/// A limit source: display label, interpretation and reader
+type Source = (&'static str, LimitKind, fn() -> Option<(u64, u64)>);
+
+const SOURCES: [Source; 6] = [
+    ("Files", LimitKind::Capacity, read_file_handles),
+    ("Inodes", LimitKind::Cache, read_inodes),
+    ("Dentries", LimitKind::Cache, read_dentries),
+    ("PIDs", LimitKind::Capacity, read_pids),
+    ("Conntrack", LimitKind::Capacity, read_conntrack),
+    ("Entropy", LimitKind::Reserve, read_entropy),
+];
+
+/// System limits metrics
+#[derive(Clone)]
+pub struct LimitsMetrics {
+    limits: Vec<Limit>,
+}
+
+impl LimitsMetrics {
+    /// Create a new limits collector. Sources missing on this machine (e.g.
+    /// conntrack without netfilter) are left out; fails if none are readable.
+    pub fn new() -> Result<Self, AppError> {
+        let limits: Vec<Limit> = SOURCES.iter().filter_map(Limit::new).collect();
+
+        if limits.is_empty() {
+            return Err(AppError::System("no kernel limits available".into()));
+        }
+        Ok(Self { limits })
+    }
+
+    /// Update limits metrics. Unreadable limits keep their last reading, so this can't fail.
+    pub fn update(&mut self) {
+        for limit in &mut self.limits {
+            limit.update();
+        }
+    }
+
+    /// Rebuild limits from a recorded sample
+    pub fn from_sample(sample: &[LimitSample], at: Instant) -> Self {
+        Self { limits: sample.iter().filter_map(|s| Limit::from_sample(s, at)).collect() }
+    }
+
+    pub fn apply_sample(&mut self, sample: &[LimitSample], at: Instant) {
+        for s in sample {
+            match self.limits.iter_mut().find(|l| l.label == s.label) {
+                Some(limit) => {
+                    (limit.used, limit.max) = (s.used, s.max);
+                    limit.percent.update_at(fill_percent(s.used, s.max), at);
+                }
+                None => self.limits.extend(Limit::from_sample(s, at)),
+            }
+        }
+    }
+
+    /// All limits available on this machine, in display order
+    pub fn limits(&self) -> &[Limit] { &self.limits }
+
+    /// Look up a limit by its label
+    pub fn get(&self, label: &str) -> Option<&Limit> {
+        self.limits.iter().find(|l| l.label == label)
+    }
+
+    pub fn sample(&self) -> Vec<LimitSample> {
+        self.limits
+            .iter()
+            .map(|l| LimitSample { label: l.label.to_string(), used: l.used, max: l.max })
+            .collect()
+    }
+
+    pub fn resize_history(&mut self, len: usize) {
+        for limit in &mut self.limits {
+            limit.percent.resize(len);
+        }
+    }
+}
+
+fn read_numbers(path: &str) -> Option<Vec<u64>> {
+    let contents = fs::read_to_string(path).ok()?;
+    Some(parse_numbers(&contents))
+}
+
+fn read_number(path: &str) -> Option<u64> {
+    read_numbers(path)?.first().copied()
+}
+
+fn parse_numbers(contents: &str) -> Vec<u64> {
+    contents.split_whitespace().filter_map(|v| v.parse().ok()).collect()
+}
+
+/// `file-nr`: allocated, allocated-but-unused, max
+fn read_file_handles() -> Option<(u64, u64)> {
+    match read_numbers("/proc/sys/fs/file-nr")?[..] {
+        [allocated, unused, max, ..] => Some((allocated.saturating_sub(unused), max)),
+        _ => None,
+    }
+}
+
+/// `inode-nr`: allocated, free
+fn read_inodes() -> Option<(u64, u64)> {
+    match read_numbers("/proc/sys/fs/inode-nr")?[..] {
+        [allocated, free, ..] => Some((allocated.saturating_sub(free), allocated)),
+        _ => None,
+    }
+}
+
+/// `dentry-state`: allocated, unused, ...
+fn read_dentries() -> Option<(u64, u64)> {
+    match read_numbers("/proc/sys/fs/dentry-state")?[..] {
+        [allocated, unused, ..] => Some((allocated.saturating_sub(unused), allocated)),
+        _ => None,
+    }
+}
+
+/// Live scheduling entities (the denominator of `/proc/loadavg`'s 4th field) vs `pid_max`
+fn read_pids() -> Option<(u64, u64)> {
+    let loadavg = fs::read_to_string("/proc/loadavg").ok()?;
+    let tasks = loadavg.split_whitespace().nth(3)?.split_once('/')?.1.parse().ok()?;
+    Some((tasks, read_number("/proc/sys/kernel/pid_max")?))
+}
+
+fn read_conntrack() -> Option<(u64, u64)> {
+    Some((
+        read_number("/proc/sys/net/netfilter/nf_conntrack_count")?,
+        read_number("/proc/sys/net/netfilter/nf_conntrack_max")?,
+    ))
+}
+
+fn read_entropy() -> Option<(u64, u64)> {
+    Some((
+        read_number("/proc/sys/kernel/random/entropy_avail")?,
+        read_number("/proc/sys/kernel/random/poolsize")?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_metrics() {
+        assert_eq!(parse_numbers("1152\t0\t9223372036854775807\n"), vec![1152, 0, 9223372036854775807]);
+        assert_eq!(fill_percent(25, 100), 25.0);
+        assert_eq!(fill_percent(5, 0), 0.0);
+
+        if let Ok(mut limits) = LimitsMetrics::new() {
+            limits.update();
+            for limit in limits.limits() {
+                assert!(limit.percent() >= 0.0);
+                assert!(!limit.history().is_empty());
+            }
+        }
+    }
+}
@@ -1,17 +1,31 @@
 //! Memory metrics collection
 
+use serde::{Deserialize, Serialize};
 use sysinfo::System;
 use crate::core::error::AppError;
 use crate::metrics::historical_metric::HistoricalMetric;
-use std::collections::VecDeque;
+use crate::metrics::paging::{PagingMetrics, PagingSample};
+use std::time::Instant;
 
 /// Memory metrics
+#[derive(Clone)]
 pub struct MemoryMetrics {
     used_percent: HistoricalMetric<f64>,
     used_bytes: HistoricalMetric<u64>,
     pub total_bytes: u64,
     swap_used: HistoricalMetric<u64>,
     pub total_swap: u64,
+    paging: Option<PagingMetrics>,
+}
+
+/// Recorded memory state, in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySample {
+    pub used: u64,
+    pub total: u64,
+    pub swap_used: u64,
+    pub swap_total: u64,
+    pub paging: Option<PagingSample>,
 }
 
 impl MemoryMetrics {
@@ -19,13 +33,26 @@ impl MemoryMetrics {
     pub fn new(system: &System) -> Self {
         let used = system.used_memory();
         let total = system.total_memory();
-        let percent = (used as f64 / total as f64) * 100.0;
+        let percent = used_percent(used, total);
         Self {
             used_percent: HistoricalMetric::new(percent),
             used_bytes: HistoricalMetric::new(used),
             total_bytes: total,
             swap_used: HistoricalMetric::new(system.used_swap()),
             total_swap: system.total_swap(),
+            paging: PagingMetrics::new().ok(),
+        }
+    }
+
+    /// Rebuild memory state from a recorded sample
+    pub fn from_sample(sample: &MemorySample, at: Instant) -> Self {
+        Self {
+            used_percent: HistoricalMetric::new_at(used_percent(sample.used, sample.total), at),
+            used_bytes: HistoricalMetric::new_at(sample.used, at),
+            total_bytes: sample.total,
+            swap_used: HistoricalMetric::new_at(sample.swap_used, at),
+            total_swap: sample.swap_total,
+            paging: sample.paging.as_ref().map(|s| PagingMetrics::from_sample(s, at)),
         }
     }
 
@@ -34,7 +61,7 @@ impl MemoryMetrics {
         system.refresh_memory();
         let used = system.used_memory();
         let total = system.total_memory();
-        let percent = (used as f64 / total as f64) * 100.0;
+        let percent = used_percent(used, total);
         self.used_bytes.update(used);
         self.used_percent.update(percent);
         self.total_bytes = total;
@@ -44,28 +71,65 @@ impl MemoryMetrics {
     }
 
     pub fn used_bytes(&self) -> u64 { *self.used_bytes.current() }
+    pub fn used_bytes_history(&self) -> &HistoricalMetric<u64> { &self.used_bytes }
     pub fn used_percent(&self) -> f64 { *self.used_percent.current() }
-    pub fn used_percent_history(&self) -> &VecDeque<f64> { self.used_percent.history() }
+    pub fn used_percent_history(&self) -> &HistoricalMetric<f64> { &self.used_percent }
 
     pub fn swap_used_bytes(&self) -> u64 { *self.swap_used.current() }
     pub fn swap_used_percent(&self) -> f64 {
         if self.total_swap == 0 { return 0.0; }
         (*self.swap_used.current() as f64 / self.total_swap as f64) * 100.0
     }
-    pub fn swap_history(&self) -> &VecDeque<u64> { self.swap_used.history() }
+    pub fn swap_history(&self) -> &HistoricalMetric<u64> { &self.swap_used }
+
+    /// Swap traffic, faults and OOM kills, where `/proc/vmstat` is available
+    pub fn paging(&self) -> Option<&PagingMetrics> { self.paging.as_ref() }
+    /// Paging is updated on its own so a failed `/proc/vmstat` read doesn't stop the memory figures
+    pub fn paging_mut(&mut self) -> Option<&mut PagingMetrics> { self.paging.as_mut() }
+
+    pub fn sample(&self) -> MemorySample {
+        MemorySample {
+            used: self.used_bytes(),
+            total: self.total_bytes,
+            swap_used: self.swap_used_bytes(),
+            swap_total: self.total_swap,
+            paging: self.paging.as_ref().map(PagingMetrics::sample),
+        }
+    }
+
+    pub fn apply_sample(&mut self, sample: &MemorySample, at: Instant) {
+        self.used_bytes.update_at(sample.used, at);
+        self.used_percent.update_at(used_percent(sample.used, sample.total), at);
+        self.total_bytes = sample.total;
+        self.swap_used.update_at(sample.swap_used, at);
+        self.total_swap = sample.swap_total;
+        match (&mut self.paging, &sample.paging) {
+            (Some(paging), Some(s)) => paging.apply_sample(s, at),
+            (None, Some(s)) => self.paging = Some(PagingMetrics::from_sample(s, at)),
+            _ => {}
+        }
+    }
 
     pub fn resize_history(&mut self, len: usize) {
         self.used_percent.resize(len);
         self.used_bytes.resize(len);
         self.swap_used.resize(len);
+        if let Some(paging) = &mut self.paging {
+            paging.resize_history(len);
+        }
     }
 }
 
+fn used_percent(used: u64, total: u64) -> f64 {
+    if total == 0 { 0.0 } else { (used as f64 / total as f64) * 100.0 }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::manual_range_contains)]
     fn test_memory_metrics() {
         let mut system = System::new_all();
         system.refresh_memory();
@@ -77,6 +141,6 @@ mod tests {
 
         assert!(used_percent >= 0.0 && used_percent <= 100.0);
         assert!(used_bytes <= memory.total_bytes);
-        assert!(!memory.used_percent_history().is_empty());
+        assert!(!memory.used_percent_history().history().is_empty());
     }
 }
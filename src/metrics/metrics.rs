@@ -1,11 +1,12 @@
 //! System metrics collection and management
 
 use crate::core::error::AppError;
-use crate::metrics::{cpu, disk, gpu, memory, network};
-use sysinfo::{LoadAvg, System};
-use std::time::Duration;
-
-const HISTORY_WINDOW: Duration = Duration::from_secs(120);
+use crate::metrics::{cache, cpu, disk, gpu, host, kernel, limits, memory, network, numa, raid};
+use crate::metrics::sample::{FORMAT_VERSION, Header, Sample};
+use crate::metrics::historical_metric::sample_time;
+use sysinfo::System;
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// System metrics collector
 pub struct SystemMetrics {
@@ -15,6 +16,38 @@ pub struct SystemMetrics {
     network: network::NetworkMetrics,
     disk: disk::DiskMetrics,
     gpu: Option<gpu::GpuMetrics>,
+    host: host::HostMetrics,
+    kernel: Option<kernel::KernelMetrics>,
+    limits: Option<limits::LimitsMetrics>,
+    numa: Option<numa::NumaMetrics>,
+    cache: Option<cache::CacheMetrics>,
+    raid: Option<raid::RaidMetrics>,
+    /// When the newest sample was taken
+    sampled_at: Instant,
+    failures: Failures,
+}
+
+/// Collectors whose last update failed. Sampling carries on with their last
+/// values; each failure is reported once, until the collector recovers.
+#[derive(Default)]
+struct Failures {
+    failing: BTreeSet<&'static str>,
+    reports: Vec<String>,
+}
+
+impl Failures {
+    fn check(&mut self, collector: &'static str, result: Result<(), AppError>) {
+        match result {
+            Ok(()) => {
+                self.failing.remove(collector);
+            }
+            Err(e) => {
+                if self.failing.insert(collector) {
+                    self.reports.push(format!("{collector}: {e}; showing the last values"));
+                }
+            }
+        }
+    }
 }
 
 impl Default for SystemMetrics {
@@ -32,25 +65,142 @@ impl SystemMetrics {
         let network = network::NetworkMetrics::new();
         let disk = disk::DiskMetrics::new();
         let gpu = gpu::GpuMetrics::new().ok();
-        Self { system, cpu, memory, network, disk, gpu }
+        let host = host::HostMetrics::new(&system);
+        let kernel = kernel::KernelMetrics::new().ok();
+        let limits = limits::LimitsMetrics::new().ok();
+        // A single node has nothing to compare against; leave the panel out.
+        let numa = numa::NumaMetrics::new().ok().filter(|n| n.node_count() > 1);
+        let cache = cache::CacheMetrics::new().ok();
+        let raid = raid::RaidMetrics::new().ok();
+        let sampled_at = sample_time();
+        Self { system, cpu, memory, network, disk, gpu, host, kernel, limits, numa, cache, raid, sampled_at, failures: Failures::default() }
+    }
+
+    /// Rebuild metrics from a recording, without touching the local machine.
+    /// Histories start at `sample`, placed at `at` on the monotonic clock;
+    /// feed later ones through `apply_sample`.
+    pub fn from_sample(header: &Header, sample: &Sample, at: Instant) -> Self {
+        Self {
+            system: System::new(),
+            cpu: cpu::CpuMetrics::from_sample(header.cpu_name.clone(), &sample.cpu, at),
+            memory: memory::MemoryMetrics::from_sample(&sample.memory, at),
+            network: network::NetworkMetrics::from_sample(&sample.network, at),
+            disk: disk::DiskMetrics::from_sample(&sample.disk, at),
+            gpu: sample.gpu.as_ref().map(|g| gpu::GpuMetrics::from_sample(header.gpu_name.clone(), g, at)),
+            host: host::HostMetrics::from_sample(header, &sample.host),
+            kernel: sample.kernel.as_ref().map(|s| kernel::KernelMetrics::from_sample(s, at)),
+            limits: sample.limits.as_deref().map(|s| limits::LimitsMetrics::from_sample(s, at)),
+            numa: sample.numa.as_deref().map(|s| numa::NumaMetrics::from_sample(s, at)),
+            cache: sample.cache.as_ref().map(|s| cache::CacheMetrics::from_sample(s, at)),
+            raid: sample.raid.as_deref().map(raid::RaidMetrics::from_sample),
+            sampled_at: at,
+            failures: Failures::default(),
+        }
+    }
+
+    /// Copy of everything shown, histories included, for display only: the
+    /// copy can't collect further samples. Used to freeze the screen.
+    pub fn snapshot(&self) -> Self {
+        Self {
+            system: System::new(),
+            cpu: self.cpu.snapshot(),
+            memory: self.memory.clone(),
+            network: self.network.snapshot(),
+            disk: self.disk.snapshot(),
+            gpu: self.gpu.as_ref().map(gpu::GpuMetrics::snapshot),
+            host: self.host.clone(),
+            kernel: self.kernel.clone(),
+            limits: self.limits.clone(),
+            numa: self.numa.clone(),
+            cache: self.cache.clone(),
+            raid: self.raid.clone(),
+            sampled_at: self.sampled_at,
+            failures: Failures::default(),
+        }
+    }
+
+    /// Advance every collector by one recorded sample, taken at `at`
+    pub fn apply_sample(&mut self, sample: &Sample, at: Instant) {
+        self.sampled_at = at;
+        self.cpu.apply_sample(&sample.cpu, at);
+        self.memory.apply_sample(&sample.memory, at);
+        self.network.apply_sample(&sample.network, at);
+        self.disk.apply_sample(&sample.disk, at);
+        self.host.apply_sample(&sample.host);
+        // Optional collectors may appear mid-recording (e.g. an array assembled later).
+        match (&mut self.gpu, &sample.gpu) {
+            (Some(gpu), Some(s)) => gpu.apply_sample(s, at),
+            (None, Some(s)) => self.gpu = Some(gpu::GpuMetrics::from_sample(None, s, at)),
+            _ => {}
+        }
+        match (&mut self.kernel, &sample.kernel) {
+            (Some(kernel), Some(s)) => kernel.apply_sample(s, at),
+            (None, Some(s)) => self.kernel = Some(kernel::KernelMetrics::from_sample(s, at)),
+            _ => {}
+        }
+        match (&mut self.limits, &sample.limits) {
+            (Some(limits), Some(s)) => limits.apply_sample(s, at),
+            (None, Some(s)) => self.limits = Some(limits::LimitsMetrics::from_sample(s, at)),
+            _ => {}
+        }
+        match (&mut self.numa, &sample.numa) {
+            (Some(numa), Some(s)) => numa.apply_sample(s, at),
+            (None, Some(s)) => self.numa = Some(numa::NumaMetrics::from_sample(s, at)),
+            _ => {}
+        }
+        match (&mut self.cache, &sample.cache) {
+            (Some(cache), Some(s)) => cache.apply_sample(s, at),
+            (None, Some(s)) => self.cache = Some(cache::CacheMetrics::from_sample(s, at)),
+            _ => {}
+        }
+        match (&mut self.raid, &sample.raid) {
+            (Some(raid), Some(s)) => raid.apply_sample(s),
+            (None, Some(s)) => self.raid = Some(raid::RaidMetrics::from_sample(s)),
+            _ => {}
+        }
     }
 
     /// Update all metrics
     pub fn update(&mut self) -> Result<(), AppError> {
+        self.sampled_at = sample_time();
         self.cpu.update(&mut self.system)?;
+        if let Some(numa) = &mut self.numa {
+            self.failures.check("numa", numa.update(&self.system));
+        }
         self.memory.update(&mut self.system)?;
+        if let Some(paging) = self.memory.paging_mut() {
+            self.failures.check("paging", paging.update());
+        }
+        if let Some(cache) = &mut self.cache {
+            self.failures.check("cache", cache.update());
+        }
         self.network.update()?;
         self.disk.update()?;
+        if let Some(raid) = &mut self.raid {
+            self.failures.check("raid", raid.update());
+        }
         if let Some(gpu) = &mut self.gpu {
             gpu.update()?;
         }
+        self.host.update(&mut self.system)?;
+        if let Some(kernel) = &mut self.kernel {
+            self.failures.check("kernel", kernel.update());
+        }
+        if let Some(limits) = &mut self.limits {
+            limits.update();
+        }
         Ok(())
     }
 
+    /// Collector failures since the last call, one message each
+    pub fn take_failures(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.failures.reports)
+    }
+
     /// Resize all history buffers to hold `sample_interval`-spaced samples covering
-    /// a fixed 2-minute window.
-    pub fn resize_history(&mut self, sample_interval: Duration) {
-        let len = (HISTORY_WINDOW.as_secs_f64() / sample_interval.as_secs_f64()).ceil() as usize;
+    /// `window`.
+    pub fn resize_history(&mut self, sample_interval: Duration, window: Duration) {
+        let len = Self::history_len(sample_interval, window);
         self.cpu.resize_history(len);
         self.memory.resize_history(len);
         self.network.resize_history(len);
@@ -58,6 +208,55 @@ impl SystemMetrics {
         if let Some(gpu) = &mut self.gpu {
             gpu.resize_history(len);
         }
+        if let Some(kernel) = &mut self.kernel {
+            kernel.resize_history(len);
+        }
+        if let Some(limits) = &mut self.limits {
+            limits.resize_history(len);
+        }
+        if let Some(numa) = &mut self.numa {
+            numa.resize_history(len);
+        }
+        if let Some(cache) = &mut self.cache {
+            cache.resize_history(len);
+        }
+    }
+
+    /// Number of `sample_interval`-spaced samples that cover the history window
+    pub fn history_len(sample_interval: Duration, window: Duration) -> usize {
+        (window.as_secs_f64() / sample_interval.as_secs_f64()).ceil() as usize
+    }
+
+    /// Static host identity for the top of a recording
+    pub fn header(&self) -> Header {
+        Header {
+            version: FORMAT_VERSION,
+            host_name: self.host.host_name.clone(),
+            kernel_version: self.host.kernel_version.clone(),
+            os_version: self.host.os_version.clone(),
+            boot_time: self.host.boot_time,
+            cpu_name: self.cpu.name.clone(),
+            gpu_name: self.gpu.as_ref().and_then(|g| g.name.clone()),
+        }
+    }
+
+    /// Current values of every collector, stamped with the wall-clock time
+    pub fn sample(&self) -> Sample {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+        Sample {
+            ts,
+            cpu: self.cpu.sample(),
+            memory: self.memory.sample(),
+            network: self.network.sample(),
+            disk: self.disk.sample(),
+            gpu: self.gpu.as_ref().map(gpu::GpuMetrics::sample),
+            host: self.host.sample(),
+            kernel: self.kernel.as_ref().map(kernel::KernelMetrics::sample),
+            limits: self.limits.as_ref().map(limits::LimitsMetrics::sample),
+            numa: self.numa.as_ref().map(numa::NumaMetrics::sample),
+            cache: self.cache.as_ref().map(cache::CacheMetrics::sample),
+            raid: self.raid.as_ref().map(|r| r.arrays().to_vec()),
+        }
     }
 
     pub fn cpu(&self) -> &cpu::CpuMetrics { &self.cpu }
@@ -65,7 +264,13 @@ impl SystemMetrics {
     pub fn network(&self) -> &network::NetworkMetrics { &self.network }
     pub fn disk(&self) -> &disk::DiskMetrics { &self.disk }
     pub fn gpu(&self) -> Option<&gpu::GpuMetrics> { self.gpu.as_ref() }
+    pub fn host(&self) -> &host::HostMetrics { &self.host }
+    pub fn kernel(&self) -> Option<&kernel::KernelMetrics> { self.kernel.as_ref() }
+    pub fn limits(&self) -> Option<&limits::LimitsMetrics> { self.limits.as_ref() }
+    pub fn numa(&self) -> Option<&numa::NumaMetrics> { self.numa.as_ref() }
+    pub fn cache(&self) -> Option<&cache::CacheMetrics> { self.cache.as_ref() }
+    pub fn raid(&self) -> Option<&raid::RaidMetrics> { self.raid.as_ref() }
 
-    /// Current 1/5/15-minute load averages. Free read — no refresh needed.
-    pub fn load_average() -> LoadAvg { System::load_average() }
+    /// When the newest sample was taken; charts measure time back from here
+    pub fn sampled_at(&self) -> Instant { self.sampled_at }
 }
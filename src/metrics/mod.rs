@@ -1,8 +1,19 @@
+pub mod alerts;
+pub mod cache;
 pub mod cpu;
 pub mod memory;
 pub mod network;
+pub mod numa;
+pub mod paging;
 pub mod disk;
 pub mod gpu;
+pub mod host;
+pub mod kernel;
+pub mod limits;
+pub mod raid;
+pub mod sample;
+pub mod source;
+#[allow(clippy::module_inception)]
 pub mod metrics;
 pub mod historical_metric;
 
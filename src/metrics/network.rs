@@ -1,11 +1,19 @@
 //! Network metrics collection
 
+use serde::{Deserialize, Serialize};
 use sysinfo::Networks;
 use crate::core::error::AppError;
 use crate::metrics::historical_metric::HistoricalMetric;
 use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
 
+/// Recorded rates for one interface, in Mb/s
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceSample {
+    pub rx: f64,
+    pub tx: f64,
+}
+
 /// Network metrics
 pub struct NetworkMetrics {
     networks: Networks,
@@ -20,12 +28,26 @@ impl NetworkMetrics {
         // Pre-populate interface_stats so interface names are known immediately,
         // before the first update() call populates real rate data.
         let interface_stats = networks
-            .iter()
-            .map(|(name, _)| (name.to_string(), (HistoricalMetric::new(0.0), HistoricalMetric::new(0.0))))
+            .keys()
+            .map(|name| (name.to_string(), (HistoricalMetric::new(0.0), HistoricalMetric::new(0.0))))
             .collect();
         Self { networks, interface_stats, last_update: Instant::now() }
     }
 
+    /// Rebuild per-interface rates from a recorded sample
+    pub fn from_sample(sample: &BTreeMap<String, InterfaceSample>, at: Instant) -> Self {
+        let interface_stats = sample
+            .iter()
+            .map(|(name, s)| (name.clone(), (HistoricalMetric::new_at(s.rx, at), HistoricalMetric::new_at(s.tx, at))))
+            .collect();
+        Self { networks: Networks::new(), interface_stats, last_update: Instant::now() }
+    }
+
+    /// Copy of the current rates for display, without the interface list
+    pub fn snapshot(&self) -> Self {
+        Self { networks: Networks::new(), interface_stats: self.interface_stats.clone(), last_update: self.last_update }
+    }
+
     /// Update network metrics
     pub fn update(&mut self) -> Result<(), AppError> {
         self.networks.refresh(true);
@@ -68,6 +90,24 @@ impl NetworkMetrics {
         self.interface_stats.get(name).map(|(rx, tx)| (rx, tx))
     }
 
+    /// Current rates for every interface, keyed by name
+    pub fn sample(&self) -> BTreeMap<String, InterfaceSample> {
+        self.interface_stats
+            .iter()
+            .map(|(name, (rx, tx))| (name.clone(), InterfaceSample { rx: *rx.current(), tx: *tx.current() }))
+            .collect()
+    }
+
+    pub fn apply_sample(&mut self, sample: &BTreeMap<String, InterfaceSample>, at: Instant) {
+        for (name, s) in sample {
+            let entry = self.interface_stats
+                .entry(name.clone())
+                .or_insert_with(|| (HistoricalMetric::new_at(0.0, at), HistoricalMetric::new_at(0.0, at)));
+            entry.0.update_at(s.rx, at);
+            entry.1.update_at(s.tx, at);
+        }
+    }
+
     pub fn resize_history(&mut self, len: usize) {
         for (rx, tx) in self.interface_stats.values_mut() {
             rx.resize(len);
@@ -0,0 +1,326 @@
+//! NUMA topology metrics (Linux only): per-node memory, allocation locality
+//! and CPU usage grouped by node, from `/sys/devices/system/node`.
+
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+use crate::core::error::AppError;
+use crate::metrics::historical_metric::HistoricalMetric;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const NODE_ROOT: &str = "/sys/devices/system/node";
+
+/// One NUMA node
+#[derive(Clone)]
+pub struct NumaNode {
+    pub id: usize,
+    /// Logical CPU numbers belonging to this node
+    pub cpus: Vec<usize>,
+    pub mem_total: u64,
+    pub mem_free: u64,
+    path: PathBuf,
+    used_percent: HistoricalMetric<f64>,
+    cpu_usage: HistoricalMetric<f64>,
+    core_usage: Vec<f64>,
+    hit_rate: HistoricalMetric<f64>,
+    miss_rate: HistoricalMetric<f64>,
+    last_hit: u64,
+    last_miss: u64,
+}
+
+/// Recorded state of one NUMA node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NumaNodeSample {
+    pub id: usize,
+    pub cpus: Vec<usize>,
+    pub mem_total: u64,
+    pub mem_free: u64,
+    pub core_usage: Vec<f64>,
+    pub hit_rate: f64,
+    pub miss_rate: f64,
+}
+
+impl NumaNode {
+    fn new(id: usize, path: PathBuf) -> Result<Self, AppError> {
+        let cpus = parse_cpulist(&fs::read_to_string(path.join("cpulist"))?);
+        let (mem_total, mem_free) = read_node_meminfo(&path)?;
+        let (last_hit, last_miss) = read_numastat(&path)?;
+        Ok(Self {
+            id,
+            cpus,
+            mem_total,
+            mem_free,
+            path,
+            used_percent: HistoricalMetric::new(used_percent(mem_total, mem_free)),
+            cpu_usage: HistoricalMetric::new(0.0),
+            core_usage: vec![],
+            hit_rate: HistoricalMetric::new(0.0),
+            miss_rate: HistoricalMetric::new(0.0),
+            last_hit,
+            last_miss,
+        })
+    }
+
+    fn from_sample(sample: &NumaNodeSample, at: Instant) -> Self {
+        Self {
+            id: sample.id,
+            cpus: sample.cpus.clone(),
+            mem_total: sample.mem_total,
+            mem_free: sample.mem_free,
+            path: PathBuf::new(),
+            used_percent: HistoricalMetric::new_at(used_percent(sample.mem_total, sample.mem_free), at),
+            cpu_usage: HistoricalMetric::new_at(average(&sample.core_usage), at),
+            core_usage: sample.core_usage.clone(),
+            hit_rate: HistoricalMetric::new_at(sample.hit_rate, at),
+            miss_rate: HistoricalMetric::new_at(sample.miss_rate, at),
+            last_hit: 0,
+            last_miss: 0,
+        }
+    }
+
+    fn apply_sample(&mut self, sample: &NumaNodeSample, at: Instant) {
+        (self.mem_total, self.mem_free) = (sample.mem_total, sample.mem_free);
+        self.used_percent.update_at(used_percent(sample.mem_total, sample.mem_free), at);
+        self.core_usage = sample.core_usage.clone();
+        self.cpu_usage.update_at(average(&sample.core_usage), at);
+        self.hit_rate.update_at(sample.hit_rate, at);
+        self.miss_rate.update_at(sample.miss_rate, at);
+    }
+
+    /// A file that can't be read leaves its values as they were; the error
+    /// is returned once everything else is updated.
+    fn update(&mut self, system: &System, elapsed_secs: f64) -> Result<(), AppError> {
+        let meminfo = read_node_meminfo(&self.path);
+        if let Ok(mem) = &meminfo {
+            (self.mem_total, self.mem_free) = *mem;
+        }
+        self.used_percent.update(used_percent(self.mem_total, self.mem_free));
+
+        let numastat = read_numastat(&self.path);
+        let (hit_rate, miss_rate) = match &numastat {
+            Ok((hit, miss)) => {
+                let rates = (
+                    hit.saturating_sub(self.last_hit) as f64 / elapsed_secs,
+                    miss.saturating_sub(self.last_miss) as f64 / elapsed_secs,
+                );
+                (self.last_hit, self.last_miss) = (*hit, *miss);
+                rates
+            }
+            Err(_) => (self.hit_rate(), self.miss_rate()),
+        };
+        self.hit_rate.update(hit_rate);
+        self.miss_rate.update(miss_rate);
+
+        let cores = system.cpus();
+        self.core_usage = self.cpus
+            .iter()
+            .filter_map(|&cpu| cores.get(cpu).map(|c| c.cpu_usage() as f64))
+            .collect();
+        self.cpu_usage.update(average(&self.core_usage));
+        meminfo.and(numastat).map(drop)
+    }
+
+    pub fn mem_used(&self) -> u64 { self.mem_total.saturating_sub(self.mem_free) }
+
+    /// Current memory usage (%)
+    pub fn used_percent(&self) -> f64 { *self.used_percent.current() }
+    pub fn used_percent_history(&self) -> &HistoricalMetric<f64> { &self.used_percent }
+
+    /// Average usage (%) of this node's CPUs
+    pub fn cpu_usage(&self) -> f64 { *self.cpu_usage.current() }
+
+    /// Usage (%) of each CPU in this node, in the order of `cpus`
+    pub fn core_usage(&self) -> &[f64] { &self.core_usage }
+
+    /// Allocations satisfied on this node as intended, per second
+    pub fn hit_rate(&self) -> f64 { *self.hit_rate.current() }
+
+    /// Allocations that wanted another node but landed here, per second
+    pub fn miss_rate(&self) -> f64 { *self.miss_rate.current() }
+
+    fn sample(&self) -> NumaNodeSample {
+        NumaNodeSample {
+            id: self.id,
+            cpus: self.cpus.clone(),
+            mem_total: self.mem_total,
+            mem_free: self.mem_free,
+            core_usage: self.core_usage.clone(),
+            hit_rate: self.hit_rate(),
+            miss_rate: self.miss_rate(),
+        }
+    }
+
+    fn resize_history(&mut self, len: usize) {
+        self.used_percent.resize(len);
+        self.cpu_usage.resize(len);
+        self.hit_rate.resize(len);
+        self.miss_rate.resize(len);
+    }
+}
+
+/// NUMA metrics
+#[derive(Clone)]
+pub struct NumaMetrics {
+    nodes: Vec<NumaNode>,
+    last_update: Instant,
+}
+
+impl NumaMetrics {
+    /// Create a new NUMA metrics collector. Fails where the node sysfs tree is unavailable.
+    pub fn new() -> Result<Self, AppError> {
+        let mut nodes = fs::read_dir(NODE_ROOT)?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let id = entry.file_name().to_str()?.strip_prefix("node")?.parse().ok()?;
+                NumaNode::new(id, entry.path()).ok()
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|n| n.id);
+
+        if nodes.is_empty() {
+            return Err(AppError::System("no NUMA nodes found".into()));
+        }
+        Ok(Self { nodes, last_update: Instant::now() })
+    }
+
+    /// Update NUMA metrics. Expects CPU usage in `system` to have just been refreshed.
+    pub fn update(&mut self, system: &System) -> Result<(), AppError> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update);
+
+        if elapsed < Duration::from_millis(100) {
+            return Ok(());
+        }
+
+        // One node failing doesn't hold up the others.
+        let mut result = Ok(());
+        for node in &mut self.nodes {
+            result = result.and(node.update(system, elapsed.as_secs_f64()));
+        }
+        self.last_update = now;
+        result
+    }
+
+    pub fn nodes(&self) -> &[NumaNode] { &self.nodes }
+
+    pub fn node_count(&self) -> usize { self.nodes.len() }
+
+    pub fn sample(&self) -> Vec<NumaNodeSample> {
+        self.nodes.iter().map(NumaNode::sample).collect()
+    }
+
+    /// Rebuild NUMA state from a recorded sample
+    pub fn from_sample(sample: &[NumaNodeSample], at: Instant) -> Self {
+        Self { nodes: sample.iter().map(|s| NumaNode::from_sample(s, at)).collect(), last_update: Instant::now() }
+    }
+
+    pub fn apply_sample(&mut self, sample: &[NumaNodeSample], at: Instant) {
+        for s in sample {
+            match self.nodes.iter_mut().find(|n| n.id == s.id) {
+                Some(node) => node.apply_sample(s, at),
+                None => self.nodes.push(NumaNode::from_sample(s, at)),
+            }
+        }
+    }
+
+    pub fn resize_history(&mut self, len: usize) {
+        for node in &mut self.nodes {
+            node.resize_history(len);
+        }
+    }
+}
+
+fn used_percent(total: u64, free: u64) -> f64 {
+    if total == 0 { return 0.0; }
+    (total.saturating_sub(free) as f64 / total as f64) * 100.0
+}
+
+fn average(values: &[f64]) -> f64 {
+    if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 }
+}
+
+/// Parse a kernel cpulist such as `0-3,8-11` into CPU numbers.
+fn parse_cpulist(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter(|r| !r.is_empty())
+        .filter_map(|range| {
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            Some(start.parse::<usize>().ok()?..=end.parse::<usize>().ok()?)
+        })
+        .flatten()
+        .collect()
+}
+
+/// Read `(MemTotal, MemFree)` in bytes from a node's `meminfo`.
+fn read_node_meminfo(node: &Path) -> Result<(u64, u64), AppError> {
+    Ok(parse_node_meminfo(&fs::read_to_string(node.join("meminfo"))?))
+}
+
+/// Lines look like `Node 0 MemTotal:  16384 kB`.
+fn parse_node_meminfo(contents: &str) -> (u64, u64) {
+    let mut total = 0;
+    let mut free = 0;
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace().skip(2);
+        let key = fields.next().unwrap_or_default();
+        let kb: u64 = fields.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+        match key {
+            "MemTotal:" => total = kb * 1024,
+            "MemFree:" => free = kb * 1024,
+            _ => {}
+        }
+    }
+    (total, free)
+}
+
+/// Read `(numa_hit, numa_miss)` from a node's `numastat`.
+fn read_numastat(node: &Path) -> Result<(u64, u64), AppError> {
+    Ok(parse_numastat(&fs::read_to_string(node.join("numastat"))?))
+}
+
+fn parse_numastat(contents: &str) -> (u64, u64) {
+    let mut hit = 0;
+    let mut miss = 0;
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once(' ') else { continue };
+        let value = value.trim().parse().unwrap_or(0);
+        match key {
+            "numa_hit" => hit = value,
+            "numa_miss" => miss = value,
+            _ => {}
+        }
+    }
+    (hit, miss)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node_files() {
+        assert_eq!(parse_cpulist("0-3,8-9,12\n"), vec![0, 1, 2, 3, 8, 9, 12]);
+        assert_eq!(parse_cpulist("\n"), Vec::<usize>::new());
+        assert_eq!(
+            parse_node_meminfo("Node 1 MemTotal:  2048 kB\nNode 1 MemFree:   512 kB\nNode 1 MemUsed:  1536 kB\n"),
+            (2048 * 1024, 512 * 1024),
+        );
+        assert_eq!(parse_numastat("numa_hit 4616730\nnuma_miss 12\nnuma_foreign 0\n"), (4616730, 12));
+    }
+
+    #[test]
+    fn test_numa_metrics() {
+        let mut system = System::new_all();
+        if let Ok(mut numa) = NumaMetrics::new() {
+            std::thread::sleep(Duration::from_millis(150));
+            system.refresh_cpu_all();
+            assert!(numa.update(&system).is_ok());
+            for node in numa.nodes() {
+                assert!(node.used_percent() >= 0.0 && node.used_percent() <= 100.0);
+                assert!(!node.used_percent_history().history().is_empty());
+            }
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Software RAID health (Linux only): array state, members and resync
+//! progress from `/proc/mdstat`.
+
+use serde::{Deserialize, Serialize};
+use crate::core::error::AppError;
+use std::fs;
+
+const MDSTAT: &str = "/proc/mdstat";
+
+/// State of one array member as flagged in `/proc/mdstat`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MemberState {
+    Active,
+    Faulty,
+    Spare,
+    WriteMostly,
+    Replacement,
+}
+
+/// One component device of an array
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MdMember {
+    pub name: String,
+    pub state: MemberState,
+}
+
+/// A resync, recovery, reshape or check in progress
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncProgress {
+    /// `resync`, `recovery`, `reshape` or `check`
+    pub action: String,
+    pub percent: f64,
+    /// Estimated minutes remaining
+    pub eta_minutes: Option<f64>,
+    /// Current speed in KiB/s
+    pub speed_kib: Option<u64>,
+}
+
+/// One md array
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MdArray {
+    pub name: String,
+    pub active: bool,
+    pub level: Option<String>,
+    pub members: Vec<MdMember>,
+    /// Devices the array expects
+    pub expected_disks: Option<usize>,
+    /// Devices currently in sync
+    pub working_disks: Option<usize>,
+    /// Per-slot status, e.g. `UU_`
+    pub status: String,
+    pub sync: Option<SyncProgress>,
+}
+
+impl MdArray {
+    /// True when a member is missing or failed, or the array isn't running
+    pub fn is_degraded(&self) -> bool {
+        let missing = matches!((self.expected_disks, self.working_disks), (Some(e), Some(w)) if w < e);
+        !self.active || missing || self.failed_disks() > 0 || self.status.contains('_')
+    }
+
+    /// Members marked faulty
+    pub fn failed_disks(&self) -> usize {
+        self.members.iter().filter(|m| m.state == MemberState::Faulty).count()
+    }
+}
+
+/// Software RAID metrics
+#[derive(Clone)]
+pub struct RaidMetrics {
+    arrays: Vec<MdArray>,
+}
+
+impl RaidMetrics {
+    /// Create a new RAID metrics collector. Fails when no md arrays exist.
+    pub fn new() -> Result<Self, AppError> {
+        let arrays = parse_mdstat(&fs::read_to_string(MDSTAT)?);
+        if arrays.is_empty() {
+            return Err(AppError::System("no md arrays found".into()));
+        }
+        Ok(Self { arrays })
+    }
+
+    /// Update RAID metrics
+    pub fn update(&mut self) -> Result<(), AppError> {
+        self.arrays = parse_mdstat(&fs::read_to_string(MDSTAT)?);
+        Ok(())
+    }
+
+    /// Rebuild RAID state from recorded arrays
+    pub fn from_sample(arrays: &[MdArray]) -> Self {
+        Self { arrays: arrays.to_vec() }
+    }
+
+    pub fn apply_sample(&mut self, arrays: &[MdArray]) {
+        self.arrays = arrays.to_vec();
+    }
+
+    pub fn arrays(&self) -> &[MdArray] { &self.arrays }
+
+    pub fn degraded_count(&self) -> usize {
+        self.arrays.iter().filter(|a| a.is_degraded()).count()
+    }
+}
+
+fn parse_mdstat(contents: &str) -> Vec<MdArray> {
+    let mut arrays: Vec<MdArray> = vec![];
+
+    for line in contents.lines() {
+        if let Some((name, rest)) = line.split_once(" : ")
+            && name.starts_with("md")
+        {
+            arrays.push(parse_array_header(name.trim(), rest));
+            continue;
+        }
+
+        // Indented detail lines belong to the last array header seen.
+        let Some(array) = arrays.last_mut() else { continue };
+        let trimmed = line.trim();
+        if trimmed.contains(" blocks") {
+            parse_blocks_line(array, trimmed);
+        } else if let Some(sync) = parse_sync_line(trimmed) {
+            array.sync = Some(sync);
+        }
+    }
+    arrays
+}
+
+/// `active raid1 sdb1[1] sda1[0](F)` (level is absent on inactive arrays)
+fn parse_array_header(name: &str, rest: &str) -> MdArray {
+    let mut fields = rest.split_whitespace().peekable();
+    let active = fields.next() == Some("active");
+    // Skip `(read-only)` / `(auto-read-only)` markers.
+    while fields.peek().is_some_and(|f| f.starts_with('(')) {
+        fields.next();
+    }
+    let level = fields.next_if(|f| !f.contains('[')).map(str::to_string);
+
+    let members = fields
+        .filter_map(|field| {
+            let (name, flags) = field.split_once('[')?;
+            let state = match flags.split_once(']')?.1 {
+                "(F)" => MemberState::Faulty,
+                "(S)" => MemberState::Spare,
+                "(W)" => MemberState::WriteMostly,
+                "(R)" => MemberState::Replacement,
+                _ => MemberState::Active,
+            };
+            Some(MdMember { name: name.to_string(), state })
+        })
+        .collect();
+
+    MdArray {
+        name: name.to_string(),
+        active,
+        level,
+        members,
+        expected_disks: None,
+        working_disks: None,
+        status: String::new(),
+        sync: None,
+    }
+}
+
+/// `1953382464 blocks super 1.2 [2/2] [UU]`
+fn parse_blocks_line(array: &mut MdArray, line: &str) {
+    for field in line.split_whitespace() {
+        let Some(inner) = field.strip_prefix('[').and_then(|f| f.strip_suffix(']')) else { continue };
+        if let Some((expected, working)) = inner.split_once('/') {
+            array.expected_disks = expected.parse().ok();
+            array.working_disks = working.parse().ok();
+        } else if inner.chars().all(|c| c == 'U' || c == '_') {
+            array.status = inner.to_string();
+        }
+    }
+}
+
+/// `[=>....]  recovery =  8.5% (166327808/1953382400) finish=154.2min speed=193122K/sec`
+fn parse_sync_line(line: &str) -> Option<SyncProgress> {
+    let (before, after) = line.split_once(" = ")?;
+    let action = before.split_whitespace().last()?;
+    if !matches!(action, "resync" | "recovery" | "reshape" | "check") {
+        return None;
+    }
+    let mut fields = after.split_whitespace();
+    let percent = fields.next()?.strip_suffix('%')?.parse().ok()?;
+
+    let mut eta_minutes = None;
+    let mut speed_kib = None;
+    for field in fields {
+        if let Some(v) = field.strip_prefix("finish=").and_then(|v| v.strip_suffix("min")) {
+            eta_minutes = v.parse().ok();
+        } else if let Some(v) = field.strip_prefix("speed=").and_then(|v| v.strip_suffix("K/sec")) {
+            speed_kib = v.parse().ok();
+        }
+    }
+
+    Some(SyncProgress { action: action.to_string(), percent, eta_minutes, speed_kib })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mdstat() {
+        let mdstat = "Personalities : [raid1] [raid6] [raid5] [raid4]\n\
+            md0 : active raid1 sdb1[1] sda1[0]\n      \
+            1953382464 blocks super 1.2 [2/2] [UU]\n      \
+            bitmap: 0/15 pages [0KB], 65536KB chunk\n\
+            \n\
+            md1 : active raid5 sdd1[3] sdc1[1] sdb2[0](F) sde1[4](S)\n      \
+            3906764800 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]\n      \
+            [=>...................]  recovery =  8.5% (166327808/1953382400) finish=154.2min speed=193122K/sec\n\
+            \n\
+            md2 : inactive sdf1[0](S)\n      \
+            976630488 blocks super 1.2\n\
+            \n\
+            unused devices: <none>\n";
+
+        let arrays = parse_mdstat(mdstat);
+        assert_eq!(arrays.len(), 3);
+
+        assert_eq!(arrays[0].level.as_deref(), Some("raid1"));
+        assert_eq!(arrays[0].status, "UU");
+        assert!(!arrays[0].is_degraded());
+
+        assert_eq!(arrays[1].members.len(), 4);
+        assert_eq!(arrays[1].members[2], MdMember { name: "sdb2".into(), state: MemberState::Faulty });
+        assert_eq!(arrays[1].members[3].state, MemberState::Spare);
+        assert_eq!((arrays[1].expected_disks, arrays[1].working_disks), (Some(3), Some(2)));
+        assert!(arrays[1].is_degraded());
+        assert_eq!(arrays[1].sync, Some(SyncProgress {
+            action: "recovery".into(),
+            percent: 8.5,
+            eta_minutes: Some(154.2),
+            speed_kib: Some(193122),
+        }));
+
+        assert!(!arrays[2].active);
+        assert_eq!(arrays[2].level, None);
+        assert!(arrays[2].is_degraded());
+    }
+}
@@ -0,0 +1,398 @@
+//! Serializable point-in-time view of `SystemMetrics`, used to record and
+//! replay sessions and for machine-readable output. Each collector owns its own sample type; this module ties them
+//! together, and names the values in them for alert rules and `sysvitals query`.
+
+use serde::{Deserialize, Serialize};
+use crate::metrics::cache::{ArcSample, CacheSample, ZramSample};
+use crate::metrics::cpu::CpuSample;
+use crate::metrics::disk::DiskSample;
+use crate::metrics::gpu::GpuSample;
+use crate::metrics::host::HostSample;
+use crate::metrics::kernel::KernelSample;
+use crate::metrics::limits::LimitSample;
+use crate::metrics::memory::MemorySample;
+use crate::metrics::network::InterfaceSample;
+use crate::metrics::numa::NumaNodeSample;
+use crate::metrics::raid::MdArray;
+use std::collections::BTreeMap;
+
+/// Bumped whenever a sample type changes shape incompatibly.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Host identity, written once at the top of every recording file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Header {
+    pub version: u32,
+    pub host_name: Option<String>,
+    pub kernel_version: Option<String>,
+    pub os_version: Option<String>,
+    /// Seconds since the Unix epoch
+    pub boot_time: u64,
+    pub cpu_name: Option<String>,
+    pub gpu_name: Option<String>,
+}
+
+/// Everything the UI shows at one instant. Collectors that are absent on the
+/// recording machine are `None`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sample {
+    /// Milliseconds since the Unix epoch
+    pub ts: u64,
+    pub cpu: CpuSample,
+    pub memory: MemorySample,
+    pub network: BTreeMap<String, InterfaceSample>,
+    pub disk: DiskSample,
+    pub gpu: Option<GpuSample>,
+    pub host: HostSample,
+    pub kernel: Option<KernelSample>,
+    pub limits: Option<Vec<LimitSample>>,
+    pub numa: Option<Vec<NumaNodeSample>>,
+    pub cache: Option<CacheSample>,
+    pub raid: Option<Vec<MdArray>>,
+}
+
+/// `sysvitals snapshot` output: one sample and the host it was taken on,
+/// the same shape as a one-sample recording. `header.version` versions the schema.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub header: Header,
+    pub sample: Sample,
+}
+
+// ── Metric names ─────────────────────────────────────────────────────────────
+
+/// A value alert rules and `sysvitals query` can name
+#[derive(Debug)]
+pub struct Metric {
+    pub name: &'static str,
+    /// As sampled: `%`, `bytes`, `Mb/s`, `MB/s`, `/s`, ... or empty for plain numbers
+    pub unit: &'static str,
+    /// The value in a sample; `device` is empty for whole-machine collectors
+    pub read: fn(sample: &Sample, device: &str) -> Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct Collector {
+    pub name: &'static str,
+    /// Values are named `<collector>.<device>.<value>` rather than `<collector>.<value>`
+    pub per_device: bool,
+    pub metrics: &'static [Metric],
+}
+
+/// Every nameable value, by collector
+pub const COLLECTORS: &[Collector] = &[
+    Collector {
+        name: "cpu",
+        per_device: false,
+        metrics: &[
+            Metric { name: "usage", unit: "%", read: |s, _| Some(s.cpu.usage) },
+            Metric { name: "temperature", unit: "°C", read: |s, _| s.cpu.temperature },
+            Metric { name: "load_1m", unit: "", read: |s, _| Some(s.cpu.load[0]) },
+            Metric { name: "load_5m", unit: "", read: |s, _| Some(s.cpu.load[1]) },
+            Metric { name: "load_15m", unit: "", read: |s, _| Some(s.cpu.load[2]) },
+        ],
+    },
+    Collector {
+        name: "memory",
+        per_device: false,
+        metrics: &[
+            Metric { name: "used", unit: "bytes", read: |s, _| Some(s.memory.used as f64) },
+            Metric { name: "total", unit: "bytes", read: |s, _| Some(s.memory.total as f64) },
+            Metric { name: "swap_used", unit: "bytes", read: |s, _| Some(s.memory.swap_used as f64) },
+            Metric { name: "swap_total", unit: "bytes", read: |s, _| Some(s.memory.swap_total as f64) },
+            Metric { name: "used_percent", unit: "%", read: |s, _| percent(s.memory.used, s.memory.total) },
+            Metric { name: "swap_used_percent", unit: "%", read: |s, _| percent(s.memory.swap_used, s.memory.swap_total) },
+        ],
+    },
+    Collector {
+        name: "paging",
+        per_device: false,
+        metrics: &[
+            Metric { name: "swap_in", unit: "pages/s", read: |s, _| Some(s.memory.paging.as_ref()?.swap_in) },
+            Metric { name: "swap_out", unit: "pages/s", read: |s, _| Some(s.memory.paging.as_ref()?.swap_out) },
+            Metric { name: "faults", unit: "/s", read: |s, _| Some(s.memory.paging.as_ref()?.faults) },
+            Metric { name: "major_faults", unit: "/s", read: |s, _| Some(s.memory.paging.as_ref()?.major_faults) },
+            Metric { name: "scanned", unit: "pages/s", read: |s, _| Some(s.memory.paging.as_ref()?.scanned) },
+            Metric { name: "stolen", unit: "pages/s", read: |s, _| Some(s.memory.paging.as_ref()?.stolen) },
+            Metric { name: "oom_kills", unit: "", read: |s, _| Some(s.memory.paging.as_ref()?.oom_kills as f64) },
+        ],
+    },
+    Collector {
+        name: "network",
+        per_device: true,
+        metrics: &[
+            Metric { name: "rx", unit: "Mb/s", read: |s, d| Some(s.network.get(d)?.rx) },
+            Metric { name: "tx", unit: "Mb/s", read: |s, d| Some(s.network.get(d)?.tx) },
+        ],
+    },
+    Collector {
+        name: "disk",
+        per_device: false,
+        metrics: &[
+            Metric { name: "read", unit: "MB/s", read: |s, _| Some(s.disk.read) },
+            Metric { name: "write", unit: "MB/s", read: |s, _| Some(s.disk.write) },
+        ],
+    },
+    Collector {
+        name: "gpu",
+        per_device: false,
+        metrics: &[
+            Metric { name: "usage", unit: "%", read: |s, _| Some(s.gpu.as_ref()?.usage) },
+            Metric { name: "memory", unit: "%", read: |s, _| Some(s.gpu.as_ref()?.memory) },
+        ],
+    },
+    Collector {
+        name: "kernel",
+        per_device: false,
+        metrics: &[
+            Metric { name: "context_switches", unit: "/s", read: |s, _| Some(s.kernel.as_ref()?.context_switches) },
+            Metric { name: "interrupts", unit: "/s", read: |s, _| Some(s.kernel.as_ref()?.interrupts) },
+            Metric { name: "forks", unit: "/s", read: |s, _| Some(s.kernel.as_ref()?.forks) },
+            Metric { name: "procs_running", unit: "", read: |s, _| Some(s.kernel.as_ref()?.procs_running) },
+            Metric { name: "procs_blocked", unit: "", read: |s, _| Some(s.kernel.as_ref()?.procs_blocked) },
+        ],
+    },
+    Collector {
+        name: "host",
+        per_device: false,
+        metrics: &[
+            Metric { name: "uptime", unit: "s", read: |s, _| Some(s.host.uptime as f64) },
+            Metric { name: "processes", unit: "", read: |s, _| Some(s.host.processes as f64) },
+            Metric { name: "threads", unit: "", read: |s, _| Some(s.host.threads as f64) },
+        ],
+    },
+    Collector {
+        name: "numa",
+        per_device: true,
+        metrics: &[
+            Metric {
+                name: "usage",
+                unit: "%",
+                read: |s, d| node(s, d).filter(|n| !n.core_usage.is_empty()).map(|n| n.core_usage.iter().sum::<f64>() / n.core_usage.len() as f64),
+            },
+            Metric { name: "mem_total", unit: "bytes", read: |s, d| Some(node(s, d)?.mem_total as f64) },
+            Metric { name: "mem_free", unit: "bytes", read: |s, d| Some(node(s, d)?.mem_free as f64) },
+            Metric { name: "mem_used", unit: "bytes", read: |s, d| node(s, d).map(|n| n.mem_total.saturating_sub(n.mem_free) as f64) },
+            Metric { name: "hit_rate", unit: "/s", read: |s, d| Some(node(s, d)?.hit_rate) },
+            Metric { name: "miss_rate", unit: "/s", read: |s, d| Some(node(s, d)?.miss_rate) },
+        ],
+    },
+    Collector {
+        name: "limits",
+        per_device: true,
+        metrics: &[
+            Metric { name: "used", unit: "", read: |s, d| Some(limit(s, d)?.used as f64) },
+            Metric { name: "max", unit: "", read: |s, d| Some(limit(s, d)?.max as f64) },
+            Metric { name: "used_percent", unit: "%", read: |s, d| limit(s, d).and_then(|l| percent(l.used, l.max)) },
+        ],
+    },
+    // The ZFS ARC is device `arc`; zram and bcache devices go by their names.
+    Collector {
+        name: "cache",
+        per_device: true,
+        metrics: &[
+            Metric {
+                name: "hit_ratio",
+                unit: "%",
+                read: |s, d| arc(s, d).map(|a| a.hit_ratio).or_else(|| s.cache.as_ref()?.bcache.iter().find(|b| b.name == d).map(|b| b.hit_ratio)),
+            },
+            Metric { name: "size", unit: "bytes", read: |s, d| Some(arc(s, d)?.size) },
+            Metric { name: "target", unit: "bytes", read: |s, d| Some(arc(s, d)?.target) },
+            Metric { name: "max", unit: "bytes", read: |s, d| Some(arc(s, d)?.max as f64) },
+            Metric { name: "orig", unit: "bytes", read: |s, d| Some(zram(s, d)?.orig as f64) },
+            Metric { name: "compr", unit: "bytes", read: |s, d| Some(zram(s, d)?.compr as f64) },
+            Metric { name: "mem_used", unit: "bytes", read: |s, d| Some(zram(s, d)?.mem_used as f64) },
+            Metric { name: "compression_ratio", unit: "", read: |s, d| zram(s, d).filter(|z| z.compr > 0).map(|z| z.orig as f64 / z.compr as f64) },
+        ],
+    },
+    Collector {
+        name: "raid",
+        per_device: true,
+        metrics: &[
+            Metric { name: "active", unit: "", read: |s, d| Some(array(s, d)?.active as u8 as f64) },
+            Metric { name: "expected_disks", unit: "", read: |s, d| Some(array(s, d)?.expected_disks? as f64) },
+            Metric { name: "working_disks", unit: "", read: |s, d| Some(array(s, d)?.working_disks? as f64) },
+            Metric { name: "failed_disks", unit: "", read: |s, d| Some(array(s, d)?.failed_disks() as f64) },
+            Metric { name: "sync_percent", unit: "%", read: |s, d| Some(array(s, d)?.sync.as_ref()?.percent) },
+            Metric {
+                name: "missing_disks",
+                unit: "",
+                read: |s, d| array(s, d).and_then(|a| Some(a.expected_disks?.saturating_sub(a.working_disks?) as f64)),
+            },
+        ],
+    },
+];
+
+fn percent(used: u64, total: u64) -> Option<f64> {
+    (total > 0).then(|| used as f64 * 100.0 / total as f64)
+}
+
+fn node<'a>(sample: &'a Sample, id: &str) -> Option<&'a NumaNodeSample> {
+    sample.numa.as_ref()?.iter().find(|n| n.id.to_string() == id)
+}
+
+fn limit<'a>(sample: &'a Sample, label: &str) -> Option<&'a LimitSample> {
+    sample.limits.as_ref()?.iter().find(|l| l.label == label)
+}
+
+fn arc<'a>(sample: &'a Sample, device: &str) -> Option<&'a ArcSample> {
+    sample.cache.as_ref()?.arc.as_ref().filter(|_| device == "arc")
+}
+
+fn zram<'a>(sample: &'a Sample, name: &str) -> Option<&'a ZramSample> {
+    sample.cache.as_ref()?.zram.iter().find(|z| z.name == name)
+}
+
+fn array<'a>(sample: &'a Sample, name: &str) -> Option<&'a MdArray> {
+    sample.raid.as_ref()?.iter().find(|a| a.name == name)
+}
+
+/// A metric name checked against `COLLECTORS`
+#[derive(Debug, Clone)]
+pub struct MetricName<'a> {
+    pub collector: &'static Collector,
+    /// Set exactly when the collector is per device
+    pub device: Option<&'a str>,
+    pub metric: &'static Metric,
+}
+
+/// Look up `<collector>[.<device>].<value>`; errors list the valid names.
+pub fn metric_name(name: &str) -> Result<MetricName<'_>, String> {
+    let (collector_name, rest) = name.split_once('.').ok_or_else(|| format!("{name}: expected <collector>.<value>, e.g. cpu.usage"))?;
+    let collector = COLLECTORS.iter().find(|c| c.name == collector_name).ok_or_else(|| {
+        let names: Vec<&str> = COLLECTORS.iter().map(|c| c.name).collect();
+        format!("unknown collector {collector_name}; one of {}", names.join(", "))
+    })?;
+    let (device, value) = match collector.per_device {
+        true => {
+            let (device, value) = rest.rsplit_once('.').ok_or_else(|| {
+                format!("{name}: {collector_name} needs a device, e.g. {collector_name}.<device>.{}", collector.metrics[0].name)
+            })?;
+            (Some(device), value)
+        }
+        false => (None, rest),
+    };
+    let metric = collector.metrics.iter().find(|m| m.name == value).ok_or_else(|| {
+        let names: Vec<&str> = collector.metrics.iter().map(|m| m.name).collect();
+        format!("unknown {collector_name} value {value}; one of {}", names.join(", "))
+    })?;
+    Ok(MetricName { collector, device, metric })
+}
+
+/// `90`, `90s`, `15m`, `1h` or `2d`, in seconds
+pub fn parse_window(val: &str) -> Option<u64> {
+    let (number, unit) = match val.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => val.split_at(i),
+        None => (val, "s"),
+    };
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    };
+    number.parse::<u64>().ok().filter(|n| *n > 0).map(|n| n * scale)
+}
+
+/// Hand-built values for tests that need a sample without reading the machine
+#[cfg(test)]
+pub mod test_support {
+    use super::*;
+    use crate::metrics::paging::PagingSample;
+    use crate::metrics::raid::{MdMember, MemberState};
+
+    const GIB: u64 = 1 << 30;
+
+    pub fn header() -> Header {
+        Header {
+            version: FORMAT_VERSION,
+            host_name: Some("box".into()),
+            kernel_version: None,
+            os_version: None,
+            boot_time: 0,
+            cpu_name: None,
+            gpu_name: None,
+        }
+    }
+
+    /// A sample with every collector present and one device of each kind
+    pub fn sample() -> Sample {
+        Sample {
+            ts: 1_700_000_000_000,
+            cpu: CpuSample { usage: 12.5, temperature: Some(45.0), load: [0.5, 0.25, 0.125] },
+            memory: MemorySample {
+                used: 4 * GIB,
+                total: 16 * GIB,
+                swap_used: 0,
+                swap_total: 2 * GIB,
+                paging: Some(PagingSample { swap_in: 0.0, swap_out: 0.0, faults: 120.0, major_faults: 1.0, scanned: 0.0, stolen: 0.0, oom_kills: 0 }),
+            },
+            network: BTreeMap::from([("eth0".to_string(), InterfaceSample { rx: 8.0, tx: 1.5 })]),
+            disk: DiskSample { read: 2.0, write: 0.5 },
+            gpu: Some(GpuSample { usage: 30.0, memory: 25.0 }),
+            host: HostSample { uptime: 3600, processes: 200, threads: 800, users: Some(1), cores: 8 },
+            kernel: Some(KernelSample { context_switches: 1000.0, interrupts: 500.0, forks: 2.0, procs_running: 1.0, procs_blocked: 0.0, irqs: vec![] }),
+            limits: Some(vec![LimitSample { label: "Files".into(), used: 1000, max: 100_000 }]),
+            numa: None,
+            cache: Some(CacheSample {
+                arc: None,
+                zram: vec![ZramSample { name: "zram0".into(), orig: GIB, compr: GIB / 4, mem_used: GIB / 4 + GIB / 16 }],
+                bcache: vec![],
+            }),
+            raid: Some(vec![MdArray {
+                name: "md0".into(),
+                active: true,
+                level: Some("raid1".into()),
+                members: ["sda1", "sdb1"].map(|name| MdMember { name: name.into(), state: MemberState::Active }).into(),
+                expected_disks: Some(2),
+                working_disks: Some(2),
+                status: "UU".into(),
+                sync: None,
+            }]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::SystemMetrics;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_sample_round_trip() {
+        let mut system = SystemMetrics::new();
+        assert!(system.update().is_ok());
+
+        let sample = system.sample();
+        let line = serde_json::to_string(&sample).unwrap();
+        assert!(!line.contains('\n'));
+
+        let parsed: Sample = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.ts, sample.ts);
+        assert_eq!(parsed.memory.total, sample.memory.total);
+        assert_eq!(parsed.network.len(), sample.network.len());
+        assert_eq!(system.header().version, FORMAT_VERSION);
+    }
+
+    #[test]
+    fn test_rebuild_from_samples() {
+        let mut live = SystemMetrics::new();
+        assert!(live.update().is_ok());
+        let header = live.header();
+        let first = live.sample();
+        assert!(live.update().is_ok());
+        let second = live.sample();
+
+        let start = Instant::now();
+        let mut replayed = SystemMetrics::from_sample(&header, &first, start);
+        replayed.apply_sample(&second, start + Duration::from_millis(second.ts - first.ts));
+        assert_eq!(replayed.cpu().usage_history().history().len(), 2);
+        assert_eq!(replayed.sampled_at() - start, Duration::from_millis(second.ts - first.ts));
+        assert_eq!(replayed.cpu().usage_percent(), second.cpu.usage);
+        assert_eq!(replayed.memory().used_bytes(), second.memory.used);
+        assert_eq!(replayed.host().host_name, header.host_name);
+        assert_eq!(replayed.network().interface_names().len(), second.network.len());
+    }
+}
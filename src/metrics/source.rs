@@ -0,0 +1,114 @@
+//! Where the UI's metrics come from: the live collectors, or a recording
+//! being replayed. `Ui::draw` only ever sees a `MetricsSource`.
+
+use crate::core::error::AppError;
+use crate::metrics::SystemMetrics;
+use std::time::{Duration, Instant};
+
+/// Playback position of a non-live source, for display
+#[derive(Debug, Clone)]
+pub struct Playback {
+    pub playing: bool,
+    pub speed: u32,
+    /// Zero-based index of the sample currently shown
+    pub position: usize,
+    pub len: usize,
+    /// Timestamp of the sample currently shown, in ms since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// Playback controls; live sources ignore them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaybackCommand {
+    TogglePause,
+    Faster,
+    Slower,
+    /// Move by this many samples, pausing playback
+    Step(i64),
+    /// Move by this many seconds of recorded time
+    Seek(i64),
+    Start,
+    End,
+}
+
+pub trait MetricsSource {
+    /// Metrics as of the current position
+    fn metrics(&self) -> &SystemMetrics;
+
+    /// Called on every loop iteration. Returns `true` when `metrics` changed.
+    /// `interval` is the user's refresh interval; sources with their own
+    /// timing may ignore it.
+    fn tick(&mut self, interval: Duration) -> Result<bool, AppError>;
+
+    /// Size history buffers to cover `window` with samples spaced
+    /// `sample_interval` apart
+    fn resize_history(&mut self, sample_interval: Duration, window: Duration);
+
+    /// Playback state, or `None` for live data
+    fn playback(&self) -> Option<Playback> { None }
+
+    /// Apply a playback control. Returns `true` if it was handled.
+    fn control(&mut self, _command: PlaybackCommand) -> bool { false }
+
+    /// Problems collecting that didn't stop sampling, to tell the user about
+    fn take_failures(&mut self) -> Vec<String> { vec![] }
+}
+
+/// Live collection from the local machine
+pub struct LiveSource {
+    system: SystemMetrics,
+    last_update: Instant,
+}
+
+impl LiveSource {
+    pub fn new() -> Self {
+        Self { system: SystemMetrics::new(), last_update: Instant::now() }
+    }
+}
+
+impl MetricsSource for LiveSource {
+    fn metrics(&self) -> &SystemMetrics { &self.system }
+
+    fn tick(&mut self, interval: Duration) -> Result<bool, AppError> {
+        let now = Instant::now();
+        if now.duration_since(self.last_update) < interval {
+            return Ok(false);
+        }
+        self.system.update()?;
+        self.last_update = now;
+        Ok(true)
+    }
+
+    fn resize_history(&mut self, sample_interval: Duration, window: Duration) {
+        self.system.resize_history(sample_interval, window);
+    }
+
+    fn take_failures(&mut self) -> Vec<String> {
+        self.system.take_failures()
+    }
+}
+
+/// A still copy of another source, shown while the display is paused so
+/// the screen can be read while collection carries on underneath
+pub struct FrozenSource {
+    system: SystemMetrics,
+    playback: Option<Playback>,
+}
+
+impl FrozenSource {
+    pub fn new(source: &dyn MetricsSource) -> Self {
+        Self { system: source.metrics().snapshot(), playback: source.playback() }
+    }
+}
+
+impl MetricsSource for FrozenSource {
+    fn metrics(&self) -> &SystemMetrics { &self.system }
+
+    fn tick(&mut self, _interval: Duration) -> Result<bool, AppError> { Ok(false) }
+
+    fn resize_history(&mut self, sample_interval: Duration, window: Duration) {
+        self.system.resize_history(sample_interval, window);
+    }
+
+    fn playback(&self) -> Option<Playback> { self.playback.clone() }
+}
@@ -0,0 +1,114 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use crate::metrics::alerts::{Alert, Alerts, Severity, State};
+use crate::ui::header::format_utc_seconds;
+use std::time::{Duration, Instant};
+
+/// Rows the list grows to before the rest are summarised in its title
+pub const MAX_ROWS: usize = 5;
+/// The bell rings again this often until the alarm is acknowledged.
+const BELL_REPEAT: Duration = Duration::from_secs(30);
+/// Length of each on and off phase of the border flash
+const FLASH_PHASE: Duration = Duration::from_millis(500);
+
+/// Attention asked for by a newly firing alert, until acknowledged with `a`
+/// or every alert that raised it has stopped firing
+#[derive(Debug, Clone, Copy)]
+pub struct Alarm {
+    pub bell: bool,
+    pub flash: bool,
+    started: Instant,
+    rung: Option<Instant>,
+}
+
+impl Alarm {
+    pub fn new(bell: bool, flash: bool) -> Self {
+        Self { bell, flash, started: Instant::now(), rung: None }
+    }
+
+    /// Add another firing alert's wishes; a new bell rings straight away.
+    pub fn raise(&mut self, bell: bool, flash: bool) {
+        if bell {
+            self.bell = true;
+            self.rung = None;
+        }
+        self.flash |= flash;
+    }
+
+    /// Whether the bell should ring now: at once, then every `BELL_REPEAT`.
+    pub fn ring(&mut self, now: Instant) -> bool {
+        if !self.bell || self.rung.is_some_and(|t| now.duration_since(t) < BELL_REPEAT) {
+            return false;
+        }
+        self.rung = Some(now);
+        true
+    }
+
+    /// Outer border colour at `now`; `None` in the off phase or without flashing
+    pub fn flash_color(&self, now: Instant) -> Option<Color> {
+        let phase = now.duration_since(self.started).as_millis() / FLASH_PHASE.as_millis();
+        (self.flash && phase.is_multiple_of(2)).then_some(Color::Red)
+    }
+}
+
+pub fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Info => Color::Cyan,
+        Severity::Warning => Color::Yellow,
+        Severity::Critical => Color::Red,
+    }
+}
+
+/// Height the alert list needs, or 0 when nothing is pending, firing or just resolved
+pub fn height(alerts: &Alerts) -> u16 {
+    match alerts.visible().len() {
+        0 => 0,
+        n => n.min(MAX_ROWS) as u16 + 2,
+    }
+}
+
+pub fn draw_list(frame: &mut Frame, area: Rect, alerts: &Alerts) {
+    let visible = alerts.visible();
+    let firing = visible.iter().filter(|a| a.state == State::Firing).count();
+    let hidden = visible.len().saturating_sub(MAX_ROWS);
+    let mut title = format!(" 🚨 Alerts: {firing} firing ");
+    if hidden > 0 {
+        title.push_str(&format!("(+{hidden} more) "));
+    }
+    let border = visible
+        .iter()
+        .filter(|a| a.state == State::Firing)
+        .map(|a| a.severity)
+        .max()
+        .map_or(Color::DarkGray, severity_color);
+
+    let lines: Vec<Line> = visible.iter().take(MAX_ROWS).copied().map(row).collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .title(Span::styled(title, Style::default().fg(Color::White).bold()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border)),
+        ),
+        area,
+    );
+}
+
+fn row(alert: &Alert) -> Line<'static> {
+    let (state_style, text_style) = match alert.state {
+        State::Firing => (Style::default().fg(severity_color(alert.severity)).bold(), Style::default().fg(Color::White)),
+        State::Pending => (Style::default().fg(Color::Yellow), Style::default().fg(Color::Gray)),
+        _ => (Style::default().fg(Color::Green), Style::default().fg(Color::DarkGray)),
+    };
+    let value = alert.value.map_or("—".to_string(), |v| format!("{v:.1}"));
+    Line::from(vec![
+        Span::styled(format!("{:<9}", alert.state.label()), state_style),
+        Span::styled(format!("{:<9}", alert.severity.label()), state_style),
+        Span::styled(format!("{}  ", alert.name), text_style.bold()),
+        Span::styled(format!("{} = {value} (rule {})  ", alert.metric, alert.condition()), text_style),
+        Span::styled(format!("since {}", format_utc_seconds(alert.since / 1000)), Style::default().fg(Color::DarkGray)),
+    ])
+}
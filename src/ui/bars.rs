@@ -1,49 +1,52 @@
-//! Compact bars view — live values only, no history.
+//! Compact bars view — live values, with a faint marker at each metric's
+//! peak over the history window.
 
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::prelude::{Color, Style, Stylize};
 use ratatui::widgets::{Gauge, Paragraph};
 use crate::metrics::SystemMetrics;
-use crate::ui::chart_utils::{dynamic_bound, format_rate, usage_color};
+use crate::ui::Ui;
+use crate::ui::chart_utils::{format_count, format_rate, peak_bound, usage_color};
+use crate::ui::limits::limit_color;
+use crate::ui::raid::array_color;
 
 struct Row {
     label: &'static str,
     ratio: f64,
     color: Color,
     value: String,
+    /// Highest ratio seen over the history window
+    peak: Option<f64>,
 }
 
 impl Row {
     fn new(label: &'static str, ratio: f64, color: Color, value: String) -> Self {
-        Self { label, ratio: ratio.clamp(0.0, 1.0), color, value }
+        Self { label, ratio: ratio.clamp(0.0, 1.0), color, value, peak: None }
+    }
+
+    fn peak(mut self, ratio: f64) -> Self {
+        self.peak = Some(ratio.clamp(0.0, 1.0));
+        self
     }
 }
 
-pub fn draw_bars(
-    frame: &mut Frame,
-    area: Rect,
-    system: &SystemMetrics,
-    show_cpu: bool,
-    show_memory: bool,
-    show_gpu: bool,
-    show_network: bool,
-    show_disk: bool,
-    selected_interface: usize,
-) {
+/// Draw one labelled gauge per enabled metric, honouring the `show_*` toggles on `ui`.
+pub fn draw_bars(frame: &mut Frame, area: Rect, system: &SystemMetrics, ui: &Ui) {
     let mut rows: Vec<Row> = vec![];
 
-    if show_cpu {
+    if ui.show_cpu {
         let cpu = system.cpu();
         let pct = cpu.usage_percent();
-        rows.push(Row::new("CPU", pct / 100.0, usage_color(pct), format!("{pct:.1}%")));
+        let peak = cpu.usage_history().peak();
+        rows.push(Row::new("CPU", pct / 100.0, usage_color(pct), format!("{pct:.1}%")).peak(peak / 100.0));
 
         if let Some(temp) = cpu.temperature() {
             rows.push(Row::new("TEMP", temp / 100.0, usage_color(temp), format!("{temp:.1}°C")));
         }
     }
 
-    if show_memory {
+    if ui.show_memory {
         let mem = system.memory();
         let pct = mem.used_percent();
         let used_gb = mem.used_bytes() as f64 / 1024.0f64.powi(3);
@@ -53,7 +56,7 @@ pub fn draw_bars(
             pct / 100.0,
             usage_color(pct),
             format!("{used_gb:.1} / {total_gb:.1} GB"),
-        ));
+        ).peak(mem.used_percent_history().peak() / 100.0));
 
         if mem.total_swap > 0 {
             let swap_pct = mem.swap_used_percent();
@@ -64,43 +67,83 @@ pub fn draw_bars(
                 swap_pct / 100.0,
                 usage_color(swap_pct),
                 format!("{swap_used_gb:.1} / {swap_total_gb:.1} GB"),
-            ));
+            ).peak(mem.swap_history().peak() / mem.total_swap as f64));
         }
     }
 
-    if show_gpu {
-        if let Some(gpu) = system.gpu() {
-            let pct = gpu.usage_percent();
-            rows.push(Row::new("GPU", pct / 100.0, usage_color(pct), format!("{pct:.1}%")));
-            let vram_pct = gpu.memory_percent();
-            rows.push(Row::new("VRAM", vram_pct / 100.0, usage_color(vram_pct), format!("{vram_pct:.1}%")));
-        }
+    if ui.show_gpu && let Some(gpu) = system.gpu() {
+        let pct = gpu.usage_percent();
+        let peak = gpu.usage_history().peak();
+        rows.push(Row::new("GPU", pct / 100.0, usage_color(pct), format!("{pct:.1}%")).peak(peak / 100.0));
+        let vram_pct = gpu.memory_percent();
+        let vram_peak = gpu.memory_history().peak();
+        rows.push(Row::new("VRAM", vram_pct / 100.0, usage_color(vram_pct), format!("{vram_pct:.1}%")).peak(vram_peak / 100.0));
     }
 
-    if show_network {
+    if ui.show_network {
         let net = system.network();
         let interfaces = net.interface_names();
-        let selected = selected_interface.min(interfaces.len().saturating_sub(1));
-        if let Some(iface) = interfaces.get(selected).cloned() {
-            if let Some((rx_hist, tx_hist)) = net.get_interface_stats(&iface) {
-                let rx = *rx_hist.current();
-                let tx = *tx_hist.current();
-                let rx_bound = dynamic_bound(rx_hist.history());
-                let tx_bound = dynamic_bound(tx_hist.history());
-                rows.push(Row::new("NET ↓", rx / rx_bound, Color::Cyan, format!("{} Mb/s", format_rate(rx))));
-                rows.push(Row::new("NET ↑", tx / tx_bound, Color::Magenta,   format!("{} Mb/s", format_rate(tx))));
-            }
+        let selected = ui.selected_interface.min(interfaces.len().saturating_sub(1));
+        if let Some(iface) = interfaces.get(selected).cloned()
+            && let Some((rx_hist, tx_hist)) = net.get_interface_stats(&iface)
+        {
+            let rx = *rx_hist.current();
+            let tx = *tx_hist.current();
+            let rx_peak = rx_hist.peak();
+            let tx_peak = tx_hist.peak();
+            let rx_bound = peak_bound(rx_peak);
+            let tx_bound = peak_bound(tx_peak);
+            rows.push(Row::new("NET ↓", rx / rx_bound, Color::Cyan, format!("{} Mb/s", format_rate(rx))).peak(rx_peak / rx_bound));
+            rows.push(Row::new("NET ↑", tx / tx_bound, Color::Magenta,   format!("{} Mb/s", format_rate(tx))).peak(tx_peak / tx_bound));
         }
     }
 
-    if show_disk {
+    if ui.show_disk {
         let disk = system.disk();
         let read  = disk.read_rate();
         let write = disk.write_rate();
-        let read_bound  = dynamic_bound(disk.read_history());
-        let write_bound = dynamic_bound(disk.write_history());
-        rows.push(Row::new("DISK ↓", read  / read_bound,  Color::Cyan, format!("{} MB/s", format_rate(read))));
-        rows.push(Row::new("DISK ↑", write / write_bound, Color::Magenta, format!("{} MB/s", format_rate(write))));
+        let read_peak  = disk.read_history().peak();
+        let write_peak = disk.write_history().peak();
+        let read_bound  = peak_bound(read_peak);
+        let write_bound = peak_bound(write_peak);
+        rows.push(Row::new("DISK ↓", read  / read_bound,  Color::Cyan, format!("{} MB/s", format_rate(read))).peak(read_peak / read_bound));
+        rows.push(Row::new("DISK ↑", write / write_bound, Color::Magenta, format!("{} MB/s", format_rate(write))).peak(write_peak / write_bound));
+    }
+
+    if ui.show_raid && let Some(raid) = system.raid() {
+        let arrays = raid.arrays();
+        let degraded = raid.degraded_count();
+        // A running resync matters more than the healthy-array count.
+        let syncing = arrays.iter().find_map(|a| a.sync.as_ref().map(|s| (a, s)));
+        let worst = arrays.iter().find(|a| a.is_degraded()).or(syncing.map(|(a, _)| a));
+        let color = worst.map_or(Color::Green, array_color);
+        let (ratio, value) = match syncing {
+            Some((array, sync)) => (sync.percent / 100.0, format!("{} {} {:.1}%", array.name, sync.action, sync.percent)),
+            None if degraded > 0 => (1.0, format!("{degraded} DEGRADED")),
+            None => (1.0, format!("{} arrays OK", arrays.len())),
+        };
+        rows.push(Row::new("RAID", ratio, color, value));
+    }
+
+    if ui.show_kernel && let Some(kernel) = system.kernel() {
+        let ctxt = kernel.context_switch_rate();
+        let intr = kernel.interrupt_rate();
+        let ctxt_peak = kernel.context_switch_history().peak();
+        let intr_peak = kernel.interrupt_history().peak();
+        let ctxt_bound = peak_bound(ctxt_peak);
+        let intr_bound = peak_bound(intr_peak);
+        rows.push(Row::new("CTXSW", ctxt / ctxt_bound, Color::Cyan, format!("{}/s", format_rate(ctxt))).peak(ctxt_peak / ctxt_bound));
+        rows.push(Row::new("IRQ", intr / intr_bound, Color::Yellow, format!("{}/s", format_rate(intr))).peak(intr_peak / intr_bound));
+    }
+
+    if ui.show_limits && let Some(files) = system.limits().and_then(|l| l.get("Files")) {
+        let pct = files.percent();
+        rows.push(Row::new(
+            "FILES",
+            pct / 100.0,
+            limit_color(files),
+            format!("{} / {}", format_count(files.used), format_count(files.max)),
+        ).peak(files.peak() / 100.0));
     }
 
     if rows.is_empty() {
@@ -140,12 +183,24 @@ pub fn draw_bars(
         );
 
         // Bottom line: full-width gauge bar
+        let bar = lines[1];
         frame.render_widget(
             Gauge::default()
                 .ratio(row.ratio)
                 .label("")
                 .gauge_style(Style::default().fg(row.color)),
-            lines[1],
+            bar,
         );
+
+        // Peak marker, only where it falls past the filled part of the bar.
+        if let Some(peak) = row.peak && bar.width > 0 {
+            let column = (peak * (bar.width - 1) as f64).round() as u16;
+            if peak > row.ratio && column as f64 >= (row.ratio * bar.width as f64).ceil() {
+                frame.render_widget(
+                    Paragraph::new("│").style(Style::default().fg(Color::DarkGray)),
+                    Rect { x: bar.x + column, y: bar.y, width: 1, height: 1 },
+                );
+            }
+        }
     }
 }
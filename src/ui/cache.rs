@@ -0,0 +1,96 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Axis, Block, Borders, Chart, Paragraph};
+use crate::metrics::cache::CacheMetrics;
+use crate::ui::chart_utils::{chart_areas, peak_bound, rate_axis_labels, split_horizontal, Series, ChartView};
+
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+pub fn draw_chart(frame: &mut Frame, area: Rect, cache: &CacheMetrics, view: ChartView) -> Vec<Rect> {
+    let (title_area, chart_area) = chart_areas(area);
+
+    frame.render_widget(
+        Paragraph::new(Span::styled("🧊 Caches", Style::default().fg(Color::White).bold())),
+        title_area,
+    );
+
+    let width = chart_area.width as usize;
+    let in_gb = |history| Series::new(history, view.window, width).scaled(1.0 / GB);
+
+    // (label, colour, series in GB) for every series present on this machine.
+    let mut series: Vec<(&str, Color, Series)> = vec![];
+    if let Some(arc) = cache.arc() {
+        series.push(("ARC", Color::Cyan, in_gb(arc.size_history())));
+        series.push(("ARC target", Color::Gray, in_gb(arc.target_history())));
+    }
+    if !cache.zram().is_empty() {
+        series.push(("zram data", Color::Yellow, in_gb(cache.zram_orig_history())));
+        series.push(("zram compressed", Color::Green, in_gb(cache.zram_compr_history())));
+    }
+
+    let bound = peak_bound(series.iter().map(|(_, _, s)| s.peak()).fold(0.0, f64::max));
+    let datasets = series
+        .iter()
+        .flat_map(|(name, color, s)| s.lines(Some(name.to_string()), Style::default().fg(*color)))
+        .collect();
+
+    let mut block = Block::default().title("Size (GB)").borders(Borders::ALL);
+    let primary = match cache.arc() {
+        Some(arc) => Some(("ARC", arc.size_history())),
+        None => (!cache.zram().is_empty()).then(|| ("zram data", cache.zram_orig_history())),
+    };
+    if let Some((label, history)) = primary {
+        block = view.stats_footer(block, Some(label), history, |v| format!("{:.2}", v / GB));
+    }
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(view.window.axis())
+        .y_axis(Axis::default()
+            .bounds([0.0, bound])
+            .style(Style::default().fg(Color::Gray))
+            .labels(rate_axis_labels(bound)));
+
+    let label = |text: String| Span::styled(text, Style::default().fg(Color::White).bold());
+    let mut lines: Vec<Line> = vec![];
+
+    if let Some(arc) = cache.arc() {
+        lines.push(Line::from(vec![
+            label(" ARC ".into()),
+            Span::raw(format!(
+                "{:.1} GB (target {:.1}, max {:.1}) | hit {:.1}%",
+                arc.size() / GB,
+                arc.target() / GB,
+                arc.max_bytes as f64 / GB,
+                arc.hit_ratio(),
+            )),
+        ]));
+    }
+    for dev in cache.zram() {
+        lines.push(Line::from(vec![
+            label(format!(" {} ", dev.name)),
+            Span::raw(format!(
+                "{:.2} GB → {:.2} GB ({:.1}x) | RAM used {:.2} GB",
+                dev.orig_bytes as f64 / GB,
+                dev.compr_bytes as f64 / GB,
+                dev.ratio(),
+                dev.mem_used_bytes as f64 / GB,
+            )),
+        ]));
+    }
+    for dev in cache.bcache() {
+        lines.push(Line::from(vec![
+            label(format!(" {} ", dev.name)),
+            Span::raw(format!("hit {:.1}% | dirty {}", dev.hit_ratio, dev.dirty)),
+        ]));
+    }
+
+    let details = Paragraph::new(lines).block(Block::default().title("Details").borders(Borders::ALL));
+
+    let (left, right) = split_horizontal(chart_area);
+    frame.render_widget(chart, left);
+    frame.render_widget(details, right);
+    vec![view.window.plot_area(left, &rate_axis_labels(bound))]
+}
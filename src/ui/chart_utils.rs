@@ -1,24 +1,302 @@
 //! Shared helpers for all chart panels.
 
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::prelude::Color;
-use std::collections::VecDeque;
-
-// ── History ──────────────────────────────────────────────────────────────────
-
-/// Trim `history` to the last `width` samples and convert to `(x, y)` pairs
-/// suitable for a ratatui `Dataset`.
-pub fn trim_to_width(history: &VecDeque<f64>, width: usize) -> Vec<(f64, f64)> {
-    history
-        .iter()
-        .rev()
-        .take(width)
-        .collect::<Vec<_>>()
-        .into_iter()
-        .rev()
-        .enumerate()
-        .map(|(i, v)| (i as f64, *v))
-        .collect()
+use ratatui::layout::{Constraint, Direction, Layout, Margin, Rect};
+use ratatui::prelude::{Color, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::{Axis, Block, Dataset, GraphType};
+use crate::metrics::historical_metric::{Buckets, HistoricalMetric, Sampled};
+use std::time::{Duration, Instant};
+
+// ── Time axis ────────────────────────────────────────────────────────────────
+
+/// A sample more than this many times further from its predecessor than the
+/// neighbouring samples are from each other starts a new line segment.
+const GAP_FACTOR: f64 = 3.0;
+
+/// The stretch of time every chart shows, so all panels stay aligned.
+/// X coordinates are seconds relative to `end`, so live data sits at 0.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeWindow {
+    /// When the newest sample was taken
+    pub end: Instant,
+    pub span: Duration,
+    /// How far before `end` the right edge sits; zero when following live data
+    pub offset: Duration,
+}
+
+impl TimeWindow {
+    fn x(&self, time: Instant) -> f64 {
+        if time > self.end {
+            (time - self.end).as_secs_f64()
+        } else {
+            -(self.end - time).as_secs_f64()
+        }
+    }
+
+    /// Left and right edges as X coordinates
+    fn bounds(&self) -> [f64; 2] {
+        let right = -self.offset.as_secs_f64();
+        [right - self.span.as_secs_f64(), right]
+    }
+
+    /// X axis spanning the window, labelled in time before `end`.
+    pub fn axis(&self) -> Axis<'static> {
+        Axis::default()
+            .bounds(self.bounds())
+            .style(Style::default().fg(Color::Gray))
+            .labels(self.axis_labels())
+    }
+
+    fn axis_labels(&self) -> [String; 3] {
+        let [left, right] = self.bounds();
+        [format_ago(-left), format_ago(-(left + right) / 2.0), self.edge_label(right)]
+    }
+
+    /// Time at `fraction` of the way across, 0 being the left edge
+    pub fn time_at(&self, fraction: f64) -> Instant {
+        let back = self.offset + self.span.mul_f64(1.0 - fraction.clamp(0.0, 1.0));
+        self.end.checked_sub(back).unwrap_or(self.end)
+    }
+
+    /// How far across `time` sits, 0 being the left edge; `None` outside the window
+    pub fn fraction(&self, time: Instant) -> Option<f64> {
+        let [left, right] = self.bounds();
+        let x = self.x(time);
+        (left..=right).contains(&x).then(|| (x - left) / (right - left))
+    }
+
+    /// `time` relative to `end`, e.g. `-42s`, or `now`
+    pub fn ago(&self, time: Instant) -> String {
+        let secs = -self.x(time).round();
+        if secs <= 0.0 { "now".to_string() } else { format_ago(secs) }
+    }
+
+    /// Where a `Chart` in `area` with a bordered block, these Y labels and
+    /// this window's X axis draws its plot, following ratatui's chart layout.
+    pub fn plot_area<S: AsRef<str>>(&self, area: Rect, y_labels: &[S]) -> Rect {
+        let inner = area.inner(Margin::new(1, 1));
+        let y_width = y_labels.iter().map(|l| l.as_ref().chars().count()).max().unwrap_or(0);
+        let x_width = self.axis_labels()[0].chars().count().saturating_sub(1);
+        let left = (y_width.max(x_width) as u16).min(inner.width / 3) + 1;
+        // The X axis line and its labels take the bottom two rows.
+        Rect {
+            x: inner.x + left,
+            y: inner.y,
+            width: inner.width.saturating_sub(left),
+            height: inner.height.saturating_sub(2),
+        }
+    }
+
+    /// Visible range, e.g. `-7.5m → -5m`
+    pub fn label(&self) -> String {
+        let [left, right] = self.bounds();
+        format!("{} → {}", format_ago(-left), self.edge_label(right))
+    }
+
+    fn edge_label(&self, right: f64) -> String {
+        if self.offset.is_zero() { "now".to_string() } else { format_ago(-right) }
+    }
+}
+
+/// Per-frame settings shared by every chart panel
+#[derive(Debug, Clone, Copy)]
+pub struct ChartView {
+    pub window: TimeWindow,
+    /// Show window statistics along the bottom edge of each chart
+    pub stats: bool,
+    /// Border colour while an alert on this panel is firing
+    pub alert: Option<Color>,
+}
+
+impl ChartView {
+    /// Add a footer summarising `metric` to `block` when stats are on, and
+    /// colour its border while an alert is firing. `label` names the series
+    /// on charts that plot more than one.
+    pub fn stats_footer<'a, T: Sampled>(
+        &self,
+        block: Block<'a>,
+        label: Option<&str>,
+        metric: &HistoricalMetric<T>,
+        format: impl Fn(f64) -> String,
+    ) -> Block<'a> {
+        let block = match self.alert {
+            Some(color) => block.border_style(Style::default().fg(color)),
+            None => block,
+        };
+        if !self.stats {
+            return block;
+        }
+        let stats = metric.stats();
+        let prefix = label.map(|l| format!("{l}: ")).unwrap_or_default();
+        block.title_bottom(Line::styled(
+            format!(
+                " {prefix}min {} avg {} p95 {} p99 {} max {} σ {} ",
+                format(stats.min),
+                format(stats.mean),
+                format(stats.p95),
+                format(stats.p99),
+                format(stats.max),
+                format(stats.stddev),
+            ),
+            Style::default().fg(Color::DarkGray),
+        ))
+    }
+}
+
+/// `-45s`, `-7.5m`, `-12h`
+fn format_ago(secs: f64) -> String {
+    let (value, unit) = if secs < 120.0 {
+        (secs, "s")
+    } else if secs < 7200.0 {
+        (secs / 60.0, "m")
+    } else {
+        (secs / 3600.0, "h")
+    };
+    if value.fract() == 0.0 {
+        format!("-{value:.0}{unit}")
+    } else {
+        format!("-{value:.1}{unit}")
+    }
+}
+
+/// Points plotted in one chart column
+struct Column {
+    index: i64,
+    x: f64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    count: usize,
+}
+
+/// Unbroken stretches of chart points
+type Runs = Vec<Vec<(f64, f64)>>;
+
+/// A metric's history laid out on a `TimeWindow`, at most one point per
+/// chart column. Where sampling stopped (suspend, a paused recording) the
+/// line is split rather than drawn across the gap.
+pub struct Series {
+    /// Average per column, one run per unbroken stretch of samples
+    lines: Runs,
+    /// Minimum and maximum per column, when some column covers more than one sample
+    band: Option<(Runs, Runs)>,
+}
+
+impl Series {
+    pub fn new<T: Sampled>(metric: &HistoricalMetric<T>, window: TimeWindow, width: usize) -> Self {
+        let since = window.end.checked_sub(window.offset + window.span);
+        let Buckets { values, range, times } = metric.buckets(since);
+        let xs: Vec<f64> = times.iter().map(|t| window.x(*t)).collect();
+        let [left, right] = window.bounds();
+        let column_width = window.span.as_secs_f64() / width.max(1) as f64;
+
+        let mut runs: Vec<Vec<Column>> = vec![];
+        for (i, &x) in xs.iter().enumerate() {
+            if x < left || x > right {
+                continue;
+            }
+            let (value, low, high) = match range {
+                Some((min, max)) => (values[i].to_f64(), min[i].to_f64(), max[i].to_f64()),
+                None => (values[i].to_f64(), values[i].to_f64(), values[i].to_f64()),
+            };
+            let index = ((x - left) / column_width) as i64;
+
+            if runs.is_empty() || is_gap(&xs, i) {
+                runs.push(vec![]);
+            }
+            let run = runs.last_mut().expect("a run was started above");
+            match run.last_mut() {
+                Some(column) if column.index == index => {
+                    column.x = x;
+                    column.sum += value;
+                    column.min = column.min.min(low);
+                    column.max = column.max.max(high);
+                    column.count += 1;
+                }
+                _ => run.push(Column { index, x, sum: value, min: low, max: high, count: 1 }),
+            }
+        }
+
+        let points = |f: fn(&Column) -> f64| -> Runs {
+            runs.iter().map(|run| run.iter().map(|c| (c.x, f(c))).collect()).collect()
+        };
+        let merged = range.is_some() || runs.iter().flatten().any(|c| c.count > 1);
+        Self {
+            lines: points(|c| c.sum / c.count as f64),
+            band: merged.then(|| (points(|c| c.min), points(|c| c.max))),
+        }
+    }
+
+    /// Multiply every value by `factor`, e.g. to change units
+    pub fn scaled(mut self, factor: f64) -> Self {
+        let scale = |runs: &mut Runs| runs.iter_mut().flatten().for_each(|p| p.1 *= factor);
+        scale(&mut self.lines);
+        if let Some((low, high)) = &mut self.band {
+            scale(low);
+            scale(high);
+        }
+        self
+    }
+
+    /// Highest value in each column, spikes included
+    pub fn highs(&self) -> impl Iterator<Item = &(f64, f64)> {
+        self.band.as_ref().map_or(&self.lines, |(_, high)| high).iter().flatten()
+    }
+
+    /// Highest value anywhere in the window
+    pub fn peak(&self) -> f64 {
+        self.highs().map(|p| p.1).fold(0.0, f64::max)
+    }
+
+    /// The line itself, one dataset per unbroken run. Only the first carries
+    /// `name`, so the legend lists each series once.
+    pub fn lines(&self, name: Option<String>, style: Style) -> Vec<Dataset<'_>> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, run)| {
+                let dataset = Dataset::default()
+                    .marker(Marker::Braille)
+                    .style(style)
+                    .graph_type(GraphType::Line)
+                    .data(run);
+                match (&name, i) {
+                    (Some(name), 0) => dataset.name(name.clone()),
+                    _ => dataset,
+                }
+            })
+            .collect()
+    }
+
+    /// Faint min and max edges behind the line; put them first so the line
+    /// is drawn on top. Empty when every point is a single sample.
+    pub fn band(&self) -> Vec<Dataset<'_>> {
+        let Some((low, high)) = &self.band else { return vec![] };
+        low.iter()
+            .chain(high)
+            .map(|run| Dataset::default()
+                .marker(Marker::Braille)
+                .style(Style::default().fg(Color::DarkGray))
+                .graph_type(GraphType::Line)
+                .data(run))
+            .collect()
+    }
+}
+
+/// Whether sample `i` comes after a gap, judged against the spacing of its
+/// neighbours so a change of refresh interval isn't mistaken for one.
+fn is_gap(xs: &[f64], i: usize) -> bool {
+    if i == 0 {
+        return false;
+    }
+    let step = xs[i] - xs[i - 1];
+    let before = (i >= 2).then(|| xs[i - 1] - xs[i - 2]);
+    let after = xs.get(i + 1).map(|next| next - xs[i]);
+    match before.into_iter().chain(after).reduce(f64::min) {
+        Some(usual) if usual > 0.0 => step > GAP_FACTOR * usual,
+        _ => false,
+    }
 }
 
 // ── Color ─────────────────────────────────────────────────────────────────────
@@ -37,10 +315,9 @@ pub fn usage_color(percent: f64) -> Color {
 
 // ── Dynamic Y-axis ────────────────────────────────────────────────────────────
 
-/// Scale the Y-axis to 120 % of the maximum value in `history`, with a
+/// Scale the Y-axis to 120 % of `max`, e.g. `Series::peak`, with a
 /// minimum floor of 1.0 so the axis is never degenerate.
-pub fn dynamic_bound(history: &VecDeque<f64>) -> f64 {
-    let max = history.iter().cloned().fold(0.0f64, f64::max);
+pub fn peak_bound(max: f64) -> f64 {
     (max * 1.2).max(1.0)
 }
 
@@ -55,6 +332,28 @@ pub fn format_rate(val: f64) -> String {
     }
 }
 
+/// Format a count with a metric suffix (`k`, `M`, `G`, ...) so very large
+/// limits such as an unbounded `file-max` stay readable.
+pub fn format_count(val: u64) -> String {
+    const SUFFIXES: [&str; 6] = ["k", "M", "G", "T", "P", "E"];
+    if val < 1000 {
+        return val.to_string();
+    }
+    let mut scaled = val as f64;
+    let mut suffix = "";
+    for s in SUFFIXES {
+        if scaled < 1000.0 {
+            break;
+        }
+        scaled /= 1000.0;
+        suffix = s;
+    }
+    format!("{}{suffix}", format_rate(scaled))
+}
+
+/// Y labels for percentage charts
+pub const PERCENT_LABELS: [&str; 3] = ["0%", "50%", "100%"];
+
 /// Generate `[min, mid, max]` label strings for a dynamic Y-axis.
 pub fn rate_axis_labels(bound: f64) -> [String; 3] {
     [format_rate(0.0), format_rate(bound / 2.0), format_rate(bound)]
@@ -1,18 +1,15 @@
 use ratatui::Frame;
 use ratatui::layout::Rect;
 use ratatui::prelude::{Color, Style, Stylize};
-use ratatui::symbols::Marker;
-use ratatui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph};
-use crate::ui::chart_utils::{chart_areas, trim_to_width, usage_color};
-
-pub fn draw_chart(frame: &mut Frame, area: Rect, cpu: &crate::metrics::cpu::CpuMetrics) {
-    use crate::metrics::SystemMetrics;
+use ratatui::widgets::{Axis, Block, Borders, Chart, Paragraph};
+use crate::ui::chart_utils::{chart_areas, usage_color, PERCENT_LABELS, Series, ChartView};
 
+pub fn draw_chart(frame: &mut Frame, area: Rect, cpu: &crate::metrics::cpu::CpuMetrics, view: ChartView) -> Vec<Rect> {
     let (title_area, chart_area) = chart_areas(area);
 
     let usage = cpu.usage_percent();
     let cpu_name = cpu.name.as_deref().unwrap_or("Unknown");
-    let load = SystemMetrics::load_average();
+    let load = cpu.load_average();
 
     let temp_str = cpu.temperature()
         .map(|t| format!("| {t:.0}°C "))
@@ -28,21 +25,24 @@ pub fn draw_chart(frame: &mut Frame, area: Rect, cpu: &crate::metrics::cpu::CpuM
     );
 
     let width = chart_area.width as usize;
-    let trimmed = trim_to_width(cpu.usage_history(), width);
-
-    let chart = Chart::new(vec![Dataset::default()
-        .marker(Marker::Braille)
-        .style(Style::default().fg(usage_color(usage)))
-        .graph_type(GraphType::Line)
-        .data(&trimmed)])
-    .block(Block::default().title("Usage (%)").borders(Borders::ALL))
-    .x_axis(Axis::default()
-        .bounds([0.0, trimmed.len().max(1) as f64])
-        .style(Style::default().fg(Color::Gray)))
+    let series = Series::new(cpu.usage_history(), view.window, width);
+
+    let mut datasets = series.band();
+    datasets.extend(series.lines(None, Style::default().fg(usage_color(usage))));
+
+    let chart = Chart::new(datasets)
+    .block(view.stats_footer(
+        Block::default().title("Usage (%)").borders(Borders::ALL),
+        None,
+        cpu.usage_history(),
+        |v| format!("{v:.0}%"),
+    ))
+    .x_axis(view.window.axis())
     .y_axis(Axis::default()
         .bounds([0.0, 100.0])
         .style(Style::default().fg(Color::Gray))
-        .labels(["0%", "50%", "100%"]));
+        .labels(PERCENT_LABELS));
 
     frame.render_widget(chart, chart_area);
+    vec![view.window.plot_area(chart_area, &PERCENT_LABELS)]
 }
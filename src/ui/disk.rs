@@ -1,12 +1,11 @@
 use ratatui::Frame;
 use ratatui::layout::Rect;
 use ratatui::prelude::{Color, Style, Stylize};
-use ratatui::symbols::Marker;
-use ratatui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph};
+use ratatui::widgets::{Axis, Block, Borders, Chart, Paragraph};
 use crate::metrics::disk::DiskMetrics;
-use crate::ui::chart_utils::{chart_areas, dynamic_bound, rate_axis_labels, split_horizontal, trim_to_width};
+use crate::ui::chart_utils::{chart_areas, format_rate, peak_bound, rate_axis_labels, split_horizontal, Series, ChartView};
 
-pub fn draw_chart(frame: &mut Frame, area: Rect, disk: &DiskMetrics) {
+pub fn draw_chart(frame: &mut Frame, area: Rect, disk: &DiskMetrics, view: ChartView) -> Vec<Rect> {
     let (title_area, chart_area) = chart_areas(area);
 
     frame.render_widget(
@@ -18,45 +17,53 @@ pub fn draw_chart(frame: &mut Frame, area: Rect, disk: &DiskMetrics) {
     );
 
     let width = chart_area.width as usize;
-    let read_history = disk.read_history();
-    let write_history = disk.write_history();
-
-    let read_trimmed = trim_to_width(read_history, width);
-    let write_trimmed = trim_to_width(write_history, width);
-
-    let read_chart = Chart::new(vec![Dataset::default()
-        .marker(Marker::Braille)
-        .style(Style::default().fg(Color::Green))
-        .graph_type(GraphType::Line)
-        .data(&read_trimmed)])
-    .block(Block::default()
-        .title(format!("Read ({:.2} MB/s)", disk.read_rate()))
-        .borders(Borders::ALL))
-    .x_axis(Axis::default()
-        .bounds([0.0, read_trimmed.len().max(1) as f64])
-        .style(Style::default().fg(Color::Gray)))
+    let read_series = Series::new(disk.read_history(), view.window, width);
+    let write_series = Series::new(disk.write_history(), view.window, width);
+    // Scale to the peaks so downsampled spikes stay on the chart.
+    let read_bound = peak_bound(read_series.peak());
+    let write_bound = peak_bound(write_series.peak());
+
+    let mut read_datasets = read_series.band();
+    read_datasets.extend(read_series.lines(None, Style::default().fg(Color::Green)));
+
+    let read_chart = Chart::new(read_datasets)
+    .block(view.stats_footer(
+        Block::default()
+            .title(format!("Read ({:.2} MB/s)", disk.read_rate()))
+            .borders(Borders::ALL),
+        None,
+        disk.read_history(),
+        format_rate,
+    ))
+    .x_axis(view.window.axis())
     .y_axis(Axis::default()
-        .bounds([0.0, dynamic_bound(read_history)])
+        .bounds([0.0, read_bound])
         .style(Style::default().fg(Color::Gray))
-        .labels(rate_axis_labels(dynamic_bound(read_history))));
-
-    let write_chart = Chart::new(vec![Dataset::default()
-        .marker(Marker::Braille)
-        .style(Style::default().fg(Color::Red))
-        .graph_type(GraphType::Line)
-        .data(&write_trimmed)])
-    .block(Block::default()
-        .title(format!("Write ({:.2} MB/s)", disk.write_rate()))
-        .borders(Borders::ALL))
-    .x_axis(Axis::default()
-        .bounds([0.0, write_trimmed.len().max(1) as f64])
-        .style(Style::default().fg(Color::Gray)))
+        .labels(rate_axis_labels(read_bound)));
+
+    let mut write_datasets = write_series.band();
+    write_datasets.extend(write_series.lines(None, Style::default().fg(Color::Red)));
+
+    let write_chart = Chart::new(write_datasets)
+    .block(view.stats_footer(
+        Block::default()
+            .title(format!("Write ({:.2} MB/s)", disk.write_rate()))
+            .borders(Borders::ALL),
+        None,
+        disk.write_history(),
+        format_rate,
+    ))
+    .x_axis(view.window.axis())
     .y_axis(Axis::default()
-        .bounds([0.0, dynamic_bound(write_history)])
+        .bounds([0.0, write_bound])
         .style(Style::default().fg(Color::Gray))
-        .labels(rate_axis_labels(dynamic_bound(write_history))));
+        .labels(rate_axis_labels(write_bound)));
 
     let (left, right) = split_horizontal(chart_area);
     frame.render_widget(read_chart, left);
     frame.render_widget(write_chart, right);
+    vec![
+        view.window.plot_area(left, &rate_axis_labels(read_bound)),
+        view.window.plot_area(right, &rate_axis_labels(write_bound)),
+    ]
 }
@@ -1,11 +1,10 @@
 use ratatui::Frame;
 use ratatui::layout::Rect;
 use ratatui::prelude::{Color, Style, Stylize};
-use ratatui::symbols::Marker;
-use ratatui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph};
-use crate::ui::chart_utils::{chart_areas, split_horizontal, trim_to_width, usage_color};
+use ratatui::widgets::{Axis, Block, Borders, Chart, Paragraph};
+use crate::ui::chart_utils::{chart_areas, split_horizontal, usage_color, Series, ChartView, PERCENT_LABELS};
 
-pub fn draw_chart(frame: &mut Frame, area: Rect, gpu: &crate::metrics::gpu::GpuMetrics) {
+pub fn draw_chart(frame: &mut Frame, area: Rect, gpu: &crate::metrics::gpu::GpuMetrics, view: ChartView) -> Vec<Rect> {
     let (title_area, chart_area) = chart_areas(area);
 
     let gpu_name = gpu.name.clone().unwrap_or_else(|| "Unknown".to_string());
@@ -21,38 +20,40 @@ pub fn draw_chart(frame: &mut Frame, area: Rect, gpu: &crate::metrics::gpu::GpuM
     let memory_usage = gpu.memory_percent();
     let width = chart_area.width as usize;
 
-    let usage_trimmed = trim_to_width(gpu.usage_history(), width);
-    let memory_trimmed = trim_to_width(gpu.memory_history(), width);
-
-    let usage_chart = Chart::new(vec![Dataset::default()
-        .marker(Marker::Braille)
-        .style(Style::default().fg(usage_color(usage)))
-        .graph_type(GraphType::Line)
-        .data(&usage_trimmed)])
-    .block(Block::default().title(format!("GPU Usage ({:.0}%)", usage)).borders(Borders::ALL))
-    .x_axis(Axis::default()
-        .bounds([0.0, usage_trimmed.len().max(1) as f64])
-        .style(Style::default().fg(Color::Gray)))
+    let usage_series = Series::new(gpu.usage_history(), view.window, width);
+    let memory_series = Series::new(gpu.memory_history(), view.window, width);
+
+    let mut usage_datasets = usage_series.band();
+    usage_datasets.extend(usage_series.lines(None, Style::default().fg(usage_color(usage))));
+
+    let usage_chart = Chart::new(usage_datasets)
+    .block(view.stats_footer(
+        Block::default().title(format!("GPU Usage ({:.0}%)", usage)).borders(Borders::ALL),
+        None,
+        gpu.usage_history(),
+        |v| format!("{v:.0}%"),
+    ))
+    .x_axis(view.window.axis())
     .y_axis(Axis::default()
         .bounds([0.0, 100.0])
         .style(Style::default().fg(Color::Gray))
-        .labels(["0%", "50%", "100%"]));
-
-    let memory_chart = Chart::new(vec![Dataset::default()
-        .marker(Marker::Braille)
-        .style(Style::default().fg(usage_color(memory_usage)))
-        .graph_type(GraphType::Line)
-        .data(&memory_trimmed)])
-    .block(Block::default().title(format!("Memory Usage ({:.0}%)", memory_usage)).borders(Borders::ALL))
-    .x_axis(Axis::default()
-        .bounds([0.0, memory_trimmed.len().max(1) as f64])
-        .style(Style::default().fg(Color::Gray)))
+        .labels(PERCENT_LABELS));
+
+    let memory_chart = Chart::new(memory_series.lines(None, Style::default().fg(usage_color(memory_usage))))
+    .block(view.stats_footer(
+        Block::default().title(format!("Memory Usage ({:.0}%)", memory_usage)).borders(Borders::ALL),
+        None,
+        gpu.memory_history(),
+        |v| format!("{v:.0}%"),
+    ))
+    .x_axis(view.window.axis())
     .y_axis(Axis::default()
         .bounds([0.0, 100.0])
         .style(Style::default().fg(Color::Gray))
-        .labels(["0%", "50%", "100%"]));
+        .labels(PERCENT_LABELS));
 
     let (left, right) = split_horizontal(chart_area);
     frame.render_widget(usage_chart, left);
     frame.render_widget(memory_chart, right);
+    vec![view.window.plot_area(left, &PERCENT_LABELS), view.window.plot_area(right, &PERCENT_LABELS)]
 }
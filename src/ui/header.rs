@@ -0,0 +1,71 @@
+//! One-line host overview shown in the outer border title.
+
+use crate::metrics::host::HostMetrics;
+
+/// Default header template. Placeholders are replaced by [`expand`].
+pub const DEFAULT_HEADER_FORMAT: &str =
+    "{host} | {os} | kernel {kernel} | up {uptime} | {users} users | {procs} procs, {threads} threads | {cores} cores";
+
+/// Expand the `{placeholder}` tokens in `template` from `host`.
+///
+/// Supported: `{host}`, `{os}`, `{kernel}`, `{uptime}`, `{boot}`, `{procs}`,
+/// `{threads}`, `{users}`, `{cores}`. Unknown tokens are left untouched.
+pub fn expand(template: &str, host: &HostMetrics) -> String {
+    let unknown = || "?".to_string();
+    template
+        .replace("{host}", &host.host_name.clone().unwrap_or_else(unknown))
+        .replace("{os}", &host.os_version.clone().unwrap_or_else(unknown))
+        .replace("{kernel}", &host.kernel_version.clone().unwrap_or_else(unknown))
+        .replace("{uptime}", &format_uptime(host.uptime()))
+        .replace("{boot}", &format_utc(host.boot_time))
+        .replace("{procs}", &host.process_count().to_string())
+        .replace("{threads}", &host.thread_count().to_string())
+        .replace("{users}", &host.user_count().map_or_else(unknown, |n| n.to_string()))
+        .replace("{cores}", &host.core_count().to_string())
+}
+
+/// Format a duration in seconds as `3d 4h 12m`, dropping leading zero units.
+fn format_uptime(secs: u64) -> String {
+    let days = secs / 86_400;
+    let hours = (secs % 86_400) / 3_600;
+    let mins = (secs % 3_600) / 60;
+    if days > 0 {
+        format!("{days}d {hours}h {mins}m")
+    } else if hours > 0 {
+        format!("{hours}h {mins}m")
+    } else {
+        format!("{mins}m")
+    }
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM UTC`.
+fn format_utc(epoch_secs: u64) -> String {
+    let (year, month, day) = civil_date(epoch_secs);
+    let hour = (epoch_secs % 86_400) / 3_600;
+    let min = (epoch_secs % 3_600) / 60;
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{min:02} UTC")
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM:SS UTC`.
+pub fn format_utc_seconds(epoch_secs: u64) -> String {
+    let (year, month, day) = civil_date(epoch_secs);
+    let hour = (epoch_secs % 86_400) / 3_600;
+    let min = (epoch_secs % 3_600) / 60;
+    let sec = epoch_secs % 60;
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{min:02}:{sec:02} UTC")
+}
+
+/// `(year, month, day)` of a Unix timestamp.
+fn civil_date(epoch_secs: u64) -> (i64, i64, i64) {
+    // Civil-from-days (Howard Hinnant); avoids pulling in a date crate for one label.
+    let days = (epoch_secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,74 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::prelude::{Color, Style, Stylize};
+use ratatui::widgets::{Axis, Block, Borders, Chart, Paragraph, Row, Table};
+use crate::metrics::kernel::KernelMetrics;
+use crate::ui::chart_utils::{chart_areas, format_rate, peak_bound, rate_axis_labels, split_horizontal, Series, ChartView};
+
+/// Number of IRQ sources listed in the table.
+const TOP_IRQS: usize = 6;
+
+pub fn draw_chart(frame: &mut Frame, area: Rect, kernel: &KernelMetrics, view: ChartView) -> Vec<Rect> {
+    let (title_area, chart_area) = chart_areas(area);
+
+    let title = format!(
+        "⚙️ Kernel | Forks: {}/s | Running: {:.0} | Blocked: {:.0}",
+        format_rate(kernel.fork_rate()),
+        kernel.procs_running(),
+        kernel.procs_blocked(),
+    );
+    frame.render_widget(
+        Paragraph::new(ratatui::text::Span::styled(title, Style::default().fg(Color::White).bold())),
+        title_area,
+    );
+
+    let width = chart_area.width as usize;
+    let ctxt = Series::new(kernel.context_switch_history(), view.window, width);
+    let intr = Series::new(kernel.interrupt_history(), view.window, width);
+
+    // Both series share one axis; scale to whichever peaks higher.
+    let bound = peak_bound(ctxt.peak().max(intr.peak()));
+
+    let mut datasets = ctxt.lines(Some("ctxt".into()), Style::default().fg(Color::Cyan));
+    datasets.extend(intr.lines(Some("intr".into()), Style::default().fg(Color::Yellow)));
+
+    let chart = Chart::new(datasets)
+    .block(view.stats_footer(
+        Block::default()
+            .title(format!(
+                "Ctx switches ({}/s) | Interrupts ({}/s)",
+                format_rate(kernel.context_switch_rate()),
+                format_rate(kernel.interrupt_rate()),
+            ))
+            .borders(Borders::ALL),
+        Some("ctxt"),
+        kernel.context_switch_history(),
+        format_rate,
+    ))
+    .x_axis(view.window.axis())
+    .y_axis(Axis::default()
+        .bounds([0.0, bound])
+        .style(Style::default().fg(Color::Gray))
+        .labels(rate_axis_labels(bound)));
+
+    let rows = kernel.top_irqs(TOP_IRQS).iter().map(|irq| {
+        let cpu = irq.busiest_cpu()
+            .map(|(cpu, rate)| format!("CPU{cpu} ({})", format_rate(rate)))
+            .unwrap_or_default();
+        Row::new(vec![irq.irq.clone(), irq.device.clone(), format_rate(irq.total), cpu])
+    });
+
+    let table = Table::new(rows, [
+        Constraint::Length(5),
+        Constraint::Min(10),
+        Constraint::Length(8),
+        Constraint::Length(16),
+    ])
+    .header(Row::new(vec!["IRQ", "Device", "/s", "Busiest CPU"]).style(Style::default().fg(Color::Gray).bold()))
+    .block(Block::default().title("Top IRQs").borders(Borders::ALL));
+
+    let (left, right) = split_horizontal(chart_area);
+    frame.render_widget(chart, left);
+    frame.render_widget(table, right);
+    vec![view.window.plot_area(left, &rate_axis_labels(bound))]
+}
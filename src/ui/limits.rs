@@ -0,0 +1,97 @@
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::prelude::{Color, Style, Stylize};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph, Sparkline};
+use crate::metrics::limits::{Limit, LimitKind, LimitsMetrics};
+use crate::ui::chart_utils::{chart_areas, format_count, usage_color};
+
+/// Colour for a limit's gauge. Caches are never alarming; reserves such as
+/// entropy warn when they run low rather than high.
+pub fn limit_color(limit: &Limit) -> Color {
+    match limit.kind {
+        LimitKind::Capacity => usage_color(limit.percent()),
+        LimitKind::Cache => Color::Cyan,
+        LimitKind::Reserve => usage_color(100.0 - limit.percent()),
+    }
+}
+
+/// `alert` colours the border while an alert on a limit is firing.
+pub fn draw_chart(frame: &mut Frame, area: Rect, limits: &LimitsMetrics, alert: Option<Color>) {
+    let (title_area, chart_area) = chart_areas(area);
+
+    frame.render_widget(
+        Paragraph::new(ratatui::text::Span::styled(
+            "🔒 Limits",
+            Style::default().fg(Color::White).bold(),
+        )),
+        title_area,
+    );
+
+    let mut block = Block::default().title("Used / Max").borders(Borders::ALL);
+    if let Some(color) = alert {
+        block = block.border_style(Style::default().fg(color));
+    }
+    let inner = block.inner(chart_area);
+    frame.render_widget(block, chart_area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1); limits.limits().len()])
+        .split(inner);
+
+    const LABEL_W: u16 = 11;
+    const VALUE_W: u16 = 24;
+
+    for (limit, &row) in limits.limits().iter().zip(rows.iter()) {
+        let color = limit_color(limit);
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(LABEL_W),
+                Constraint::Percentage(50),
+                Constraint::Length(VALUE_W),
+                Constraint::Min(0),
+            ])
+            .split(row);
+
+        frame.render_widget(
+            Paragraph::new(format!(" {}", limit.label)).style(Style::default().fg(Color::White).bold()),
+            cols[0],
+        );
+        frame.render_widget(
+            Gauge::default()
+                .ratio((limit.percent() / 100.0).clamp(0.0, 1.0))
+                .label("")
+                .gauge_style(Style::default().fg(color)),
+            cols[1],
+        );
+        frame.render_widget(
+            Paragraph::new(format!(
+                "{} / {} ({:.1}%)",
+                format_count(limit.used),
+                format_count(limit.max),
+                limit.percent(),
+            ))
+            .alignment(Alignment::Right)
+            .style(Style::default().fg(color)),
+            cols[2],
+        );
+
+        // Fill history, newest on the right; tenths of a percent keep small limits visible.
+        let width = cols[3].width.saturating_sub(1) as usize;
+        let history: Vec<u64> = limit.history()
+            .iter()
+            .rev()
+            .take(width)
+            .rev()
+            .map(|p| (p * 10.0) as u64)
+            .collect();
+        frame.render_widget(
+            Sparkline::default()
+                .data(&history)
+                .max(1000)
+                .style(Style::default().fg(color)),
+            Rect { x: cols[3].x + 1, width: cols[3].width.saturating_sub(1), ..cols[3] },
+        );
+    }
+}
@@ -2,10 +2,19 @@ use ratatui::Frame;
 use ratatui::layout::Rect;
 use ratatui::prelude::{Color, Style, Stylize};
 use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph};
-use crate::ui::chart_utils::{chart_areas, trim_to_width, usage_color};
+use crate::metrics::cache::CacheMetrics;
+use crate::metrics::paging::PagingMetrics;
+use crate::ui::chart_utils::{chart_areas, format_rate, peak_bound, rate_axis_labels, split_horizontal, usage_color, Series, PERCENT_LABELS, ChartView};
 
-pub fn draw_chart(frame: &mut Frame, area: Rect, memory: &crate::metrics::memory::MemoryMetrics) {
+pub fn draw_chart(
+    frame: &mut Frame,
+    area: Rect,
+    memory: &crate::metrics::memory::MemoryMetrics,
+    cache: Option<&CacheMetrics>,
+    view: ChartView,
+) -> Vec<Rect> {
     let (title_area, chart_area) = chart_areas(area);
 
     let used_gb  = memory.used_bytes() as f64 / 1024.0f64.powi(3);
@@ -23,51 +32,136 @@ pub fn draw_chart(frame: &mut Frame, area: Rect, memory: &crate::metrics::memory
         format!("🗃️ Memory ({:.1} / {:.1} GB)", used_gb, total_gb)
     };
 
-    frame.render_widget(
-        Paragraph::new(ratatui::text::Span::styled(title, Style::default().fg(Color::White).bold())),
-        title_area,
-    );
+    let mut title_spans = vec![Span::styled(title, Style::default().fg(Color::White).bold())];
+
+    // "Used" includes the ZFS ARC and zram's backing RAM; call them out so the
+    // number isn't mistaken for application memory.
+    if let Some(cache) = cache {
+        let mut held = vec![];
+        if let Some(arc) = cache.arc() {
+            held.push(format!("ARC {:.1} GB", arc.size() / 1024.0f64.powi(3)));
+        }
+        let zram_used: u64 = cache.zram().iter().map(|d| d.mem_used_bytes).sum();
+        if zram_used > 0 {
+            held.push(format!("zram {:.1} GB", zram_used as f64 / 1024.0f64.powi(3)));
+        }
+        if !held.is_empty() {
+            title_spans.push(Span::styled(
+                format!(" | incl. {}", held.join(", ")),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+    }
+    let oom_kills = memory.paging().map_or(0, |p| p.total_oom_kills());
+    if oom_kills > 0 {
+        title_spans.push(Span::raw(" "));
+        title_spans.push(Span::styled(
+            format!(" ⚠ OOM KILLS: {oom_kills} "),
+            Style::default().fg(Color::White).bg(Color::Red).bold(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(title_spans)), title_area);
 
     let width = chart_area.width as usize;
-    let trimmed = trim_to_width(memory.used_percent_history(), width);
+    let ram_series = Series::new(memory.used_percent_history(), view.window, width);
 
-    let mut datasets = vec![
-        Dataset::default()
-            .name("RAM")
-            .marker(Marker::Braille)
-            .style(Style::default().fg(usage_color(usage)))
-            .graph_type(GraphType::Line)
-            .data(&trimmed),
-    ];
+    let mut datasets = ram_series.band();
+    datasets.extend(ram_series.lines(Some("RAM".into()), Style::default().fg(usage_color(usage))));
 
     // Build swap dataset only when swap is configured on this machine.
-    let swap_trimmed;
-    if memory.total_swap > 0 {
-        let swap_pct_history: std::collections::VecDeque<f64> = memory
-            .swap_history()
-            .iter()
-            .map(|&b| (b as f64 / memory.total_swap as f64) * 100.0)
+    let swap_series = (memory.total_swap > 0).then(|| {
+        Series::new(memory.swap_history(), view.window, width).scaled(100.0 / memory.total_swap as f64)
+    });
+    if let Some(series) = &swap_series {
+        datasets.extend(series.lines(Some("Swap".into()), Style::default().fg(Color::Magenta)));
+    }
+
+    // Full-height red bars wherever the kernel OOM-killed a process.
+    let oom_markers: Vec<(f64, f64)>;
+    if let Some(paging) = memory.paging() {
+        oom_markers = Series::new(paging.oom_kill_history(), view.window, width)
+            .highs()
+            .filter(|(_, kills)| *kills > 0.0)
+            .map(|(x, _)| (*x, 100.0))
             .collect();
-        swap_trimmed = trim_to_width(&swap_pct_history, width);
         datasets.push(
             Dataset::default()
-                .name("Swap")
-                .marker(Marker::Braille)
-                .style(Style::default().fg(Color::Magenta))
-                .graph_type(GraphType::Line)
-                .data(&swap_trimmed),
+                .name("OOM")
+                .marker(Marker::Block)
+                .style(Style::default().fg(Color::Red))
+                .graph_type(GraphType::Bar)
+                .data(&oom_markers),
         );
     }
 
     let chart = Chart::new(datasets)
-        .block(Block::default().title("Usage (%)").borders(Borders::ALL))
-        .x_axis(Axis::default()
-            .bounds([0.0, trimmed.len().max(1) as f64])
-            .style(Style::default().fg(Color::Gray)))
+        .block(view.stats_footer(
+            Block::default().title("Usage (%)").borders(Borders::ALL),
+            Some("RAM"),
+            memory.used_percent_history(),
+            |v| format!("{v:.0}%"),
+        ))
+        .x_axis(view.window.axis())
         .y_axis(Axis::default()
             .bounds([0.0, 100.0])
             .style(Style::default().fg(Color::Gray))
-            .labels(["0%", "50%", "100%"]));
+            .labels(PERCENT_LABELS));
+
+    match memory.paging() {
+        Some(paging) => {
+            let (left, right) = split_horizontal(chart_area);
+            frame.render_widget(chart, left);
+            let paging_plot = draw_paging_chart(frame, right, paging, view, width);
+            vec![view.window.plot_area(left, &PERCENT_LABELS), paging_plot]
+        }
+        None => {
+            frame.render_widget(chart, chart_area);
+            vec![view.window.plot_area(chart_area, &PERCENT_LABELS)]
+        }
+    }
+}
+
+/// Swap-in/out and major-fault rates on a shared pages/s axis.
+fn draw_paging_chart(frame: &mut Frame, area: Rect, paging: &PagingMetrics, view: ChartView, width: usize) -> Rect {
+    let swap_in = Series::new(paging.swap_in_history(), view.window, width);
+    let swap_out = Series::new(paging.swap_out_history(), view.window, width);
+    let major = Series::new(paging.major_fault_history(), view.window, width);
+
+    let bound = peak_bound(swap_in.peak().max(swap_out.peak()).max(major.peak()));
+
+    let mut datasets = swap_in.lines(
+        Some(format!("Swap in {}", format_rate(paging.swap_in_rate()))),
+        Style::default().fg(Color::Green),
+    );
+    datasets.extend(swap_out.lines(
+        Some(format!("Swap out {}", format_rate(paging.swap_out_rate()))),
+        Style::default().fg(Color::Red),
+    ));
+    datasets.extend(major.lines(
+        Some(format!("Major faults {}", format_rate(paging.major_fault_rate()))),
+        Style::default().fg(Color::Yellow),
+    ));
+
+    let chart = Chart::new(datasets)
+    .block(view.stats_footer(
+        Block::default()
+            .title(format!(
+                "Paging (pages/s) | Faults {} | Scan {} | Steal {}",
+                format_rate(paging.fault_rate()),
+                format_rate(paging.scan_rate()),
+                format_rate(paging.steal_rate()),
+            ))
+            .borders(Borders::ALL),
+        Some("Major faults"),
+        paging.major_fault_history(),
+        format_rate,
+    ))
+    .x_axis(view.window.axis())
+    .y_axis(Axis::default()
+        .bounds([0.0, bound])
+        .style(Style::default().fg(Color::Gray))
+        .labels(rate_axis_labels(bound)));
 
-    frame.render_widget(chart, chart_area);
+    frame.render_widget(chart, area);
+    view.window.plot_area(area, &rate_axis_labels(bound))
 }
@@ -1,11 +1,19 @@
+pub mod alerts;
+pub mod cache;
 pub mod chart_utils;
+#[allow(clippy::module_inception)]
 pub mod ui;
 pub mod bars;
 pub mod cpu;
 pub mod disk;
 pub mod gpu;
+pub mod header;
+pub mod kernel;
+pub mod limits;
 pub mod memory;
 pub mod network;
+pub mod numa;
+pub mod raid;
 
 pub use ui::Ui;
 pub use ui::UiMode;
@@ -1,12 +1,11 @@
 use ratatui::Frame;
 use ratatui::layout::Rect;
 use ratatui::prelude::{Color, Style, Stylize};
-use ratatui::symbols::Marker;
-use ratatui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph};
+use ratatui::widgets::{Axis, Block, Borders, Chart, Paragraph};
 use crate::metrics::network::NetworkMetrics;
-use crate::ui::chart_utils::{chart_areas, dynamic_bound, rate_axis_labels, split_horizontal, trim_to_width};
+use crate::ui::chart_utils::{chart_areas, format_rate, peak_bound, rate_axis_labels, split_horizontal, Series, ChartView};
 
-pub fn draw_chart(frame: &mut Frame, area: Rect, network: &NetworkMetrics, selected: Option<&str>) {
+pub fn draw_chart(frame: &mut Frame, area: Rect, network: &NetworkMetrics, selected: Option<&str>, view: ChartView) -> Vec<Rect> {
     let (title_area, chart_area) = chart_areas(area);
 
     frame.render_widget(
@@ -18,49 +17,57 @@ pub fn draw_chart(frame: &mut Frame, area: Rect, network: &NetworkMetrics, selec
     );
 
     // Early-return after rendering the title if there is no valid interface.
-    let Some(iface) = selected else { return };
-    let Some((rx_hist, tx_hist)) = network.get_interface_stats(iface) else { return };
+    let Some(iface) = selected else { return vec![] };
+    let Some((rx_hist, tx_hist)) = network.get_interface_stats(iface) else { return vec![] };
 
     let width = chart_area.width as usize;
-    let rx_history = rx_hist.history();
-    let tx_history = tx_hist.history();
+    let rx_series = Series::new(rx_hist, view.window, width);
+    let tx_series = Series::new(tx_hist, view.window, width);
+    // Scale to the peaks so downsampled spikes stay on the chart.
+    let rx_bound = peak_bound(rx_series.peak());
+    let tx_bound = peak_bound(tx_series.peak());
 
-    let rx_trimmed = trim_to_width(rx_history, width);
-    let tx_trimmed = trim_to_width(tx_history, width);
+    let mut rx_datasets = rx_series.band();
+    rx_datasets.extend(rx_series.lines(None, Style::default().fg(Color::Green)));
 
-    let rx_chart = Chart::new(vec![Dataset::default()
-        .marker(Marker::Braille)
-        .style(Style::default().fg(Color::Green))
-        .graph_type(GraphType::Line)
-        .data(&rx_trimmed)])
-    .block(Block::default()
-        .title(format!("↓ RX ({:.2} Mb/s)", rx_hist.current()))
-        .borders(Borders::ALL))
-    .x_axis(Axis::default()
-        .bounds([0.0, rx_trimmed.len().max(1) as f64])
-        .style(Style::default().fg(Color::Gray)))
+    let rx_chart = Chart::new(rx_datasets)
+    .block(view.stats_footer(
+        Block::default()
+            .title(format!("↓ RX ({:.2} Mb/s)", rx_hist.current()))
+            .borders(Borders::ALL),
+        None,
+        rx_hist,
+        format_rate,
+    ))
+    .x_axis(view.window.axis())
     .y_axis(Axis::default()
-        .bounds([0.0, dynamic_bound(rx_history)])
+        .bounds([0.0, rx_bound])
         .style(Style::default().fg(Color::Gray))
-        .labels(rate_axis_labels(dynamic_bound(rx_history))));
+        .labels(rate_axis_labels(rx_bound)));
 
-    let tx_chart = Chart::new(vec![Dataset::default()
-        .marker(Marker::Braille)
-        .style(Style::default().fg(Color::Red))
-        .graph_type(GraphType::Line)
-        .data(&tx_trimmed)])
-    .block(Block::default()
-        .title(format!("↑ TX ({:.2} Mb/s)", tx_hist.current()))
-        .borders(Borders::ALL))
-    .x_axis(Axis::default()
-        .bounds([0.0, tx_trimmed.len().max(1) as f64])
-        .style(Style::default().fg(Color::Gray)))
+    let mut tx_datasets = tx_series.band();
+    tx_datasets.extend(tx_series.lines(None, Style::default().fg(Color::Red)));
+
+    let tx_chart = Chart::new(tx_datasets)
+    .block(view.stats_footer(
+        Block::default()
+            .title(format!("↑ TX ({:.2} Mb/s)", tx_hist.current()))
+            .borders(Borders::ALL),
+        None,
+        tx_hist,
+        format_rate,
+    ))
+    .x_axis(view.window.axis())
     .y_axis(Axis::default()
-        .bounds([0.0, dynamic_bound(tx_history)])
+        .bounds([0.0, tx_bound])
         .style(Style::default().fg(Color::Gray))
-        .labels(rate_axis_labels(dynamic_bound(tx_history))));
+        .labels(rate_axis_labels(tx_bound)));
 
     let (left, right) = split_horizontal(chart_area);
     frame.render_widget(rx_chart, left);
     frame.render_widget(tx_chart, right);
+    vec![
+        view.window.plot_area(left, &rate_axis_labels(rx_bound)),
+        view.window.plot_area(right, &rate_axis_labels(tx_bound)),
+    ]
 }
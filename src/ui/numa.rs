@@ -0,0 +1,108 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::prelude::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Axis, Block, Borders, Cell, Chart, Paragraph, Row, Table};
+use crate::metrics::numa::NumaMetrics;
+use crate::ui::chart_utils::{chart_areas, format_rate, split_horizontal, usage_color, Series, PERCENT_LABELS, ChartView};
+
+/// Line colours assigned to nodes in order.
+const NODE_COLORS: [Color; 6] = [Color::Cyan, Color::Magenta, Color::Yellow, Color::Green, Color::Blue, Color::Red];
+
+/// Eighth-block glyphs used to draw one character per core.
+const CORE_GLYPHS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+pub fn draw_chart(frame: &mut Frame, area: Rect, numa: &NumaMetrics, view: ChartView) -> Vec<Rect> {
+    let (title_area, chart_area) = chart_areas(area);
+
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            format!("🧩 NUMA - {} nodes", numa.node_count()),
+            Style::default().fg(Color::White).bold(),
+        )),
+        title_area,
+    );
+
+    let width = chart_area.width as usize;
+    let series: Vec<Series> = numa.nodes()
+        .iter()
+        .map(|node| Series::new(node.used_percent_history(), view.window, width))
+        .collect();
+
+    let datasets = numa.nodes()
+        .iter()
+        .zip(&series)
+        .flat_map(|(node, series)| series.lines(
+            Some(format!("node{}", node.id)),
+            Style::default().fg(NODE_COLORS[node.id % NODE_COLORS.len()]),
+        ))
+        .collect();
+
+    // Stats follow the fullest node, the one most likely to spill over.
+    let mut block = Block::default().title("Memory used per node (%)").borders(Borders::ALL);
+    if let Some(node) = numa.nodes().iter().max_by(|a, b| a.used_percent().total_cmp(&b.used_percent())) {
+        let label = format!("node{}", node.id);
+        block = view.stats_footer(block, Some(&label), node.used_percent_history(), |v| format!("{v:.0}%"));
+    }
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(view.window.axis())
+        .y_axis(Axis::default()
+            .bounds([0.0, 100.0])
+            .style(Style::default().fg(Color::Gray))
+            .labels(PERCENT_LABELS));
+
+    let rows = numa.nodes().iter().map(|node| {
+        let used_gb = node.mem_used() as f64 / 1024.0f64.powi(3);
+        let total_gb = node.mem_total as f64 / 1024.0f64.powi(3);
+        let cores: Vec<Span> = node.core_usage()
+            .iter()
+            .map(|&pct| {
+                let idx = ((pct / 100.0) * (CORE_GLYPHS.len() - 1) as f64).round() as usize;
+                Span::styled(
+                    CORE_GLYPHS[idx.min(CORE_GLYPHS.len() - 1)].to_string(),
+                    Style::default().fg(usage_color(pct)),
+                )
+            })
+            .collect();
+
+        Row::new(vec![
+            Cell::from(Span::styled(
+                format!("node{}", node.id),
+                Style::default().fg(NODE_COLORS[node.id % NODE_COLORS.len()]),
+            )),
+            Cell::from(Span::styled(
+                format!("{used_gb:.1}/{total_gb:.1} GB"),
+                Style::default().fg(usage_color(node.used_percent())),
+            )),
+            Cell::from(Span::styled(
+                format!("{:.0}%", node.cpu_usage()),
+                Style::default().fg(usage_color(node.cpu_usage())),
+            )),
+            Cell::from(format_rate(node.hit_rate())),
+            Cell::from(Span::styled(
+                format_rate(node.miss_rate()),
+                Style::default().fg(if node.miss_rate() > 0.0 { Color::Yellow } else { Color::Reset }),
+            )),
+            Cell::from(Line::from(cores)),
+        ])
+    });
+
+    let table = Table::new(rows, [
+        Constraint::Length(6),
+        Constraint::Length(14),
+        Constraint::Length(5),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Min(4),
+    ])
+    .header(Row::new(vec!["Node", "Memory", "CPU", "Hit/s", "Miss/s", "Cores"])
+        .style(Style::default().fg(Color::Gray).bold()))
+    .block(Block::default().title("Per node").borders(Borders::ALL));
+
+    let (left, right) = split_horizontal(chart_area);
+    frame.render_widget(chart, left);
+    frame.render_widget(table, right);
+    vec![view.window.plot_area(left, &PERCENT_LABELS)]
+}
@@ -0,0 +1,110 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::prelude::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use crate::metrics::raid::{MdArray, MemberState, RaidMetrics, SyncProgress};
+use crate::ui::chart_utils::chart_areas;
+
+/// Width of the text progress bar in the sync column.
+const SYNC_BAR_W: usize = 12;
+
+/// Colour for an array: red when degraded, yellow while syncing, green otherwise.
+pub fn array_color(array: &MdArray) -> Color {
+    if array.is_degraded() {
+        Color::Red
+    } else if array.sync.is_some() {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+/// `alert` colours the border while an alert on an array is firing.
+pub fn draw_chart(frame: &mut Frame, area: Rect, raid: &RaidMetrics, alert: Option<Color>) {
+    let (title_area, chart_area) = chart_areas(area);
+    let degraded = raid.degraded_count();
+
+    let mut title = vec![Span::styled("💽 Storage Health", Style::default().fg(Color::White).bold())];
+    if degraded > 0 {
+        title.push(Span::raw(" "));
+        title.push(Span::styled(
+            format!(" ⚠ {degraded} DEGRADED "),
+            Style::default().fg(Color::White).bg(Color::Red).bold(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(title)), title_area);
+
+    let rows = raid.arrays().iter().map(|array| {
+        let color = array_color(array);
+        let state = if !array.active {
+            "inactive"
+        } else if array.is_degraded() {
+            "degraded"
+        } else {
+            "clean"
+        };
+        let disks = match (array.working_disks, array.expected_disks) {
+            (Some(w), Some(e)) => format!("{w}/{e}"),
+            _ => "-".into(),
+        };
+        let members: Vec<Span> = array.members
+            .iter()
+            .flat_map(|m| {
+                let (suffix, color) = match m.state {
+                    MemberState::Active => ("", Color::Reset),
+                    MemberState::Faulty => ("(F)", Color::Red),
+                    MemberState::Spare => ("(S)", Color::Gray),
+                    MemberState::WriteMostly => ("(W)", Color::Reset),
+                    MemberState::Replacement => ("(R)", Color::Yellow),
+                };
+                [Span::styled(format!("{}{suffix}", m.name), Style::default().fg(color)), Span::raw(" ")]
+            })
+            .collect();
+
+        let row = Row::new(vec![
+            Cell::from(Span::styled(array.name.clone(), Style::default().fg(color).bold())),
+            Cell::from(array.level.clone().unwrap_or_else(|| "-".into())),
+            Cell::from(Span::styled(state, Style::default().fg(color))),
+            Cell::from(disks),
+            Cell::from(Span::styled(array.status.clone(), Style::default().fg(color))),
+            Cell::from(Line::from(members)),
+            Cell::from(array.sync.as_ref().map(format_sync).unwrap_or_default()),
+        ]);
+        if array.is_degraded() { row.style(Style::default().bg(Color::Rgb(64, 0, 0))) } else { row }
+    });
+
+    let border_color = alert.unwrap_or(if degraded > 0 { Color::Red } else { Color::Reset });
+    let table = Table::new(rows, [
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(9),
+        Constraint::Length(6),
+        Constraint::Length(8),
+        Constraint::Min(16),
+        Constraint::Length(48),
+    ])
+    .header(Row::new(vec!["Array", "Level", "State", "Disks", "Status", "Members", "Sync"])
+        .style(Style::default().fg(Color::Gray).bold()))
+    .block(Block::default()
+        .title("md arrays")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border_color)));
+
+    frame.render_widget(table, chart_area);
+}
+
+/// `recovery [█...........] 8.5% ETA 2h34m @ 188.6 MB/s`
+fn format_sync(sync: &SyncProgress) -> String {
+    let filled = ((sync.percent / 100.0) * SYNC_BAR_W as f64).round() as usize;
+    let bar = format!("{}{}", "█".repeat(filled.min(SYNC_BAR_W)), ".".repeat(SYNC_BAR_W.saturating_sub(filled)));
+    let mut text = format!("{} [{bar}] {:.1}%", sync.action, sync.percent);
+    if let Some(minutes) = sync.eta_minutes {
+        let minutes = minutes.round() as u64;
+        text.push_str(&format!(" ETA {}h{:02}m", minutes / 60, minutes % 60));
+    }
+    if let Some(kib) = sync.speed_kib {
+        text.push_str(&format!(" @ {:.1} MB/s", kib as f64 / 1024.0));
+    }
+    text
+}
@@ -1,11 +1,22 @@
 use crate::metrics::SystemMetrics;
-use crate::ui::{bars, cpu, disk, gpu, memory, network};
+use crate::metrics::alerts::Alerts;
+use crate::metrics::source::{MetricsSource, Playback};
+use crate::ui::{alerts, bars, cache, cpu, disk, gpu, header, kernel, limits, memory, network, numa, raid};
+use crate::ui::chart_utils::{format_rate, ChartView, TimeWindow};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, BorderType};
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, BorderType};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::Frame;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Deferred renderer for one chart panel, laid out once all enabled panels are
+/// known. Returns the areas its plots were drawn in, for the cursor.
+type PanelFn<'a> = Box<dyn FnOnce(&mut Frame, Rect) -> Vec<Rect> + 'a>;
+
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+/// How long a notice stays under the title
+const NOTICE_DURATION: Duration = Duration::from_secs(30);
 
 pub enum UiMode {
     Normal,
@@ -19,20 +30,51 @@ pub struct Ui {
     pub show_gpu: bool,
     pub show_network: bool,
     pub show_disk: bool,
+    pub show_kernel: bool,
+    pub show_limits: bool,
+    pub show_numa: bool,
+    pub show_cache: bool,
+    pub show_raid: bool,
     pub selected_option: usize,
     pub selected_interface: usize,
     pub update_interval_presets: Vec<Duration>,
     pub selected_update_interval_idx: usize,
+    pub history_window_presets: Vec<Duration>,
+    pub selected_history_window_idx: usize,
     pub compact_view: bool,
+    /// Window statistics under each chart
+    pub show_stats: bool,
+    /// Visible span when zoomed in; `None` shows the whole history window
+    pub zoom_span: Option<Duration>,
+    /// Right edge of the charts when panned back; `None` follows live data
+    pub pan_end: Option<Instant>,
+    /// The display is frozen while collection continues
+    pub paused: bool,
+    /// Bell and border flash for a firing alert; cleared by acknowledging it
+    pub alarm: Option<alerts::Alarm>,
+    /// Latest problem worth telling the user about, and when it was raised
+    pub notice: Option<(String, Instant)>,
+    /// Time under the crosshair cursor; `None` when hidden
+    pub cursor: Option<Instant>,
+    pub header_format: String,
+    /// Plot areas and time range of the last chart frame, for mapping mouse positions to times
+    plots: Vec<Rect>,
+    last_window: Option<TimeWindow>,
 }
 
 impl Ui {
     /// Number of toggleable metric panels (one per show_* field).
     /// Increment this when adding a new panel.
-    pub const METRIC_COUNT: usize = 5; // CPU, Memory, GPU, Network, Disk
+    pub const METRIC_COUNT: usize = 10; // CPU, Memory, GPU, Network, Disk, Kernel, Limits, NUMA, Caches, RAID
+
+    /// Menu rows above the metric toggles: update interval, history window.
+    pub const SETTING_COUNT: usize = 2;
+
+    /// Total navigable items in the options menu: settings + metrics.
+    pub const MENU_OPTION_COUNT: usize = Self::METRIC_COUNT + Self::SETTING_COUNT;
 
-    /// Total navigable items in the options menu: update interval + metrics.
-    pub const MENU_OPTION_COUNT: usize = Self::METRIC_COUNT + 1;
+    /// Narrowest span zooming in can reach.
+    const MIN_ZOOM_SPAN: Duration = Duration::from_secs(10);
 
     pub fn new() -> Self {
         Self {
@@ -42,6 +84,11 @@ impl Ui {
             show_gpu: true,
             show_network: true,
             show_disk: true,
+            show_kernel: true,
+            show_limits: true,
+            show_numa: true,
+            show_cache: true,
+            show_raid: true,
             selected_option: 0,
             selected_interface: 0,
             update_interval_presets: vec![
@@ -51,10 +98,119 @@ impl Ui {
                 Duration::from_secs(5),
             ],
             selected_update_interval_idx: 1,
+            history_window_presets: vec![
+                Duration::from_secs(120),
+                Duration::from_secs(15 * 60),
+                Duration::from_secs(3600),
+                Duration::from_secs(24 * 3600),
+            ],
+            selected_history_window_idx: 0,
             compact_view: false,
+            show_stats: false,
+            zoom_span: None,
+            pan_end: None,
+            paused: false,
+            alarm: None,
+            notice: None,
+            cursor: None,
+            header_format: header::DEFAULT_HEADER_FORMAT.to_string(),
+            plots: vec![],
+            last_window: None,
+        }
+    }
+
+    fn history_window(&self) -> Duration {
+        self.history_window_presets[self.selected_history_window_idx]
+    }
+
+    /// Span the charts show: the zoomed span, never wider than the history window.
+    fn visible_span(&self) -> Duration {
+        let window = self.history_window();
+        self.zoom_span.map_or(window, |span| span.min(window))
+    }
+
+    /// Halve or double the visible span, keeping the right edge in place.
+    pub fn zoom(&mut self, zoom_in: bool) {
+        let span = self.visible_span();
+        self.zoom_span = if zoom_in {
+            Some((span / 2).max(Self::MIN_ZOOM_SPAN))
+        } else if span * 2 >= self.history_window() {
+            None
+        } else {
+            Some(span * 2)
+        };
+    }
+
+    /// Move the charts a quarter of the visible span back or forward in time.
+    /// `latest` is when the newest sample was taken; reaching it resumes following live data.
+    pub fn pan(&mut self, latest: Instant, back: bool) {
+        let window = self.time_window(latest);
+        let step = window.span / 4;
+        let right = latest.checked_sub(window.offset).unwrap_or(latest);
+        let target = if back {
+            let oldest = latest.checked_sub(self.history_window() - window.span).unwrap_or(right);
+            right.checked_sub(step).unwrap_or(right).max(oldest)
+        } else {
+            right + step
+        };
+        self.pan_end = (target < latest).then_some(target);
+    }
+
+    /// Follow live data again, keeping the zoom level.
+    pub fn snap_to_live(&mut self) {
+        self.pan_end = None;
+    }
+
+    /// Show the cursor in the middle of the visible range, or hide it.
+    pub fn toggle_cursor(&mut self, latest: Instant) {
+        self.cursor = match self.cursor {
+            Some(_) => None,
+            None => Some(self.time_window(latest).time_at(0.5)),
+        };
+    }
+
+    /// Move the cursor by `fraction` of the visible span, negative being back
+    /// in time, panning along when it leaves the visible range.
+    pub fn move_cursor(&mut self, latest: Instant, fraction: f64) {
+        let Some(cursor) = self.cursor else { return };
+        let window = self.time_window(latest);
+        let step = window.span.mul_f64(fraction.abs());
+        let target = if fraction < 0.0 {
+            let oldest = latest.checked_sub(self.history_window()).unwrap_or(cursor);
+            cursor.checked_sub(step).unwrap_or(cursor).max(oldest)
+        } else {
+            (cursor + step).min(latest)
+        };
+        self.cursor = Some(target);
+
+        if window.fraction(target).is_none() {
+            let end = if target > cursor { target } else { target + window.span };
+            self.pan_end = (end < latest).then_some(end);
+        }
+    }
+
+    /// Put the cursor at the time under a screen cell, if it lies on a plot.
+    /// With `show` unset the cursor only follows the mouse while already visible.
+    pub fn cursor_at(&mut self, column: u16, row: u16, show: bool) {
+        if !show && self.cursor.is_none() {
+            return;
+        }
+        let Some(window) = self.last_window else { return };
+        let position = ratatui::layout::Position { x: column, y: row };
+        if let Some(plot) = self.plots.iter().find(|plot| plot.contains(position)) {
+            let fraction = (column - plot.x) as f64 / plot.width.saturating_sub(1).max(1) as f64;
+            self.cursor = Some(window.time_at(fraction));
         }
     }
 
+    /// Time range shared by every chart. A panned view stays on the same
+    /// stretch of time as new samples arrive, until it would scroll past the oldest one.
+    fn time_window(&self, latest: Instant) -> TimeWindow {
+        let span = self.visible_span();
+        let offset = self.pan_end.map_or(Duration::ZERO, |end| latest.saturating_duration_since(end));
+        TimeWindow { end: latest, span, offset: offset.min(self.history_window() - span) }
+    }
+
     /// Returns the ordered list of metric toggle options for the options menu.
     /// Each entry is `(label, current_enabled_state)`.
     /// Keep in sync with `METRIC_COUNT`.
@@ -65,84 +221,156 @@ impl Ui {
             ("GPU",     self.show_gpu),
             ("Disk",    self.show_disk),
             ("Network", self.show_network),
+            ("Kernel",  self.show_kernel),
+            ("Limits",  self.show_limits),
+            ("NUMA",    self.show_numa),
+            ("Caches",  self.show_cache),
+            ("RAID",    self.show_raid),
         ]
     }
 
-    pub fn draw(&mut self, frame: &mut Frame, system: &SystemMetrics, stats_refreshed: bool) {
+    pub fn draw(&mut self, frame: &mut Frame, source: &dyn MetricsSource, alerts: &Alerts, stats_refreshed: bool) {
         let area = frame.area();
+        let system = source.metrics();
+        let playback = source.playback();
+
+        let instructions = match (&self.mode, &playback) {
+            (UiMode::Normal, None) => "<q>/<Esc>: Quit | <o>: Options | <v>: Toggle view | <s>: Stats | <p>: Pause | <+/->: Zoom | <←→>: Pan | <c>: Cursor".gray().bold(),
+            (UiMode::Normal, Some(_)) => "<q>: Quit | <Space>: Play/Pause | <[ ]>: Speed | <, .>: Step | <PgUp/PgDn>: Seek | <Home/End> | <+/-> <←→>: Zoom/Pan".gray().bold(),
+            (UiMode::OptionsMenu, _) => "<o>/<Esc>: Close Options | <↑↓>: Navigate | <Enter>: Toggle | <Tab>: Cycle Interface".gray().bold(),
+        };
 
-        let instructions = match self.mode {
-            UiMode::Normal => "<q>/<Esc>: Quit | <o>: Options | <v>: Toggle view".gray().bold(),
-            UiMode::OptionsMenu => "<o>/<Esc>: Close Options | <↑↓>: Navigate | <Enter>: Toggle | <Tab>: Cycle Interface".gray().bold(),
+        let mut title = if self.header_format.is_empty() {
+            "System Monitor".to_string()
+        } else {
+            header::expand(&self.header_format, system.host())
         };
+        if let Some(playback) = &playback {
+            title = format!("{} | {title}", replay_status(playback));
+        }
+        if self.alarm.is_some() {
+            title = format!("🔔 <a>: Acknowledge | {title}");
+        }
 
-        let block = Block::bordered()
-            .title(" System Monitor ".bold())
+        let mut block = Block::bordered()
+            .title(format!(" {title} ").bold())
             .title_bottom(instructions)
             .border_set(ratatui::symbols::border::THICK)
             .border_type(BorderType::Rounded);
+        if let Some(color) = self.alarm.and_then(|alarm| alarm.flash_color(Instant::now())) {
+            block = block.border_style(Style::default().fg(color));
+        }
 
         frame.render_widget(block, area);
 
         match self.mode {
-            UiMode::Normal => self.draw_main_ui(frame, area, system, stats_refreshed),
+            UiMode::Normal => self.draw_main_ui(frame, area, system, alerts, stats_refreshed),
             UiMode::OptionsMenu => self.draw_options_menu(frame, area, system),
         }
+        if let Some((notice, raised)) = &self.notice
+            && raised.elapsed() < NOTICE_DURATION
+        {
+            frame.render_widget(
+                Paragraph::new(format!("⚠ {notice}")).style(Style::default().fg(Color::Yellow)),
+                Rect { x: area.x + 2, y: area.y + 1, width: (area.width / 2).saturating_sub(2), height: 1 },
+            );
+        }
     }
 
-    fn draw_main_ui(&self, frame: &mut Frame, area: Rect, system: &SystemMetrics, stats_refreshed: bool) {
-        let inner_area = Rect {
+    fn draw_main_ui(&mut self, frame: &mut Frame, area: Rect, system: &SystemMetrics, alerts: &Alerts, stats_refreshed: bool) {
+        let mut inner_area = Rect {
             x: area.x + 2,
             y: area.y + 2,
             width: area.width - 4,
             height: area.height - 4,
         };
 
+        let list_height = alerts::height(alerts).min(inner_area.height);
+        if list_height > 0 {
+            alerts::draw_list(frame, Rect { height: list_height, ..inner_area }, alerts);
+            inner_area.y += list_height;
+            inner_area.height -= list_height;
+        }
+
         if self.compact_view {
-            bars::draw_bars(
-                frame, inner_area, system,
-                self.show_cpu, self.show_memory, self.show_gpu,
-                self.show_network, self.show_disk,
-                self.selected_interface,
-            );
+            self.plots.clear();
+            bars::draw_bars(frame, inner_area, system, self);
             // Blink dot still shown in compact mode
-            let blink_style = if stats_refreshed {
-                Style::default().fg(Color::Green)
-            } else {
-                Style::default().fg(Color::Black)
-            };
-            frame.render_widget(
-                Paragraph::new("•").style(blink_style).block(Block::default().borders(Borders::NONE)),
-                Rect { x: area.x + area.width - 3, y: area.y, width: 1, height: 1 },
-            );
+            self.draw_blink_dot(frame, area, stats_refreshed);
             return;
         }
 
-        let mut enabled_metrics: Vec<Box<dyn FnOnce(&mut Frame, Rect)>> = vec![];
+        let view = ChartView { window: self.time_window(system.sampled_at()), stats: self.show_stats, alert: None };
+        // Panels take their border colour from firing alerts on their collectors.
+        let alerted = |collectors: &[&str]| ChartView { alert: alerts.firing(collectors).map(alerts::severity_color), ..view };
+
+        // Visible range in the padding row under the title, once zoomed or panned.
+        if self.zoom_span.is_some() || self.pan_end.is_some() {
+            let live = if view.window.offset.is_zero() { "" } else { " | <l>: Live" };
+            let range = format!(
+                "{} of {} | {}{live}",
+                format_window(view.window.span),
+                format_window(self.history_window()),
+                view.window.label(),
+            );
+            frame.render_widget(
+                Paragraph::new(range).alignment(Alignment::Right).style(Style::default().fg(Color::Yellow).bold()),
+                Rect { x: inner_area.x, y: area.y + 1, width: inner_area.width, height: 1 },
+            );
+        }
+        let mut enabled_metrics: Vec<PanelFn> = vec![];
 
         if self.show_cpu {
             let cpu_data = system.cpu();
-            enabled_metrics.push(Box::new(move |f, r| cpu::draw_chart(f, r, cpu_data)));
+            let view = alerted(&["cpu"]);
+            enabled_metrics.push(Box::new(move |f, r| cpu::draw_chart(f, r, cpu_data, view)));
+        }
+        if self.show_numa && let Some(numa_data) = system.numa() {
+            let view = alerted(&["numa"]);
+            enabled_metrics.push(Box::new(move |f, r| numa::draw_chart(f, r, numa_data, view)));
         }
         if self.show_memory {
             let memory_data = system.memory();
-            enabled_metrics.push(Box::new(move |f, r| memory::draw_chart(f, r, memory_data)));
+            let cache_data = system.cache();
+            let view = alerted(&["memory", "paging"]);
+            enabled_metrics.push(Box::new(move |f, r| memory::draw_chart(f, r, memory_data, cache_data, view)));
+        }
+        if self.show_cache && let Some(cache_data) = system.cache() {
+            let view = alerted(&["cache"]);
+            enabled_metrics.push(Box::new(move |f, r| cache::draw_chart(f, r, cache_data, view)));
         }
         if self.show_disk {
             let disk_data = system.disk();
-            enabled_metrics.push(Box::new(move |f, r| disk::draw_chart(f, r, disk_data)));
+            let view = alerted(&["disk"]);
+            enabled_metrics.push(Box::new(move |f, r| disk::draw_chart(f, r, disk_data, view)));
+        }
+        if self.show_raid && let Some(raid_data) = system.raid() {
+            let alert = alerted(&["raid"]).alert;
+            enabled_metrics.push(Box::new(move |f, r| {
+                raid::draw_chart(f, r, raid_data, alert);
+                vec![]
+            }));
         }
         if self.show_network {
             let network_data = system.network();
-            let interfaces = network_data.interface_names();
-            let selected = self.selected_interface.min(interfaces.len().saturating_sub(1));
-            let selected_iface = interfaces.get(selected).cloned();
-            enabled_metrics.push(Box::new(move |f, r| network::draw_chart(f, r, network_data, selected_iface.as_deref())));
-        }
-        if self.show_gpu {
-            if let Some(gpu_data) = system.gpu() {
-                enabled_metrics.push(Box::new(move |f, r| gpu::draw_chart(f, r, gpu_data)));
-            }
+            let selected_iface = self.interface_name(system);
+            let view = alerted(&["network"]);
+            enabled_metrics.push(Box::new(move |f, r| network::draw_chart(f, r, network_data, selected_iface.as_deref(), view)));
+        }
+        if self.show_gpu && let Some(gpu_data) = system.gpu() {
+            let view = alerted(&["gpu"]);
+            enabled_metrics.push(Box::new(move |f, r| gpu::draw_chart(f, r, gpu_data, view)));
+        }
+        if self.show_kernel && let Some(kernel_data) = system.kernel() {
+            let view = alerted(&["kernel"]);
+            enabled_metrics.push(Box::new(move |f, r| kernel::draw_chart(f, r, kernel_data, view)));
+        }
+        if self.show_limits && let Some(limits_data) = system.limits() {
+            let alert = alerted(&["limits"]).alert;
+            enabled_metrics.push(Box::new(move |f, r| {
+                limits::draw_chart(f, r, limits_data, alert);
+                vec![]
+            }));
         }
 
         let constraints = vec![Constraint::Length(12); enabled_metrics.len()];
@@ -152,11 +380,146 @@ impl Ui {
             .split(inner_area)
             .to_vec();
 
-        for (render_fn, chunk) in enabled_metrics.into_iter().zip(chunks) {
-            render_fn(frame, chunk);
+        self.plots = enabled_metrics
+            .into_iter()
+            .zip(chunks)
+            .flat_map(|(render_fn, chunk)| render_fn(frame, chunk))
+            .collect();
+        self.last_window = Some(view.window);
+        self.draw_cursor(frame, inner_area, system, view.window);
+
+        self.draw_blink_dot(frame, area, stats_refreshed);
+    }
+
+    /// Name of the interface the network chart shows
+    fn interface_name(&self, system: &SystemMetrics) -> Option<String> {
+        let interfaces = system.network().interface_names();
+        let selected = self.selected_interface.min(interfaces.len().saturating_sub(1));
+        interfaces.get(selected).cloned()
+    }
+
+    /// Crosshair down every plot at the cursor, with a box listing each
+    /// visible series' value at that moment beside the topmost one.
+    fn draw_cursor(&self, frame: &mut Frame, area: Rect, system: &SystemMetrics, window: TimeWindow) {
+        let Some(cursor) = self.cursor else { return };
+        let Some(fraction) = window.fraction(cursor) else { return };
+
+        let mut anchor = None;
+        for plot in self.plots.iter().filter(|plot| !plot.is_empty()) {
+            let column = plot.x + (fraction * (plot.width - 1) as f64).round() as u16;
+            for row in plot.top()..plot.bottom() {
+                frame.buffer_mut()[(column, row)].set_bg(Color::DarkGray);
+            }
+            anchor.get_or_insert((column, plot.y));
+        }
+        let Some((column, row)) = anchor else { return };
+
+        let values = self.cursor_values(system, cursor);
+        let label_width = values.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+        let lines: Vec<Line> = values
+            .into_iter()
+            .map(|(label, value)| Line::from(vec![
+                Span::styled(format!("{label:<label_width$} "), Style::default().fg(Color::Gray)),
+                Span::styled(value, Style::default().fg(Color::White).bold()),
+            ]))
+            .collect();
+
+        let title = format!(" {} ", window.ago(cursor));
+        let content_width = lines.iter().map(Line::width).max().unwrap_or(0).max(title.len());
+        let width = (content_width as u16 + 2).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.bottom().saturating_sub(row));
+        // Beside the crosshair, flipping to the left near the right edge.
+        let x = if column + 2 + width <= area.right() { column + 2 } else { column.saturating_sub(width + 1).max(area.x) };
+        let tooltip = Rect { x, y: row, width, height };
+
+        frame.render_widget(Clear, tooltip);
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::bordered()
+                    .title(title)
+                    .border_style(Style::default().fg(Color::Yellow)),
+            ),
+            tooltip,
+        );
+    }
+
+    /// `(label, value)` for each series on the visible charts at `at`, in panel order.
+    fn cursor_values(&self, system: &SystemMetrics, at: Instant) -> Vec<(String, String)> {
+        const MISSING: &str = "—";
+        let percent = |v: Option<f64>| v.map_or(MISSING.to_string(), |v| format!("{v:.1}%"));
+        let gb = |v: Option<f64>| v.map_or(MISSING.to_string(), |v| format!("{:.2} GB", v / GB));
+        let rate = |v: Option<f64>, unit: &str| v.map_or(MISSING.to_string(), |v| format!("{} {unit}", format_rate(v)));
+        let mut values = vec![];
+
+        if self.show_cpu {
+            values.push(("CPU".to_string(), percent(system.cpu().usage_history().value_at(at))));
+        }
+        if self.show_numa && let Some(numa) = system.numa() {
+            for node in numa.nodes() {
+                values.push((format!("node{}", node.id), percent(node.used_percent_history().value_at(at))));
+            }
+        }
+        if self.show_memory {
+            let memory = system.memory();
+            let used = memory.used_bytes_history().value_at(at).map(|v| v as f64);
+            let used_percent = memory.used_percent_history().value_at(at);
+            let ram = match (used, used_percent) {
+                (Some(used), Some(pct)) => format!("{} ({pct:.1}%)", gb(Some(used))),
+                _ => MISSING.to_string(),
+            };
+            values.push(("RAM".to_string(), ram));
+            if memory.total_swap > 0 {
+                values.push(("Swap".to_string(), gb(memory.swap_history().value_at(at).map(|v| v as f64))));
+            }
+            if let Some(paging) = memory.paging() {
+                values.push(("Swap in".to_string(), rate(paging.swap_in_history().value_at(at), "pg/s")));
+                values.push(("Swap out".to_string(), rate(paging.swap_out_history().value_at(at), "pg/s")));
+                values.push(("Major faults".to_string(), rate(paging.major_fault_history().value_at(at), "/s")));
+            }
+        }
+        if self.show_cache && let Some(cache) = system.cache() {
+            if let Some(arc) = cache.arc() {
+                values.push(("ARC".to_string(), gb(arc.size_history().value_at(at))));
+            }
+            if !cache.zram().is_empty() {
+                values.push(("zram data".to_string(), gb(cache.zram_orig_history().value_at(at))));
+            }
+        }
+        if self.show_disk {
+            let disk = system.disk();
+            values.push(("Disk read".to_string(), rate(disk.read_history().value_at(at), "MB/s")));
+            values.push(("Disk write".to_string(), rate(disk.write_history().value_at(at), "MB/s")));
         }
+        if self.show_network
+            && let Some(iface) = self.interface_name(system)
+            && let Some((rx, tx)) = system.network().get_interface_stats(&iface)
+        {
+            values.push((format!("{iface} RX"), rate(rx.value_at(at), "Mb/s")));
+            values.push((format!("{iface} TX"), rate(tx.value_at(at), "Mb/s")));
+        }
+        if self.show_gpu && let Some(gpu) = system.gpu() {
+            values.push(("GPU".to_string(), percent(gpu.usage_history().value_at(at))));
+            values.push(("VRAM".to_string(), percent(gpu.memory_history().value_at(at))));
+        }
+        if self.show_kernel && let Some(kernel) = system.kernel() {
+            values.push(("Ctx switches".to_string(), rate(kernel.context_switch_history().value_at(at), "/s")));
+            values.push(("Interrupts".to_string(), rate(kernel.interrupt_history().value_at(at), "/s")));
+        }
+        values
+    }
 
-        // Blink dot: green on data refresh, invisible otherwise.
+    /// Blink dot: green on data refresh, invisible otherwise. While paused
+    /// a badge takes its place, since the screen no longer refreshes.
+    fn draw_blink_dot(&self, frame: &mut Frame, area: Rect, stats_refreshed: bool) {
+        if self.paused {
+            const BADGE: &str = " PAUSED ";
+            let width = BADGE.len() as u16;
+            frame.render_widget(
+                Paragraph::new(BADGE).style(Style::default().fg(Color::Black).bg(Color::Yellow).bold()),
+                Rect { x: (area.x + area.width).saturating_sub(width + 2), y: area.y, width, height: 1 },
+            );
+            return;
+        }
         let blink_style = if stats_refreshed {
             Style::default().fg(Color::Green)
         } else {
@@ -180,12 +543,14 @@ impl Ui {
             format!("{} s", current_interval.as_secs())
         };
         lines.push(Line::raw(format!(" {cursor} Update Interval: {interval_label}")));
+        let cursor = if self.selected_option == 1 { ">" } else { " " };
+        lines.push(Line::raw(format!(" {cursor} History Window:  {}", format_window(self.history_window()))));
         lines.push(Line::raw(""));
         lines.push(Line::from(Span::styled(" Metrics:", Style::default().bold())));
         lines.push(Line::raw(""));
 
         for (i, (label, enabled)) in self.metric_options().iter().enumerate() {
-            let cursor = if self.selected_option == i + 1 { ">" } else { " " };
+            let cursor = if self.selected_option == i + Self::SETTING_COUNT { ">" } else { " " };
             let status = if *enabled { "[x]" } else { "[ ]" };
             lines.push(Line::raw(format!(" {cursor} {status} {label}")));
         }
@@ -210,6 +575,35 @@ impl Ui {
     }
 }
 
+/// `2 min`, `7.5 min`, `1 h`
+fn format_window(window: Duration) -> String {
+    let secs = window.as_secs_f64();
+    let (value, unit) = if secs >= 3600.0 && window.as_secs().is_multiple_of(3600) {
+        (secs / 3600.0, "h")
+    } else if secs >= 60.0 {
+        (secs / 60.0, "min")
+    } else {
+        (secs, "s")
+    };
+    if value.fract() == 0.0 {
+        format!("{value:.0} {unit}")
+    } else {
+        format!("{value:.1} {unit}")
+    }
+}
+
+/// `⏸ REPLAY 4x | 2026-10-18 12:00:03 UTC | 120/3600`
+fn replay_status(playback: &Playback) -> String {
+    format!(
+        "{} REPLAY {}x | {} | {}/{}",
+        if playback.playing { "▶" } else { "⏸" },
+        playback.speed,
+        header::format_utc_seconds(playback.timestamp / 1000),
+        playback.position + 1,
+        playback.len,
+    )
+}
+
 impl Default for Ui {
     fn default() -> Self {
         Self::new()